@@ -0,0 +1,303 @@
+use crate::date::Date;
+use crate::game::{GameMode, COLOR_GRAY};
+use crate::history::{GameRecord, History};
+use crate::stats::Stats;
+use crate::storage::{data_dir, load_key_values, save_key_values};
+use crate::text::n_;
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+/// What has to happen for an achievement to be unlocked
+#[derive(Clone, Copy)]
+pub enum Condition {
+    /// Any one game in the history has to pass the check
+    Game(fn(&GameRecord) -> bool),
+    /// The stats have to pass the check
+    Stats(fn(&Stats) -> bool),
+}
+
+/// Something the player can unlock by playing
+#[derive(Clone, Copy)]
+pub struct Achievement {
+    /// The name the unlock is saved under, which must never change
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub condition: Condition,
+}
+
+/// Every achievement, in the order they're listed
+pub const ACHIEVEMENTS: [Achievement; 13] = [
+    Achievement {
+        id: "first-win",
+        name: n_("First Win"),
+        description: n_("Win a game"),
+        condition: Condition::Game(|record| record.won),
+    },
+    Achievement {
+        id: "hole-in-one",
+        name: n_("Hole in One"),
+        description: n_("Solve a word with the first guess"),
+        condition: Condition::Game(|record| record.won && record.guesses.len() == 1),
+    },
+    Achievement {
+        id: "solve-in-two",
+        name: n_("Two Step"),
+        description: n_("Solve a word in two guesses"),
+        condition: Condition::Game(|record| record.won && record.guesses.len() == 2),
+    },
+    Achievement {
+        id: "close-call",
+        name: n_("Close Call"),
+        description: n_("Solve a word with the last guess"),
+        condition: Condition::Game(|record| {
+            record.won && record.guesses.len() > 1 && record.guesses.len() == record.max_guesses
+        }),
+    },
+    Achievement {
+        id: "hard-win",
+        name: n_("Hard as Nails"),
+        description: n_("Win a game in hard mode"),
+        condition: Condition::Game(|record| record.won && record.hard_mode),
+    },
+    Achievement {
+        id: "no-gray",
+        name: n_("Clean Sweep"),
+        description: n_("Win without guessing a letter that was already ruled out"),
+        condition: Condition::Game(|record| {
+            record.won && record.guesses.len() > 1 && !reused_gray_letter(record)
+        }),
+    },
+    Achievement {
+        id: "triple-letter",
+        name: n_("Triple Threat"),
+        description: n_("Solve a word with the same letter three times"),
+        condition: Condition::Game(|record| record.won && has_triple_letter(&record.answer)),
+    },
+    Achievement {
+        id: "speed-demon",
+        name: n_("Speed Demon"),
+        description: n_("Solve a word in under 30 seconds"),
+        condition: Condition::Game(|record| record.won && record.duration() < 30),
+    },
+    Achievement {
+        id: "beat-the-clock",
+        name: n_("Beat the Clock"),
+        description: n_("Win a timed game"),
+        condition: Condition::Game(|record| record.won && record.mode == GameMode::Timed),
+    },
+    Achievement {
+        id: "daily-streak-7",
+        name: n_("Week Streak"),
+        description: n_("Win the daily game 7 days in a row"),
+        condition: Condition::Stats(|stats| stats.daily_max_streak >= 7),
+    },
+    Achievement {
+        id: "daily-streak-30",
+        name: n_("Month Streak"),
+        description: n_("Win the daily game 30 days in a row"),
+        condition: Condition::Stats(|stats| stats.daily_max_streak >= 30),
+    },
+    Achievement {
+        id: "survivor",
+        name: n_("Survivor"),
+        description: n_("Solve 10 words in one survival run"),
+        condition: Condition::Stats(|stats| stats.survival_best() >= 10),
+    },
+    Achievement {
+        id: "centurion",
+        name: n_("Centurion"),
+        description: n_("Win 100 games"),
+        condition: Condition::Stats(|stats| stats.wins >= 100),
+    },
+];
+
+/// Check if a guess used a letter an earlier row had already shown isn't in the answer
+fn reused_gray_letter(record: &GameRecord) -> bool {
+    let mut ruled_out: Vec<char> = Vec::new();
+    for (guess, colors) in &record.guesses {
+        if guess.chars().any(|c| ruled_out.contains(&c)) {
+            return true;
+        }
+        let scored: Vec<(char, usize)> = guess.chars().zip(colors.iter().copied()).collect();
+        for (c, color) in &scored {
+            // A repeated letter can be gray in one place and still be in the answer
+            let elsewhere: bool = scored
+                .iter()
+                .any(|(other, other_color)| other == c && *other_color != COLOR_GRAY);
+            if *color == COLOR_GRAY && !elsewhere {
+                ruled_out.push(*c);
+            }
+        }
+    }
+    return false;
+}
+
+/// Check if a word has some letter three or more times
+fn has_triple_letter(word: &str) -> bool {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in word.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    return counts.values().any(|&count| count >= 3);
+}
+
+impl Achievement {
+    /// Check if the history and stats meet the condition
+    pub fn earned(&self, history: &History, stats: &Stats) -> bool {
+        return match self.condition {
+            Condition::Game(check) => history.games.iter().any(check),
+            Condition::Stats(check) => check(stats),
+        };
+    }
+}
+
+/// The achievements the player has unlocked, with the date each was unlocked on
+#[derive(Clone, Debug, Default)]
+pub struct Unlocks {
+    pub dates: HashMap<String, Date>,
+}
+
+impl Unlocks {
+    /// Get the file the unlocks are saved in
+    pub fn path() -> PathBuf {
+        return data_dir().join("achievements.txt");
+    }
+
+    /// Load the saved unlocks
+    pub fn load() -> Unlocks {
+        let dates: HashMap<String, Date> = load_key_values(&Unlocks::path())
+            .into_iter()
+            .filter_map(|(id, date)| Some((id, Date::parse(&date)?)))
+            .collect();
+        return Unlocks { dates };
+    }
+
+    /// Save the unlocks
+    pub fn save(&self) -> io::Result<()> {
+        let mut values: Vec<(&str, String)> = self
+            .dates
+            .iter()
+            .map(|(id, date)| (id.as_str(), date.to_string()))
+            .collect();
+        values.sort();
+        return save_key_values(&Unlocks::path(), &values);
+    }
+
+    /// Get the date an achievement was unlocked on, if it has been
+    pub fn unlocked(&self, achievement: &Achievement) -> Option<Date> {
+        return self.dates.get(achievement.id).copied();
+    }
+
+    /// Unlock every achievement the history and stats have earned, returning the ones that
+    /// weren't unlocked before
+    pub fn check(
+        &mut self,
+        history: &History,
+        stats: &Stats,
+        today: Date,
+    ) -> Vec<&'static Achievement> {
+        let mut unlocked: Vec<&'static Achievement> = Vec::new();
+        for achievement in &ACHIEVEMENTS {
+            if self.unlocked(achievement).is_none() && achievement.earned(history, stats) {
+                self.dates.insert(achievement.id.to_string(), today);
+                unlocked.push(achievement);
+            }
+        }
+        return unlocked;
+    }
+
+    /// Describe the achievements as text, with the date of each one that's unlocked
+    pub fn summary(&self) -> String {
+        let unlocked: usize = ACHIEVEMENTS
+            .iter()
+            .filter(|achievement| self.unlocked(achievement).is_some())
+            .count();
+        let mut text: String = format!("Achievements: {} of {}\n", unlocked, ACHIEVEMENTS.len());
+        for achievement in &ACHIEVEMENTS {
+            let date: String = self
+                .unlocked(achievement)
+                .map_or(String::from("locked"), |date| date.to_string());
+            text.push_str(&format!(
+                "  {:<14} {:<10}  {}\n",
+                achievement.name, date, achievement.description
+            ));
+        }
+        return text;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::parse_pattern;
+
+    /// Make a won practice game of "crane" with some guesses and their patterns
+    fn record(guesses: &[(&str, &str)]) -> GameRecord {
+        return GameRecord {
+            date: Date::new(2026, 10, 19).unwrap(),
+            started: 1_792_400_000,
+            finished: 1_792_400_100,
+            mode: GameMode::Practice,
+            hard_mode: false,
+            seed: 1,
+            answer: String::from("crane"),
+            max_guesses: 6,
+            won: true,
+            guesses: guesses
+                .iter()
+                .map(|(guess, pattern)| (guess.to_string(), parse_pattern(pattern)))
+                .collect(),
+        };
+    }
+
+    #[test]
+    fn gray_letters_are_noticed() -> () {
+        assert!(!reused_gray_letter(&record(&[
+            ("slate", "BBGBG"),
+            ("crane", "GGGGG")
+        ])));
+        assert!(reused_gray_letter(&record(&[
+            ("slate", "BBGBG"),
+            ("trace", "BGGYG"),
+            ("crane", "GGGGG"),
+        ])));
+        // Only the last e is in the answer, so e isn't ruled out
+        assert!(!reused_gray_letter(&record(&[
+            ("geese", "BBBBG"),
+            ("crane", "GGGGG")
+        ])));
+        assert!(reused_gray_letter(&record(&[
+            ("geese", "BBBBG"),
+            ("grace", "BGGYG"),
+            ("crane", "GGGGG"),
+        ])));
+    }
+
+    #[test]
+    fn triple_letters_are_found() -> () {
+        assert!(has_triple_letter("eerie"));
+        assert!(has_triple_letter("bobbed"));
+        assert!(!has_triple_letter("sleep"));
+        assert!(!has_triple_letter("crane"));
+        assert!(!has_triple_letter(""));
+    }
+
+    #[test]
+    fn achievements_are_unlocked_once() -> () {
+        let history: History = History {
+            games: vec![record(&[("slate", "BBGBG"), ("crane", "GGGGG")])],
+        };
+        let today: Date = Date::new(2026, 10, 19).unwrap();
+        let mut unlocks: Unlocks = Unlocks::default();
+        let ids: Vec<&str> = unlocks
+            .check(&history, &Stats::default(), today)
+            .iter()
+            .map(|achievement| achievement.id)
+            .collect();
+        assert_eq!(ids, vec!["first-win", "solve-in-two", "no-gray"]);
+        assert_eq!(unlocks.unlocked(&ACHIEVEMENTS[0]), Some(today));
+        assert!(unlocks.check(&history, &Stats::default(), today).is_empty());
+    }
+}
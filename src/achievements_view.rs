@@ -0,0 +1,89 @@
+use crate::i18n::fill;
+use gettextrs::gettext;
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4::glib;
+use gtk4::{Image, Label, Widget};
+use libadwaita::prelude::{ActionRowExt, AdwDialogExt, PreferencesGroupExt, PreferencesPageExt};
+use libadwaita::{
+    ActionRow, Dialog, HeaderBar, PreferencesGroup, PreferencesPage, Toast, ToastOverlay,
+    ToolbarView,
+};
+use rustle::achievements::{Achievement, Unlocks, ACHIEVEMENTS};
+use rustle::date::Date;
+
+/// Write a date in the local format
+fn format_date(date: Date) -> String {
+    return glib::DateTime::from_local(date.year, date.month as i32, date.day as i32, 0, 0, 0.0)
+        .and_then(|time| time.format("%x"))
+        .map(|text| text.to_string())
+        .unwrap_or_else(|_| date.to_string());
+}
+
+/// Create the row listing an achievement, showing when it was unlocked if it has been
+fn achievement_row(achievement: &Achievement, unlocked: Option<Date>) -> ActionRow {
+    let row: ActionRow = ActionRow::builder()
+        .title(gettext(achievement.name))
+        .subtitle(gettext(achievement.description))
+        .build();
+    let icon: Image = match unlocked {
+        Some(_) => Image::from_icon_name("starred-symbolic"),
+        None => Image::from_icon_name("changes-prevent-symbolic"),
+    };
+    row.add_prefix(&icon);
+    match unlocked {
+        Some(date) => {
+            let date: Label = Label::new(Some(&format_date(date)));
+            date.add_css_class("dim-label");
+            date.add_css_class("numeric");
+            row.add_suffix(&date);
+        }
+        None => {
+            row.add_css_class("dim-label");
+            icon.update_property(&[gtk::accessible::Property::Label(&gettext("Locked"))]);
+        }
+    }
+    return row;
+}
+
+/// Announce newly unlocked achievements, one toast each
+pub fn announce_achievements(toast_overlay: &ToastOverlay, unlocked: &[&Achievement]) -> () {
+    for achievement in unlocked {
+        let toast: Toast = Toast::new(&fill(
+            &gettext("Achievement unlocked: {}"),
+            &[&gettext(achievement.name)],
+        ));
+        toast.set_timeout(4);
+        toast_overlay.add_toast(toast);
+    }
+}
+
+/// Show every achievement, unlocked ones with the date they were unlocked on
+pub fn show_achievements(parent: &impl IsA<Widget>, unlocks: &Unlocks) -> () {
+    let unlocked_count: usize = ACHIEVEMENTS
+        .iter()
+        .filter(|achievement| unlocks.unlocked(achievement).is_some())
+        .count();
+    let group: PreferencesGroup = PreferencesGroup::builder()
+        .description(fill(
+            &gettext("{} of {} unlocked"),
+            &[&unlocked_count.to_string(), &ACHIEVEMENTS.len().to_string()],
+        ))
+        .build();
+    for achievement in &ACHIEVEMENTS {
+        group.add(&achievement_row(achievement, unlocks.unlocked(achievement)));
+    }
+    let page: PreferencesPage = PreferencesPage::new();
+    page.add(&group);
+
+    let toolbar_view: ToolbarView = ToolbarView::new();
+    toolbar_view.add_top_bar(&HeaderBar::new());
+    toolbar_view.set_content(Some(&page));
+    let dialog: Dialog = Dialog::builder()
+        .title(gettext("Achievements"))
+        .content_width(480)
+        .content_height(640)
+        .child(&toolbar_view)
+        .build();
+    dialog.present(Some(parent));
+}
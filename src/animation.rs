@@ -0,0 +1,194 @@
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4::glib;
+use gtk4::graphene::Point;
+use gtk4::gsk::Transform;
+use gtk4::{Fixed, Label};
+use libadwaita::prelude::AnimationExt;
+use libadwaita::{CallbackAnimationTarget, Easing, TimedAnimation};
+use std::cell::Cell;
+use std::f64::consts::PI;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// CSS classes that show the color of a scored tile
+const TILE_COLOR_CLASSES: [&str; 3] = ["green", "yellow", "gray"];
+/// How long one tile takes to flip over, in milliseconds
+const FLIP_DURATION: u32 = 500;
+/// How long to wait before flipping the next tile in a row, in milliseconds
+const FLIP_STAGGER: u64 = 250;
+/// How long a typed letter pops for, in milliseconds
+const POP_DURATION: u32 = 120;
+/// How long a row shakes for, in milliseconds
+const SHAKE_DURATION: u32 = 400;
+/// How long one tile takes to bounce, in milliseconds
+const BOUNCE_DURATION: u32 = 400;
+/// How long to wait before bouncing the next tile in a row, in milliseconds
+const BOUNCE_STAGGER: u64 = 100;
+
+/// Called once when a group of animations has finished
+type DoneCallback = Rc<Cell<Option<Box<dyn FnOnce()>>>>;
+
+thread_local! {
+    /// Whether animations are turned on in the preferences
+    static ALLOWED: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Turn animations on or off, as long as the system allows them
+pub fn set_allowed(allowed: bool) -> () {
+    ALLOWED.set(allowed);
+}
+
+/// Whether animations should play, following the preferences and the system "reduce motion"
+/// setting
+pub fn animations_enabled(tile: &Label) -> bool {
+    return ALLOWED.get() && libadwaita::is_animations_enabled(tile);
+}
+
+/// Move and scale a tile around its center
+fn set_tile_transform(tile: &Label, scale_x: f32, scale_y: f32, dx: f32, dy: f32) -> () {
+    let Some(fixed) = tile
+        .parent()
+        .and_then(|parent| parent.downcast::<Fixed>().ok())
+    else {
+        return;
+    };
+    let center: Point = Point::new(tile.width() as f32 / 2.0, tile.height() as f32 / 2.0);
+    let transform: Transform = Transform::new()
+        .translate(&Point::new(center.x() + dx, center.y() + dy))
+        .scale(scale_x, scale_y)
+        .translate(&Point::new(-center.x(), -center.y()));
+    fixed.set_child_transform(tile, Some(&transform));
+}
+
+/// Play an animation from 0 to 1 on a tile
+fn animate(
+    tile: &Label,
+    duration: u32,
+    easing: Easing,
+    step: impl Fn(f64) + 'static,
+) -> TimedAnimation {
+    let target: CallbackAnimationTarget = CallbackAnimationTarget::new(step);
+    let animation: TimedAnimation = TimedAnimation::new(tile, 0.0, 1.0, duration, target);
+    animation.set_easing(easing);
+    return animation;
+}
+
+/// Play an animation on every tile of a row, starting each one a little after the last,
+/// then call `done`
+fn stagger(
+    tiles: &[Label],
+    delay: u64,
+    duration: u32,
+    easing: Easing,
+    step: impl Fn(usize, &Label, f64) + Clone + 'static,
+    done: impl FnOnce() + 'static,
+) -> () {
+    let done: DoneCallback = Rc::new(Cell::new(Some(Box::new(done))));
+    for (i, tile) in tiles.iter().enumerate() {
+        let tile_2: Label = tile.clone();
+        let step = step.clone();
+        let animation: TimedAnimation = animate(tile, duration, easing, move |value: f64| {
+            step(i, &tile_2, value)
+        });
+        if i == tiles.len() - 1 {
+            let done: DoneCallback = done.clone();
+            animation.connect_done(move |_| {
+                if let Some(done) = done.take() {
+                    done();
+                }
+            });
+        }
+        glib::timeout_add_local_once(Duration::from_millis(i as u64 * delay), move || {
+            animation.play()
+        });
+    }
+}
+
+/// Flip the tiles of a scored row over one at a time, showing each color halfway through.
+/// The tiles should already have their colors, `done` is called once every tile has flipped.
+pub fn flip_row(tiles: &[Label], done: impl FnOnce() + 'static) -> () {
+    if tiles.is_empty() || !animations_enabled(&tiles[0]) {
+        done();
+        return;
+    }
+    let colors: Rc<Vec<Option<&str>>> = Rc::new(
+        tiles
+            .iter()
+            .map(|tile| {
+                TILE_COLOR_CLASSES
+                    .into_iter()
+                    .find(|class| tile.has_css_class(class))
+            })
+            .collect(),
+    );
+    for tile in tiles {
+        for class in TILE_COLOR_CLASSES {
+            tile.remove_css_class(class);
+        }
+    }
+    let step = move |i: usize, tile: &Label, value: f64| {
+        if value >= 0.5
+            && let Some(color) = colors[i]
+        {
+            tile.add_css_class(color);
+        }
+        set_tile_transform(tile, 1.0, (1.0 - 2.0 * value).abs() as f32, 0.0, 0.0);
+    };
+    stagger(
+        tiles,
+        FLIP_STAGGER,
+        FLIP_DURATION,
+        Easing::EaseInOutSine,
+        step,
+        done,
+    );
+}
+
+/// Briefly grow a tile that a letter was just typed into
+pub fn pop(tile: &Label) -> () {
+    if !animations_enabled(tile) {
+        return;
+    }
+    let tile_2: Label = tile.clone();
+    let step = move |value: f64| {
+        let scale: f32 = 1.0 + 0.12 * (PI * value).sin() as f32;
+        set_tile_transform(&tile_2, scale, scale, 0.0, 0.0);
+    };
+    animate(tile, POP_DURATION, Easing::EaseOutCubic, step).play();
+}
+
+/// Shake a row from side to side, for a guess that wasn't accepted
+pub fn shake(tiles: &[Label]) -> () {
+    if tiles.is_empty() || !animations_enabled(&tiles[0]) {
+        return;
+    }
+    let tiles_2: Vec<Label> = tiles.to_vec();
+    let step = move |value: f64| {
+        let dx: f32 = (12.0 * (1.0 - value) * (6.0 * PI * value).sin()) as f32;
+        for tile in &tiles_2 {
+            set_tile_transform(tile, 1.0, 1.0, dx, 0.0);
+        }
+    };
+    animate(&tiles[0], SHAKE_DURATION, Easing::Linear, step).play();
+}
+
+/// Bounce the tiles of a winning row one after another, then call `done`
+pub fn bounce(tiles: &[Label], done: impl FnOnce() + 'static) -> () {
+    if tiles.is_empty() || !animations_enabled(&tiles[0]) {
+        done();
+        return;
+    }
+    let step = |_: usize, tile: &Label, value: f64| {
+        let dy: f32 = (-20.0 * (PI * value).sin()) as f32;
+        set_tile_transform(tile, 1.0, 1.0, 0.0, dy);
+    };
+    stagger(
+        tiles,
+        BOUNCE_STAGGER,
+        BOUNCE_DURATION,
+        Easing::EaseOutSine,
+        step,
+        done,
+    );
+}
@@ -0,0 +1,150 @@
+use crate::date::Date;
+use crate::game::{daily_seed, GameMode};
+use crate::history::{GameRecord, History};
+use crate::storage::data_dir;
+use std::fs::{create_dir_all, read_to_string, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// Past daily puzzles played again from the archive. They're kept apart from the history, so
+/// they don't count in the statistics or the daily streak. Each record's date is the date of
+/// the puzzle rather than the day it was played.
+#[derive(Clone, Debug, Default)]
+pub struct Archive {
+    pub games: Vec<GameRecord>,
+}
+
+impl Archive {
+    /// Get the file the archive games are saved in
+    pub fn path() -> PathBuf {
+        return data_dir().join("archive.txt");
+    }
+
+    /// Load the saved archive games, skipping any lines that can't be read
+    pub fn load() -> Archive {
+        let games: Vec<GameRecord> = match read_to_string(Archive::path()) {
+            Ok(text) => text.lines().filter_map(GameRecord::from_line).collect(),
+            Err(_) => Vec::new(),
+        };
+        return Archive { games };
+    }
+
+    /// Add a game to the end of the saved archive games
+    pub fn append(record: &GameRecord) -> io::Result<()> {
+        let path: PathBuf = Archive::path();
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let mut file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        return writeln!(file, "{}", record.to_line());
+    }
+
+    /// Get the best game played of a date's puzzle, whether on the day (from the history) or
+    /// later from the archive: the first win, or the last loss if it hasn't been solved
+    pub fn result<'a>(&'a self, history: &'a History, date: Date) -> Option<&'a GameRecord> {
+        // A daily game played on the day is in the history under the day it finished, which is
+        // the next day if it was finished after midnight, so it's found by its seed instead
+        let seed: u64 = daily_seed(date);
+        let played: Vec<&GameRecord> = history
+            .games
+            .iter()
+            .filter(|record| record.mode == GameMode::Daily && record.seed == seed)
+            .chain(self.games.iter().filter(|record| record.date == date))
+            .collect();
+        return played
+            .iter()
+            .find(|record| record.won)
+            .or(played.last())
+            .copied();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Make a record of a daily puzzle that was won or lost, finished on a day
+    fn record(puzzle: Date, finished: Date, won: bool) -> GameRecord {
+        return GameRecord {
+            date: finished,
+            started: 0,
+            finished: 0,
+            mode: GameMode::Daily,
+            hard_mode: false,
+            seed: daily_seed(puzzle),
+            answer: String::from("crane"),
+            max_guesses: 6,
+            won,
+            guesses: Vec::new(),
+        };
+    }
+
+    /// Make a date in October 2026
+    fn october(day: u32) -> Date {
+        return Date::new(2026, 10, day).unwrap();
+    }
+
+    #[test]
+    fn the_first_win_counts() -> () {
+        let history: History = History {
+            games: vec![record(october(5), october(5), false)],
+        };
+        let archive: Archive = Archive {
+            games: vec![
+                record(october(5), october(5), true),
+                record(october(5), october(5), false),
+            ],
+        };
+        assert_eq!(
+            archive.result(&history, october(5)).map(|r| r.won),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn the_last_loss_counts_if_unsolved() -> () {
+        // Archive games are kept under the date of the puzzle
+        let mut lost: GameRecord = record(october(5), october(5), false);
+        lost.answer = String::from("slate");
+        let history: History = History {
+            games: vec![record(october(5), october(5), false)],
+        };
+        let archive: Archive = Archive { games: vec![lost] };
+        let result: Option<&GameRecord> = archive.result(&history, october(5));
+        assert_eq!(result.map(|r| r.answer.as_str()), Some("slate"));
+    }
+
+    #[test]
+    fn games_played_on_the_day_count() -> () {
+        let history: History = History {
+            games: vec![
+                // Finished just after midnight
+                record(october(5), october(6), true),
+                record(october(7), october(7), false),
+            ],
+        };
+        let archive: Archive = Archive::default();
+        assert_eq!(
+            archive.result(&history, october(5)).map(|r| r.won),
+            Some(true)
+        );
+        assert_eq!(
+            archive.result(&history, october(7)).map(|r| r.won),
+            Some(false)
+        );
+        assert!(archive.result(&history, october(6)).is_none());
+    }
+
+    #[test]
+    fn other_games_are_ignored() -> () {
+        let mut practice: GameRecord = record(october(5), october(5), true);
+        practice.mode = GameMode::Practice;
+        let history: History = History {
+            games: vec![practice, record(october(4), october(4), true)],
+        };
+        let archive: Archive = Archive {
+            games: vec![record(october(6), october(6), true)],
+        };
+        assert!(archive.result(&history, october(5)).is_none());
+    }
+}
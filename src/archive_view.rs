@@ -0,0 +1,249 @@
+use crate::i18n::fill;
+use gettextrs::{gettext, ngettext};
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4::accessible::Property;
+use gtk4::glib::{self, WeakRef};
+use gtk4::Orientation::{Horizontal, Vertical};
+use gtk4::{Align, Box, Button, Grid, Label, Widget};
+use libadwaita::prelude::AdwDialogExt;
+use libadwaita::{Dialog, HeaderBar, ToolbarView};
+use rustle::archive::Archive;
+use rustle::date::{days_in_month, Date};
+use rustle::history::{GameRecord, History};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Write a month and its year, such as "October 2026", in the local format
+fn month_title(year: i32, month: u32) -> String {
+    return glib::DateTime::from_local(year, month as i32, 1, 0, 0, 0.0)
+        .and_then(|time| time.format("%B %Y"))
+        .map(|text| text.to_string())
+        .unwrap_or_else(|_| format!("{:04}-{:02}", year, month));
+}
+
+/// Write a date in the local format
+fn format_date(date: Date) -> String {
+    return glib::DateTime::from_local(date.year, date.month as i32, date.day as i32, 0, 0, 0.0)
+        .and_then(|time| time.format("%x"))
+        .map(|text| text.to_string())
+        .unwrap_or_else(|_| date.to_string());
+}
+
+/// Get the short name of a day of the week, from 0 for Monday
+fn weekday_name(weekday: u32) -> String {
+    // 2024-01-01 was a Monday
+    return glib::DateTime::from_local(2024, 1, 1 + weekday as i32, 0, 0, 0.0)
+        .and_then(|time| time.format("%a"))
+        .map(|text| text.to_string())
+        .unwrap_or_default();
+}
+
+/// Get the month before or after a month
+fn add_months(year: i32, month: u32, months: i32) -> (i32, u32) {
+    let index: i32 = year * 12 + month as i32 - 1 + months;
+    return (index.div_euclid(12), index.rem_euclid(12) as u32 + 1);
+}
+
+/// Build the button for a day of the calendar, marked with how its puzzle went. Today's puzzle
+/// and later ones can't be played from the archive.
+fn day_button(
+    date: Date,
+    record: Option<&GameRecord>,
+    today: Date,
+    play: &Rc<dyn Fn(Date)>,
+) -> Button {
+    let number: Label = Label::new(Some(&date.day.to_string()));
+    number.add_css_class("numeric");
+    let mark: Label = Label::new(None);
+    mark.add_css_class("archive-mark");
+    let content: Box = Box::new(Vertical, 0);
+    content.append(&number);
+    content.append(&mark);
+    let button: Button = Button::builder().child(&content).build();
+    button.add_css_class("flat");
+    button.add_css_class("archive-day");
+
+    let status: String = if date >= today {
+        button.set_sensitive(false);
+        mark.set_label(" ");
+        gettext("Not in the archive yet")
+    } else {
+        match record {
+            Some(record) if record.won => {
+                mark.set_label("✓");
+                button.add_css_class("archive-won");
+                fill(
+                    &ngettext(
+                        "Solved in {} try",
+                        "Solved in {} tries",
+                        record.guesses.len() as u32,
+                    ),
+                    &[&record.guesses.len().to_string()],
+                )
+            }
+            Some(_) => {
+                mark.set_label("✗");
+                button.add_css_class("archive-lost");
+                gettext("Not solved")
+            }
+            None => {
+                mark.set_label("•");
+                button.add_css_class("archive-unplayed");
+                gettext("Not played yet")
+            }
+        }
+    };
+    button.set_tooltip_text(Some(&status));
+    button.update_property(&[Property::Label(&format!(
+        "{}: {}",
+        format_date(date),
+        status
+    ))]);
+    let play: Rc<dyn Fn(Date)> = play.clone();
+    button.connect_clicked(move |_| play(date));
+    return button;
+}
+
+/// Fill the calendar with the days of a month, each under its day of the week, and return the
+/// number of days whose puzzles can be played and the number of those that have been
+fn fill_month(
+    grid: &Grid,
+    year: i32,
+    month: u32,
+    archive: &Archive,
+    history: &History,
+    today: Date,
+    play: &Rc<dyn Fn(Date)>,
+) -> (usize, usize) {
+    while let Some(child) = grid.first_child() {
+        grid.remove(&child);
+    }
+    for weekday in 0..7 {
+        let label: Label = Label::new(Some(&weekday_name(weekday)));
+        label.add_css_class("dim-label");
+        label.add_css_class("caption-heading");
+        grid.attach(&label, weekday as i32, 0, 1, 1);
+    }
+    let mut available: usize = 0;
+    let mut played: usize = 0;
+    let first_weekday: u32 = Date {
+        year,
+        month,
+        day: 1,
+    }
+    .weekday();
+    for day in 1..=days_in_month(year, month) {
+        let date: Date = Date { year, month, day };
+        let record: Option<&GameRecord> = archive.result(history, date);
+        if date < today {
+            available += 1;
+            if record.is_some() {
+                played += 1;
+            }
+        }
+        let cell: u32 = first_weekday + day - 1;
+        grid.attach(
+            &day_button(date, record, today, play),
+            (cell % 7) as i32,
+            (cell / 7 + 1) as i32,
+            1,
+            1,
+        );
+    }
+    return (available, played);
+}
+
+/// Show a calendar of past daily puzzles, marking the ones already played on the day or from the
+/// archive. `play` is given the date of the puzzle the player picks.
+pub fn show_archive(parent: &impl IsA<Widget>, play: impl Fn(Date) + 'static) -> () {
+    let archive: Archive = Archive::load();
+    let history: History = History::load();
+    let today: Date = Date::today();
+
+    let previous: Button = Button::from_icon_name("go-previous-symbolic");
+    previous.set_tooltip_text(Some(&gettext("Previous Month")));
+    previous.add_css_class("flat");
+    let next: Button = Button::from_icon_name("go-next-symbolic");
+    next.set_tooltip_text(Some(&gettext("Next Month")));
+    next.add_css_class("flat");
+    let title: Label = Label::new(None);
+    title.add_css_class("heading");
+    title.set_hexpand(true);
+    let navigation: Box = Box::new(Horizontal, 6);
+    navigation.append(&previous);
+    navigation.append(&title);
+    navigation.append(&next);
+
+    let grid: Grid = Grid::builder()
+        .row_spacing(2)
+        .column_spacing(2)
+        .column_homogeneous(true)
+        .halign(Align::Center)
+        .build();
+    let summary: Label = Label::new(None);
+    summary.add_css_class("dim-label");
+    summary.set_wrap(true);
+    let note: Label = Label::new(Some(&gettext(
+        "Puzzles played from the archive don't count towards your statistics or daily streak",
+    )));
+    note.add_css_class("dim-label");
+    note.add_css_class("caption");
+    note.set_wrap(true);
+    note.set_justify(gtk::Justification::Center);
+
+    let content: Box = Box::new(Vertical, 12);
+    content.set_margin_top(6);
+    content.set_margin_bottom(18);
+    content.set_margin_start(18);
+    content.set_margin_end(18);
+    content.append(&navigation);
+    content.append(&grid);
+    content.append(&summary);
+    content.append(&note);
+
+    let toolbar_view: ToolbarView = ToolbarView::new();
+    toolbar_view.add_top_bar(&HeaderBar::new());
+    toolbar_view.set_content(Some(&content));
+    let dialog: Dialog = Dialog::builder()
+        .title(gettext("Daily Archive"))
+        .content_width(360)
+        .child(&toolbar_view)
+        .build();
+
+    let dialog_2: WeakRef<Dialog> = dialog.downgrade();
+    let play: Rc<dyn Fn(Date)> = Rc::new(move |date: Date| {
+        if let Some(dialog) = dialog_2.upgrade() {
+            dialog.close();
+        }
+        play(date);
+    });
+    // Start on the month of the latest puzzle in the archive
+    let latest: Date = today.add_days(-1);
+    let shown: Rc<Cell<(i32, u32)>> = Rc::new(Cell::new((latest.year, latest.month)));
+    let next_2: WeakRef<Button> = next.downgrade();
+    let show_month: Rc<dyn Fn(i32)> = Rc::new(move |months: i32| {
+        let (year, month): (i32, u32) = shown.get();
+        let (year, month): (i32, u32) = add_months(year, month, months);
+        shown.set((year, month));
+        title.set_label(&month_title(year, month));
+        if let Some(next) = next_2.upgrade() {
+            next.set_sensitive((year, month) < (latest.year, latest.month));
+        }
+        let (available, played): (usize, usize) =
+            fill_month(&grid, year, month, &archive, &history, today, &play);
+        summary.set_label(&fill(
+            &ngettext(
+                "{} of {} puzzle played",
+                "{} of {} puzzles played",
+                available as u32,
+            ),
+            &[&played.to_string(), &available.to_string()],
+        ));
+    });
+    show_month(0);
+    let show_month_2: Rc<dyn Fn(i32)> = show_month.clone();
+    previous.connect_clicked(move |_| show_month_2(-1));
+    next.connect_clicked(move |_| show_month(1));
+    dialog.present(Some(parent));
+}
@@ -0,0 +1,109 @@
+use crate::game::{Difficulty, GameMode, GameOptions};
+
+/// Help text for the game options every command line front-end accepts
+pub const GAME_OPTIONS_HELP: &str = "  -m, --mode=MODE          Game mode (see below)
+  -l, --length=LENGTH      Number of letters in the word
+  -g, --guesses=GUESSES    Number of guesses allowed
+  --hard                   Hard mode: revealed hints must be used in later guesses
+  -s, --seed=SEED          Play the game with the given seed
+  --word-list=FILE         File of valid guesses, one per line
+  --answer-list=FILE       File of possible answers, one per line, optionally with frequencies
+  --difficulty=LEVEL       Answers of random games: any, easy (common) or hard (obscure)
+  --avoid-recent=GAMES     Don't pick the answers of this many recent games (default 30)
+  -d, --daily              Play today's daily game (same as --mode daily)
+  --time-limit=SECONDS     Time allowed in timed mode (default 180)
+  --words=COUNT            Number of words in speedrun mode (default 5)
+";
+
+/// Get the list of game modes for help text
+pub fn modes_help() -> String {
+    let mut text: String = String::from("Modes:\n");
+    for mode in GameMode::ALL {
+        text.push_str(&format!("  {:<12}{}\n", mode.name(), mode.description()));
+    }
+    return text;
+}
+
+/// Reads `--name value`, `--name=value` and `-n value` style options
+pub struct ArgParser<'a> {
+    args: &'a [String],
+    i: usize,
+    inline_value: Option<&'a str>,
+}
+
+impl<'a> ArgParser<'a> {
+    /// Parse a list of arguments, not including the program name
+    pub fn new(args: &'a [String]) -> ArgParser<'a> {
+        return ArgParser {
+            args,
+            i: 0,
+            inline_value: None,
+        };
+    }
+
+    /// Get the name of the next option
+    pub fn next_option(&mut self) -> Option<&'a str> {
+        let arg: &'a String = self.args.get(self.i)?;
+        self.i += 1;
+        return match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => {
+                self.inline_value = Some(value);
+                Some(name)
+            }
+            _ => {
+                self.inline_value = None;
+                Some(arg.as_str())
+            }
+        };
+    }
+
+    /// Get the value of the option that was just read
+    pub fn value(&mut self) -> Result<String, String> {
+        if let Some(value) = self.inline_value.take() {
+            return Ok(value.to_string());
+        }
+        let value: &String = self
+            .args
+            .get(self.i)
+            .ok_or(format!("Missing value for {}", self.args[self.i - 1]))?;
+        self.i += 1;
+        return Ok(value.clone());
+    }
+
+    /// Get the value of the option that was just read as a number
+    pub fn number<T: std::str::FromStr>(&mut self) -> Result<T, String> {
+        let value: String = self.value()?;
+        return value
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid number \"{}\"", value));
+    }
+
+    /// Handle an option that sets up the game, returning false if it isn't one
+    pub fn game_option(&mut self, name: &str, options: &mut GameOptions) -> Result<bool, String> {
+        match name {
+            "-m" | "--mode" => {
+                let mode: String = self.value()?;
+                options.mode =
+                    GameMode::from_name(mode.trim()).ok_or(format!("Unknown mode \"{}\"", mode))?;
+            }
+            "-l" | "--length" => options.word_length = self.number()?,
+            "-g" | "--guesses" => options.max_guesses = self.number()?,
+            "-s" | "--seed" => options.seed = Some(self.number()?),
+            "--word-list" => options.word_list = self.value()?,
+            "--answer-list" => options.answer_list = self.value()?,
+            "--difficulty" => {
+                let difficulty: String = self.value()?;
+                options.difficulty = Difficulty::from_name(difficulty.trim())
+                    .ok_or(format!("Unknown difficulty \"{}\"", difficulty))?;
+            }
+            "--avoid-recent" => options.avoid_recent = self.number()?,
+            "--hard" => options.hard_mode = true,
+            "-d" | "--daily" => options.mode = GameMode::Daily,
+            "--time-limit" => options.time_limit = self.number()?,
+            "--words" => options.speed_run_words = self.number()?,
+            _ => return Ok(false),
+        }
+        return Ok(true);
+    }
+}
@@ -0,0 +1,467 @@
+use rustle::args::{ArgParser, GAME_OPTIONS_HELP};
+use rustle::game::{GameOptions, WordLists};
+use rustle::history::parse_pattern;
+use rustle::race::{
+    Message, RaceClient, RaceEvent, RaceHost, RaceSettings, RaceState, RaceStatus, DEFAULT_PORT,
+};
+use rustle::referee::{
+    run_tournament, BotConnection, GameOutcome, ProcessBot, RefereeConfig, StdioBot,
+    DEFAULT_MAX_INVALID, DEFAULT_TIMEOUT_MS,
+};
+use rustle::scoreboard::Scoreboard;
+use rustle::solver::{solve, Strategy, Trace};
+use std::fs::write;
+use std::process::ExitCode;
+use std::thread::{self, ScopedJoinHandle};
+use std::time::{Duration, Instant};
+
+/// The opening word the solver benchmark uses if none is given
+const DEFAULT_OPENER: &str = "crane";
+
+const USAGE: &str = "Usage: rustle-cli COMMAND [OPTION…]
+
+Commands:
+  referee    Referee bots over a line based protocol (see docs/referee.md)
+  bench      Run the built-in solver against every answer
+  race       Host a race on the local network, or join one with the built-in solver
+
+Run rustle-cli COMMAND --help for the options of a command.
+";
+
+const REFEREE_USAGE: &str = "Usage: rustle-cli referee [OPTION…]
+
+Plays a bot against every answer in the answer list and reports how it did.
+
+Options:
+  --bot=COMMAND            Run COMMAND with sh and speak to it over its stdin and stdout
+  --stdio                  Speak to the bot over this program's stdin and stdout instead
+  --timeout=MS             Time the bot gets for each guess (default 5000)
+  --max-invalid=COUNT      Invalid guesses allowed per game before forfeiting (default 3)
+  --limit=COUNT            Only play the first COUNT answers
+  -v, --verbose            Print the outcome of every game
+";
+
+const BENCH_USAGE: &str = "Usage: rustle-cli bench [OPTION…]
+
+Runs the built-in solver against every answer in the answer list, starting with the same opening
+word each time, and reports how it did.
+
+Options:
+  -o, --opener=WORD        The first guess of every game (default crane)
+  --strategy=STRATEGY      How the solver picks guesses: first, expected or entropy (default expected)
+  --csv=FILE               Write every game's guesses and patterns to FILE
+  -j, --threads=COUNT      Number of threads to use (default: one per core)
+";
+
+const RACE_USAGE: &str = "Usage: rustle-cli race --host [OPTION…]
+       rustle-cli race --join=ADDRESS [OPTION…]
+
+Hosts races for players on the local network, or joins one and plays it with the built-in solver
+(see docs/race.md).
+
+Options:
+  --host                   Host races, starting each once enough players have joined
+  --port=PORT              The port to host on (default 7373)
+  --players=COUNT          Players to wait for before starting a race (default 2)
+  --rounds=COUNT           Races to run before stopping (default 1)
+  --join=ADDRESS           Join the race hosted at ADDRESS, given as HOST or HOST:PORT
+  --name=NAME              The name to join as (default Solver)
+  --delay=MS               Time to wait before each guess (default 1000)
+  --strategy=STRATEGY      How the solver picks guesses: first, expected or entropy (default expected)
+";
+
+/// How long the host waits between checks on the race
+const HOST_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long a joined player waits for the host's next message before checking again
+const JOIN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Host races until the number of rounds asked for have been run
+fn host_races(options: &GameOptions, port: u16, players: usize, rounds: u32) -> Result<(), String> {
+    let host: RaceHost = RaceHost::start(port, options)?;
+    println!("Hosting on port {}", host.port());
+    for round in 1..=rounds {
+        println!("Waiting for {} players", players);
+        while host.status().players < players {
+            thread::sleep(HOST_POLL_INTERVAL);
+        }
+        host.start_race();
+        println!("Race {} started", round);
+        let status: RaceStatus = loop {
+            let status: RaceStatus = host.status();
+            if !status.running {
+                break status;
+            }
+            thread::sleep(HOST_POLL_INTERVAL);
+        };
+        match status.winner {
+            Some((name, guesses)) => println!("Race {} won by {} in {}", round, name, guesses),
+            None => println!("Race {} ended without a winner", round),
+        }
+    }
+    host.stop();
+    return Ok(());
+}
+
+/// Play a race with the solver, sending its rows one at a time
+fn solve_race(
+    client: &RaceClient,
+    state: &mut RaceState,
+    settings: &RaceSettings,
+    options: &GameOptions,
+    strategy: Strategy,
+    delay: Duration,
+) -> Result<(), String> {
+    let options: GameOptions = settings.game_options(options);
+    let lists: WordLists = WordLists::load(&options)?;
+    if lists.answers.is_empty() || lists.words.is_empty() {
+        return Err(format!(
+            "There are no {}-letter words to race with",
+            settings.word_length
+        ));
+    }
+    if lists.fingerprint() != settings.answers {
+        // The seed would pick another word from a different list
+        eprintln!(
+            "Sitting out race {}: the host's answer list isn't the same as this one",
+            settings.round
+        );
+        return Ok(());
+    }
+    let answer: &str = &lists.answers[lists.pick_answer(settings.seed)];
+    let opener: &str = if lists.words.iter().any(|word| word == DEFAULT_OPENER) {
+        DEFAULT_OPENER
+    } else {
+        &lists.words[0]
+    };
+    let answer_bytes: Vec<Vec<u8>> = lists
+        .answers
+        .iter()
+        .map(|answer| answer.as_bytes().to_vec())
+        .collect();
+    let trace: Trace = solve(
+        &options,
+        &lists.words,
+        &answer_bytes,
+        answer,
+        opener,
+        strategy,
+    );
+    for (row, step) in trace.steps.iter().enumerate() {
+        // Stop once the race has moved on without the solver
+        let deadline: Instant = Instant::now() + delay;
+        while let Some(event) = client.wait(deadline.saturating_duration_since(Instant::now())) {
+            if !follow_race(state, &event) {
+                return Ok(());
+            }
+        }
+        if state.over || state.settings.as_ref() != Some(settings) {
+            return Ok(());
+        }
+        println!("{} {}", step.guess, step.pattern);
+        client.send_score(settings.round, row + 1, &parse_pattern(&step.pattern));
+    }
+    return Ok(());
+}
+
+/// Keep track of a race from an event, returning whether the player is still in it
+fn follow_race(state: &mut RaceState, event: &RaceEvent) -> bool {
+    match event {
+        RaceEvent::Message(message) => {
+            state.apply(message);
+            match message {
+                Message::Winner { id, guesses } => {
+                    let name: &str = state.player(*id).map_or("?", |player| &player.name);
+                    println!("Won by {} in {}", name, guesses);
+                }
+                Message::End => println!("Nobody solved it"),
+                _ => {}
+            }
+        }
+        RaceEvent::Disconnected => eprintln!("Lost the connection, reconnecting"),
+        RaceEvent::Reconnected => {
+            eprintln!("Reconnected");
+            *state = RaceState::default();
+        }
+        RaceEvent::Closed(reason) => {
+            println!("{}", reason);
+            return false;
+        }
+    }
+    return true;
+}
+
+/// Join a race and play every race the host starts with the solver, until the host stops
+fn join_race(
+    options: &GameOptions,
+    address: &str,
+    name: &str,
+    strategy: Strategy,
+    delay: Duration,
+) -> Result<(), String> {
+    let client: RaceClient = RaceClient::connect(address, name)?;
+    println!("Joined as player {}", client.id());
+    let mut state: RaceState = RaceState::default();
+    let mut played: Option<RaceSettings> = None;
+    loop {
+        if let Some(event) = client.wait(JOIN_POLL_INTERVAL)
+            && !follow_race(&mut state, &event)
+        {
+            return Ok(());
+        }
+        let Some(settings) = state.settings.clone() else {
+            continue;
+        };
+        if state.over || played.as_ref() == Some(&settings) {
+            continue;
+        }
+        println!("Race {} started", settings.round);
+        played = Some(settings.clone());
+        solve_race(&client, &mut state, &settings, options, strategy, delay)?;
+    }
+}
+
+/// Run the race subcommand
+fn race(args: &[String]) -> Result<(), String> {
+    let mut options: GameOptions = GameOptions::default();
+    let mut host: bool = false;
+    let mut port: u16 = DEFAULT_PORT;
+    let mut players: usize = 2;
+    let mut rounds: u32 = 1;
+    let mut address: Option<String> = None;
+    let mut name: String = String::from("Solver");
+    let mut delay_ms: u64 = 1000;
+    let mut strategy: Strategy = Strategy::Expected;
+    let mut parser: ArgParser = ArgParser::new(args);
+    while let Some(option) = parser.next_option() {
+        if parser.game_option(option, &mut options)? {
+            continue;
+        }
+        match option {
+            "--host" => host = true,
+            "--port" => port = parser.number()?,
+            "--players" => players = parser.number::<usize>()?.max(1),
+            "--rounds" => rounds = parser.number()?,
+            "--join" => address = Some(parser.value()?),
+            "--name" => name = parser.value()?,
+            "--delay" => delay_ms = parser.number()?,
+            "--strategy" => {
+                let name: String = parser.value()?;
+                strategy = Strategy::from_name(name.trim())
+                    .ok_or(format!("Unknown strategy \"{}\"", name))?;
+            }
+            "-h" | "--help" => {
+                print!("{}{}", RACE_USAGE, GAME_OPTIONS_HELP);
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option {}", option)),
+        }
+    }
+    options.validate()?;
+    return match (host, address) {
+        (true, None) => host_races(&options, port, players, rounds),
+        (false, Some(address)) => join_race(
+            &options,
+            &address,
+            &name,
+            strategy,
+            Duration::from_millis(delay_ms),
+        ),
+        _ => Err(String::from("Use exactly one of --host or --join")),
+    };
+}
+
+/// Run the bench subcommand
+fn bench(args: &[String]) -> Result<(), String> {
+    let mut options: GameOptions = GameOptions::default();
+    let mut opener: String = String::from(DEFAULT_OPENER);
+    let mut strategy: Strategy = Strategy::Expected;
+    let mut csv_path: Option<String> = None;
+    let mut threads: usize = thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1);
+    let mut parser: ArgParser = ArgParser::new(args);
+    while let Some(name) = parser.next_option() {
+        if parser.game_option(name, &mut options)? {
+            continue;
+        }
+        match name {
+            "-o" | "--opener" => opener = parser.value()?.trim().to_lowercase(),
+            "--strategy" => {
+                let name: String = parser.value()?;
+                strategy = Strategy::from_name(name.trim())
+                    .ok_or(format!("Unknown strategy \"{}\"", name))?;
+            }
+            "--csv" => csv_path = Some(parser.value()?),
+            "-j" | "--threads" => threads = parser.number::<usize>()?.max(1),
+            "-h" | "--help" => {
+                print!("{}{}", BENCH_USAGE, GAME_OPTIONS_HELP);
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option {}", name)),
+        }
+    }
+    options.validate()?;
+    let lists: WordLists = WordLists::load(&options)?;
+    if !lists.words.contains(&opener) {
+        return Err(format!(
+            "\"{}\" isn't a valid {}-letter guess",
+            opener, options.word_length
+        ));
+    }
+    let answer_bytes: Vec<Vec<u8>> = lists
+        .answers
+        .iter()
+        .map(|answer| answer.as_bytes().to_vec())
+        .collect();
+
+    let start: Instant = Instant::now();
+    let chunk_size: usize = lists.answers.len().div_ceil(threads);
+    let traces: Vec<Trace> = thread::scope(|scope| {
+        let workers: Vec<ScopedJoinHandle<Vec<Trace>>> = lists
+            .answers
+            .chunks(chunk_size)
+            .map(|chunk: &[String]| {
+                let options: &GameOptions = &options;
+                let words: &[String] = &lists.words;
+                let answer_bytes: &[Vec<u8>] = &answer_bytes;
+                let opener: &str = &opener;
+                scope.spawn(move || {
+                    return chunk
+                        .iter()
+                        .map(|answer| solve(options, words, answer_bytes, answer, opener, strategy))
+                        .collect::<Vec<Trace>>();
+                })
+            })
+            .collect();
+        return workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect();
+    });
+    let elapsed: Duration = start.elapsed();
+
+    let mut scoreboard: Scoreboard = Scoreboard::default();
+    for trace in &traces {
+        if trace.solved {
+            scoreboard.record_win(trace.steps.len());
+        } else {
+            scoreboard.record_failure("lost");
+        }
+    }
+    println!(
+        "Opener: {}\nStrategy: {}\nThreads: {}\nTime: {:.2}s",
+        opener,
+        strategy.name(),
+        threads,
+        elapsed.as_secs_f64()
+    );
+    print!("{}", scoreboard.summary());
+
+    if let Some(csv_path) = csv_path {
+        let mut csv: String = String::from("answer,solved,guesses,trace\n");
+        for trace in &traces {
+            let steps: Vec<String> = trace
+                .steps
+                .iter()
+                .map(|step| format!("{} {}", step.guess, step.pattern))
+                .collect();
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                trace.answer,
+                trace.solved,
+                trace.steps.len(),
+                steps.join("|")
+            ));
+        }
+        write(&csv_path, csv)
+            .map_err(|error| format!("Failed to write {}: {}", csv_path, error))?;
+    }
+    return Ok(());
+}
+
+/// Run the referee subcommand
+fn referee(args: &[String]) -> Result<(), String> {
+    let mut options: GameOptions = GameOptions::default();
+    let mut bot_command: Option<String> = None;
+    let mut stdio: bool = false;
+    let mut timeout_ms: u64 = DEFAULT_TIMEOUT_MS;
+    let mut max_invalid: usize = DEFAULT_MAX_INVALID;
+    let mut limit: Option<usize> = None;
+    let mut verbose: bool = false;
+    let mut parser: ArgParser = ArgParser::new(args);
+    while let Some(name) = parser.next_option() {
+        if parser.game_option(name, &mut options)? {
+            continue;
+        }
+        match name {
+            "--bot" => bot_command = Some(parser.value()?),
+            "--stdio" => stdio = true,
+            "--timeout" => timeout_ms = parser.number()?,
+            "--max-invalid" => max_invalid = parser.number()?,
+            "--limit" => limit = Some(parser.number()?),
+            "-v" | "--verbose" => verbose = true,
+            "-h" | "--help" => {
+                print!("{}{}", REFEREE_USAGE, GAME_OPTIONS_HELP);
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option {}", name)),
+        }
+    }
+    options.validate()?;
+    let lists: WordLists = WordLists::load(&options)?;
+    let answers: &[String] = match limit {
+        Some(limit) => &lists.answers[..limit.min(lists.answers.len())],
+        None => &lists.answers,
+    };
+    let config: RefereeConfig = RefereeConfig {
+        options,
+        timeout: Duration::from_millis(timeout_ms),
+        max_invalid,
+    };
+
+    let mut bot: Box<dyn BotConnection> = match (bot_command, stdio) {
+        (Some(command), false) => Box::new(ProcessBot::spawn(&command)?),
+        (None, true) => Box::new(StdioBot::new()),
+        _ => return Err(String::from("Use exactly one of --bot or --stdio")),
+    };
+    let scoreboard: Scoreboard = run_tournament(
+        bot.as_mut(),
+        &config,
+        &lists,
+        answers,
+        |i: usize, answer: &str, outcome: &GameOutcome| {
+            if verbose {
+                eprintln!("{:>5} {} {:?}", i + 1, answer, outcome);
+            }
+        },
+    )?;
+    // With --stdio, stdout belongs to the bot
+    if stdio {
+        eprint!("{}", scoreboard.summary());
+    } else {
+        print!("{}", scoreboard.summary());
+    }
+    return Ok(());
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result: Result<(), String> = match args.first().map(|arg| arg.as_str()) {
+        Some("referee") => referee(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("race") => race(&args[1..]),
+        Some("-h") | Some("--help") => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => Err(format!("Unknown command \"{}\"\n\n{}", command, USAGE)),
+        None => Err(String::from(USAGE)),
+    };
+    return match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    };
+}
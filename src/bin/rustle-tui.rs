@@ -0,0 +1,643 @@
+use rustle::achievements::{Achievement, Unlocks};
+use rustle::args::{modes_help, ArgParser, GAME_OPTIONS_HELP};
+use rustle::clock::{format_clock, Clock};
+use rustle::date::Date;
+use rustle::export::{export_file, import_file};
+use rustle::game::{
+    Game, GameMode, GameOptions, GuessResult, WordLists, COLOR_GRAY, COLOR_GREEN, COLOR_YELLOW,
+};
+use rustle::history::{GameRecord, History};
+use rustle::speedrun::{PersonalBests, SpeedRun};
+use rustle::stats::Stats;
+use rustle::survival::SurvivalRun;
+use std::io::{stdout, Stdout, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// Keys on the top row of the qwerty keyboard
+const KEYBOARD_ROW1: &str = "qwertyuiop";
+/// Keys on the middle row of the qwerty keyboard
+const KEYBOARD_ROW2: &str = "asdfghjkl";
+/// Keys on the bottom row of the qwerty keyboard
+const KEYBOARD_ROW3: &str = "zxcvbnm";
+
+/// How often the clock of a timed game is redrawn, in milliseconds
+const CLOCK_TICK_MS: i32 = 100;
+
+/// Reset all text attributes
+const RESET: &str = "\x1b[0m";
+
+const USAGE: &str = "Usage: rustle-tui [OPTION…]
+
+Options:
+";
+
+/// What to do instead of playing, as asked for on the command line
+enum Command {
+    Play,
+    ShowStats,
+    Export(String),
+    Import(String),
+}
+
+/// A key read from the terminal
+enum Input {
+    Letter(char),
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    Enter,
+    Quit,
+    Other,
+}
+
+/// Puts the terminal in raw mode on the alternate screen, and restores it when dropped
+struct RawTerminal {
+    original: libc::termios,
+}
+
+impl RawTerminal {
+    /// Switch the terminal to raw mode
+    fn enter() -> Result<RawTerminal, String> {
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(String::from("stdin is not a terminal!"));
+        }
+        let mut raw: libc::termios = original;
+        raw.c_iflag &= !(libc::ICRNL | libc::IXON);
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &raw);
+        }
+        // Alternate screen, hidden cursor
+        print!("\x1b[?1049h\x1b[?25l");
+        let _ = stdout().flush();
+        return Ok(RawTerminal { original });
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        print!("{}\x1b[?25h\x1b[?1049l", RESET);
+        let _ = stdout().flush();
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &self.original);
+        }
+    }
+}
+
+/// Check if more input arrives within a few milliseconds
+fn input_pending(timeout_ms: i32) -> bool {
+    let mut poll_fd: libc::pollfd = libc::pollfd {
+        fd: libc::STDIN_FILENO,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    return unsafe { libc::poll(&mut poll_fd, 1, timeout_ms) } > 0;
+}
+
+/// Read one byte from the terminal. It's read straight from the file descriptor, as bytes left
+/// in a buffer would be missed by `input_pending`.
+fn read_byte() -> Option<u8> {
+    let mut byte: u8 = 0;
+    let read: isize = unsafe { libc::read(libc::STDIN_FILENO, (&mut byte as *mut u8).cast(), 1) };
+    return (read == 1).then_some(byte);
+}
+
+/// Read one key press
+fn read_input() -> Input {
+    let Some(byte) = read_byte() else {
+        return Input::Quit;
+    };
+    return match byte {
+        b'a'..=b'z' | b'A'..=b'Z' => Input::Letter((byte as char).to_ascii_lowercase()),
+        0x7f | 0x08 => Input::Backspace,
+        b'\r' | b'\n' => Input::Enter,
+        // Ctrl+C and Ctrl+D
+        0x03 | 0x04 => Input::Quit,
+        0x1b => {
+            // A lone escape quits, an escape sequence is a cursor key or ignored
+            if !input_pending(30) {
+                return Input::Quit;
+            }
+            // Read up to the end of the sequence, leaving any keys typed after it
+            let mut sequence: Vec<u8> = Vec::new();
+            while input_pending(5)
+                && let Some(byte) = read_byte()
+            {
+                sequence.push(byte);
+                let finished: bool = match sequence.as_slice() {
+                    [b'['] | [b'O'] => false,
+                    [b'[', .., last] => (0x40..=0x7e).contains(last),
+                    _ => true,
+                };
+                if finished {
+                    break;
+                }
+            }
+            match sequence.as_slice() {
+                b"[D" | b"OD" => Input::Left,
+                b"[C" | b"OC" => Input::Right,
+                b"[H" | b"OH" | b"[1~" | b"[7~" => Input::Home,
+                b"[F" | b"OF" | b"[4~" | b"[8~" => Input::End,
+                b"[3~" => Input::Delete,
+                _ => Input::Other,
+            }
+        }
+        _ => Input::Other,
+    };
+}
+
+/// Get the escape code that colors a tile or key
+fn color_code(color: usize) -> &'static str {
+    return match color {
+        COLOR_GREEN => "\x1b[1;30;42m",
+        COLOR_YELLOW => "\x1b[1;30;43m",
+        COLOR_GRAY => "\x1b[1;37;100m",
+        _ => "\x1b[1m",
+    };
+}
+
+/// Draw the board
+fn draw_board(out: &mut String, game: &Game) -> () {
+    for row in 0..game.max_guesses {
+        out.push_str("  ");
+        for col in 0..game.word_length {
+            let c: char = game.board_chars[row][col];
+            let color: usize = game.board_colors[row][col];
+            let is_cursor: bool = !game.locked && row == game.guess && col == game.cur_x;
+            // The cursor is an underscore on an empty tile, and underlines a typed letter
+            let letter: String = match (is_cursor, c) {
+                (true, ' ') => String::from("_"),
+                (true, _) => format!("\x1b[4m{}\x1b[24m", c.to_uppercase()),
+                (false, _) => c.to_uppercase().to_string(),
+            };
+            if color == COLOR_GREEN || color == COLOR_YELLOW || color == COLOR_GRAY {
+                out.push_str(&format!("{} {} {} ", color_code(color), letter, RESET));
+            } else {
+                out.push_str(&format!("{}[{}]{} ", color_code(color), letter, RESET));
+            }
+        }
+        out.push_str("\r\n\r\n");
+    }
+}
+
+/// Draw the keyboard with each letter colored by what is known about it
+fn draw_keyboard(out: &mut String, game: &Game) -> () {
+    for (indent, row) in [(0, KEYBOARD_ROW1), (2, KEYBOARD_ROW2), (4, KEYBOARD_ROW3)] {
+        out.push_str(&" ".repeat(2 + indent));
+        for c in row.chars() {
+            let color: usize = *game.letter_states.get(&c).unwrap_or(&0);
+            out.push_str(&format!(
+                "{} {} {} ",
+                color_code(color),
+                c.to_uppercase(),
+                RESET
+            ));
+        }
+        out.push_str("\r\n");
+    }
+}
+
+/// Redraw the whole screen, with the clock of a timed game in the title
+fn draw(stdout: &mut Stdout, game: &Game, clock: Option<&str>, message: &str) -> () {
+    let mut out: String = String::from("\x1b[2J\x1b[H\r\n");
+    let mut subtitle: String = match game.mode {
+        GameMode::Practice => format!("Seed {}", game.seed),
+        GameMode::Daily => format!("Daily {}", Date::today()),
+        GameMode::Timed | GameMode::SpeedRun => format!("Seed {}", game.seed),
+        GameMode::Survival => format!("Survival · {} guesses", game.max_guesses),
+    };
+    if game.hard_mode {
+        subtitle.push_str(" · Hard");
+    }
+    if let Some(clock) = clock {
+        subtitle.push_str(&format!(" · {}", clock));
+    }
+    out.push_str(&format!(
+        "  \x1b[1;4mRustle!{}  {}\r\n\r\n",
+        RESET, subtitle
+    ));
+    draw_board(&mut out, game);
+    draw_keyboard(&mut out, game);
+    out.push_str("\r\n");
+    for line in message.lines() {
+        out.push_str(&format!("  {}\r\n", line));
+    }
+    let _ = stdout.write_all(out.as_bytes());
+    let _ = stdout.flush();
+}
+
+/// Read the command line options
+fn parse_args(args: &[String]) -> Result<(GameOptions, Command), String> {
+    let mut options: GameOptions = GameOptions::default();
+    let mut command: Command = Command::Play;
+    let mut parser: ArgParser = ArgParser::new(args);
+    while let Some(name) = parser.next_option() {
+        if parser.game_option(name, &mut options)? {
+            continue;
+        }
+        match name {
+            "--stats" => command = Command::ShowStats,
+            "--export" => command = Command::Export(parser.value()?),
+            "--import" => command = Command::Import(parser.value()?),
+            _ => return Err(format!("Unknown option {}", name)),
+        }
+    }
+    options.validate()?;
+    return Ok((options, command));
+}
+
+/// Start the next game, with the bonus guesses of a survival run if one is being played
+fn next_game(options: &mut GameOptions, lists: &WordLists, survival: Option<&SurvivalRun>) -> Game {
+    let recent: Vec<String> = History::load().recent_answers(options.avoid_recent);
+    let seed: u64 = options.take_seed(Date::today(), lists, &recent);
+    return match survival {
+        Some(run) => Game::new(&run.word_options(options), lists, seed),
+        None => Game::new(options, lists, seed),
+    };
+}
+
+/// Get the text shown after the game ends
+fn end_message(game: &Game, stats: &Stats) -> String {
+    let mut message: String = if game.won {
+        format!("You Win! Guessed in {} tries", game.guesses_used())
+    } else if game.timed_out {
+        format!("Time's up! The word was \"{}\"", game.answer)
+    } else {
+        format!("You Lose! The word was \"{}\"", game.answer)
+    };
+    message.push_str(&format!(
+        "\nPlayed {} · Win {}% · Streak {}",
+        stats.played,
+        stats.win_percentage(),
+        stats.current_streak
+    ));
+    message.push_str("\n\nEnter: play again · Esc: quit");
+    return message;
+}
+
+/// Format how much slower or faster a time was than another, like `+0:01.2`
+fn format_difference(time: Duration, compared_to: Duration) -> String {
+    if time >= compared_to {
+        return format!("+{}", format_clock(time - compared_to, true));
+    }
+    return format!("−{}", format_clock(compared_to - time, true));
+}
+
+/// Get the text shown after a word of a speed run: the split of a solved word, or how the run
+/// ended. A finished run is saved if it's a personal best.
+fn speed_run_message(run: &SpeedRun, game: &Game) -> String {
+    if game.won && !run.complete() {
+        let split: Duration = run.splits.last().copied().unwrap_or_default();
+        return format!(
+            "Word {} of {} solved! Split {} · Total {}\n\nEnter: next word · Esc: quit",
+            run.splits.len(),
+            run.words,
+            format_clock(split, true),
+            format_clock(run.total(), true)
+        );
+    }
+    if !run.complete() {
+        return format!(
+            "Speed run over! The word was \"{}\"\nSolved {} of {} words\n\nEnter: new run · Esc: quit",
+            game.answer,
+            run.splits.len(),
+            run.words
+        );
+    }
+    let mut bests: PersonalBests = PersonalBests::load();
+    let previous: Option<Vec<Duration>> = bests.best(&run.category).map(<[_]>::to_vec);
+    let new_best: bool = bests.submit(run);
+    let mut message: String = String::from("Speed run complete!\n");
+    for (i, split) in run.splits.iter().enumerate() {
+        message.push_str(&format!("Word {}: {}", i + 1, format_clock(*split, true)));
+        if let Some(best_split) = previous.as_ref().and_then(|best| best.get(i)) {
+            message.push_str(&format!(" ({})", format_difference(*split, *best_split)));
+        }
+        message.push('\n');
+    }
+    message.push_str(&format!("Total {}\n", format_clock(run.total(), true)));
+    if new_best {
+        message.push_str("New personal best!\n");
+        if let Err(error) = bests.save() {
+            message.push_str(&format!("Failed to save personal bests: {}\n", error));
+        }
+    } else if let Some(best) = previous {
+        message.push_str(&format!(
+            "Personal best {}\n",
+            format_clock(best.iter().sum(), true)
+        ));
+    }
+    message.push_str("\nEnter: new run · Esc: quit");
+    return message;
+}
+
+/// Everything about the games being played, from the first one until the player quits
+struct Session {
+    options: GameOptions,
+    lists: WordLists,
+    stats: Stats,
+    unlocks: Unlocks,
+    /// The run being played in survival mode
+    survival: Option<SurvivalRun>,
+    /// The run being played in speed run mode
+    speed_run: Option<SpeedRun>,
+    /// Counts down a timed game, or up through a speed run
+    clock: Clock,
+    game: Game,
+    /// Shown under the keyboard
+    message: String,
+}
+
+impl Session {
+    /// Start playing the first game
+    fn new(mut options: GameOptions, lists: WordLists) -> Session {
+        let stats: Stats = Stats::load();
+        let survival: Option<SurvivalRun> =
+            (options.mode == GameMode::Survival).then(|| SurvivalRun::new(&options));
+        let speed_run: Option<SpeedRun> =
+            (options.mode == GameMode::SpeedRun).then(|| SpeedRun::new(&options));
+        let game: Game = next_game(&mut options, &lists, survival.as_ref());
+        let mut message: String = String::new();
+        if game.mode == GameMode::Daily && stats.played_daily(Date::today()) {
+            message = String::from("You already played today's puzzle, this game won't count");
+        }
+        let mut session: Session = Session {
+            options,
+            lists,
+            stats,
+            unlocks: Unlocks::load(),
+            survival,
+            speed_run,
+            clock: Clock::default(),
+            game,
+            message,
+        };
+        session.update_clock();
+        return session;
+    }
+
+    /// Start a new game after one has ended. A solved word of a speed run leads on to the next
+    /// word with the same clock, anything else starts over.
+    fn new_game(&mut self) -> () {
+        let next_word: bool =
+            self.game.won && self.speed_run.as_ref().is_some_and(|run| !run.complete());
+        if !next_word {
+            if self.options.mode == GameMode::Daily {
+                self.options.mode = GameMode::Practice;
+            }
+            if let Some(run) = self.survival.as_mut() {
+                *run = SurvivalRun::new(&self.options);
+            }
+            if let Some(run) = self.speed_run.as_mut() {
+                *run = SpeedRun::new(&self.options);
+            }
+            self.clock = Clock::default();
+        }
+        self.game = next_game(&mut self.options, &self.lists, self.survival.as_ref());
+        self.message.clear();
+        self.update_clock();
+    }
+
+    /// Run the clock only while a timed game is being played
+    fn update_clock(&mut self) -> () {
+        if self.game.mode.timed() && !self.game.locked {
+            self.clock.resume();
+        } else {
+            self.clock.pause();
+        }
+    }
+
+    /// Get the time left to find the word in a timed game
+    fn time_left(&self) -> Duration {
+        let time_limit: Duration = Duration::from_secs(self.options.time_limit);
+        return time_limit.saturating_sub(self.clock.elapsed());
+    }
+
+    /// Get the clock shown in the title: the time left in a timed game, or the time so far in a
+    /// speed run
+    fn clock_text(&self) -> Option<String> {
+        if !self.game.mode.timed() {
+            return None;
+        }
+        return match self.speed_run.as_ref() {
+            Some(run) => Some(format!(
+                "{} · Word {} of {}",
+                format_clock(self.clock.elapsed(), true),
+                run.current_word(),
+                run.words
+            )),
+            None => {
+                // Count down in whole seconds, reaching zero just as time runs out
+                let time_left: Duration = self.time_left();
+                let shown: Duration = Duration::from_secs(time_left.as_secs_f64().ceil() as u64);
+                Some(format_clock(shown, false))
+            }
+        };
+    }
+
+    /// End a timed game once its time has run out
+    fn tick_clock(&mut self) -> () {
+        if self.clock.running() && self.game.mode == GameMode::Timed && self.time_left().is_zero() {
+            self.game.time_out();
+            self.finish_game();
+        }
+    }
+
+    /// Record a game that just ended and tell the player how it went
+    fn finish_game(&mut self) -> () {
+        self.update_clock();
+        if self.game.won
+            && let Some(run) = self.speed_run.as_mut()
+        {
+            run.solve(self.clock.elapsed());
+        }
+        self.stats.record(&self.game, Date::today());
+        if let Err(error) = self.stats.save() {
+            self.message = format!("Failed to save stats: {}\n", error);
+        }
+        if let Err(error) = History::append(&GameRecord::from_game(&self.game, Date::today())) {
+            self.message
+                .push_str(&format!("Failed to save history: {}\n", error));
+        }
+        if let Some(run) = self.speed_run.as_ref() {
+            self.message.push_str(&speed_run_message(run, &self.game));
+        } else if let Some(run) = self.survival.as_mut() {
+            if self.game.won {
+                // A solved word leads straight on to the next one
+                run.solve(self.game.guesses_used());
+                self.message.push_str(&format!(
+                    "Solved! {} in a row · {} bonus guesses",
+                    run.solved,
+                    run.bonus()
+                ));
+                self.game = next_game(&mut self.options, &self.lists, self.survival.as_ref());
+            } else {
+                self.message.push_str(&format!(
+                    "Run over! You solved {} words. The word was \"{}\"",
+                    run.solved, self.game.answer
+                ));
+                run.place = self.stats.record_survival(run.solved, Date::today());
+                if let Some(place) = run.place {
+                    self.message
+                        .push_str(&format!("\nNumber {} on the leaderboard", place));
+                }
+                if let Err(error) = self.stats.save() {
+                    self.message
+                        .push_str(&format!("\nFailed to save stats: {}", error));
+                }
+                self.message.push_str("\n\nEnter: new run · Esc: quit");
+            }
+        } else {
+            self.message.push_str(&end_message(&self.game, &self.stats));
+        }
+        let unlocked: Vec<&Achievement> =
+            self.unlocks
+                .check(&History::load(), &self.stats, Date::today());
+        if !unlocked.is_empty() {
+            let mut lines: String = String::new();
+            for achievement in &unlocked {
+                lines.push_str(&format!("Achievement unlocked: {}\n", achievement.name));
+            }
+            if let Err(error) = self.unlocks.save() {
+                lines.push_str(&format!("Failed to save achievements: {}\n", error));
+            }
+            self.message.insert_str(0, &lines);
+        }
+    }
+
+    /// Act on a key press, returning false once the player quits
+    fn handle_input(&mut self, input: Input) -> bool {
+        if self.game.locked {
+            match input {
+                Input::Enter => self.new_game(),
+                Input::Quit => return false,
+                _ => {}
+            }
+            return true;
+        }
+        self.message.clear();
+        match input {
+            Input::Letter(c) => {
+                self.game.type_letter(c);
+            }
+            Input::Backspace => {
+                self.game.backspace();
+            }
+            Input::Delete => {
+                self.game.delete();
+            }
+            Input::Left => {
+                self.game.move_cursor(self.game.cur_x.saturating_sub(1));
+            }
+            Input::Right => {
+                self.game.move_cursor(self.game.cur_x + 1);
+            }
+            Input::Home => {
+                self.game.move_cursor(0);
+            }
+            Input::End => {
+                self.game.move_cursor(self.game.row_end());
+            }
+            Input::Enter => match self.game.submit(&self.lists.words) {
+                GuessResult::Incomplete => self.message = String::from("Not enough letters!"),
+                GuessResult::InvalidWord => self.message = String::from("Invalid Word!"),
+                GuessResult::HardModeViolation(violation) => self.message = violation.to_string(),
+                GuessResult::Continue => {}
+                GuessResult::Won | GuessResult::Lost => self.finish_game(),
+            },
+            Input::Quit => return false,
+            Input::Other => {}
+        }
+        return true;
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        print!("{}{}", USAGE, GAME_OPTIONS_HELP);
+        println!("  --stats                  Print your statistics and exit");
+        println!(
+            "  --export=FILE            Save your statistics and history to a .json or .csv file"
+        );
+        println!(
+            "  --import=FILE            Add the statistics and history in a .json or .csv file"
+        );
+        println!("  -h, --help               Show this help\n");
+        print!("{}", modes_help());
+        return ExitCode::SUCCESS;
+    }
+    let (options, command): (GameOptions, Command) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    match command {
+        Command::Play => {}
+        Command::ShowStats => {
+            print!("{}", Stats::load().summary());
+            print!("{}", Unlocks::load().summary());
+            return ExitCode::SUCCESS;
+        }
+        Command::Export(path) => {
+            if let Err(message) = export_file(Path::new(&path)) {
+                eprintln!("{}", message);
+                return ExitCode::FAILURE;
+            }
+            println!("Exported to {}", path);
+            return ExitCode::SUCCESS;
+        }
+        Command::Import(path) => {
+            match import_file(Path::new(&path)) {
+                Ok(added) => println!("Imported {} new games from {}", added, path),
+                Err(message) => {
+                    eprintln!("{}", message);
+                    return ExitCode::FAILURE;
+                }
+            }
+            return ExitCode::SUCCESS;
+        }
+    }
+    let lists: WordLists = match WordLists::load(&options) {
+        Ok(lists) => lists,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let terminal: RawTerminal = match RawTerminal::enter() {
+        Ok(terminal) => terminal,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut out: Stdout = stdout();
+    let mut session: Session = Session::new(options, lists);
+    loop {
+        let clock: Option<String> = session.clock_text();
+        draw(&mut out, &session.game, clock.as_deref(), &session.message);
+        // While the clock runs, wait for keys only until it's time to show it again
+        if session.clock.running() && !input_pending(CLOCK_TICK_MS) {
+            session.tick_clock();
+            continue;
+        }
+        if !session.handle_input(read_input()) {
+            break;
+        }
+    }
+
+    drop(terminal);
+    return ExitCode::SUCCESS;
+}
@@ -0,0 +1,49 @@
+use std::time::{Duration, Instant};
+
+/// A stopwatch that can be paused and resumed
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Clock {
+    /// Time counted before the clock was last resumed
+    counted: Duration,
+    /// When the clock was last resumed, if it's running
+    resumed: Option<Instant>,
+}
+
+impl Clock {
+    /// Check if the clock is running
+    pub fn running(&self) -> bool {
+        return self.resumed.is_some();
+    }
+
+    /// Start the clock, or carry on from where it was paused
+    pub fn resume(&mut self) -> () {
+        if self.resumed.is_none() {
+            self.resumed = Some(Instant::now());
+        }
+    }
+
+    /// Stop the clock, keeping the time counted so far
+    pub fn pause(&mut self) -> () {
+        if let Some(resumed) = self.resumed.take() {
+            self.counted += resumed.elapsed();
+        }
+    }
+
+    /// Get the time counted while the clock was running
+    pub fn elapsed(&self) -> Duration {
+        return match self.resumed {
+            Some(resumed) => self.counted + resumed.elapsed(),
+            None => self.counted,
+        };
+    }
+}
+
+/// Format a time as minutes and seconds, with tenths of a second if asked for
+pub fn format_clock(time: Duration, tenths: bool) -> String {
+    let total: u128 = time.as_millis() / 100;
+    let seconds: u128 = total / 10;
+    if tenths {
+        return format!("{}:{:02}.{}", seconds / 60, seconds % 60, total % 10);
+    }
+    return format!("{}:{:02}", seconds / 60, seconds % 60);
+}
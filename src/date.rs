@@ -0,0 +1,171 @@
+use std::fmt::{Display, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date in the local time zone
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Create a date from its parts, if they form a real date
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        return Some(Date { year, month, day });
+    }
+
+    /// Get the current local date
+    pub fn today() -> Date {
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        unsafe {
+            let now: libc::time_t = libc::time(std::ptr::null_mut());
+            libc::localtime_r(&now, &mut tm);
+        }
+        return Date {
+            year: tm.tm_year + 1900,
+            month: (tm.tm_mon + 1) as u32,
+            day: tm.tm_mday as u32,
+        };
+    }
+
+    /// Get the date a number of days after 1970-01-01
+    pub fn from_days(days: i64) -> Date {
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z: i64 = days + 719468;
+        let era: i64 = z.div_euclid(146097);
+        let doe: i64 = z - era * 146097;
+        let yoe: i64 = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy: i64 = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp: i64 = (5 * doy + 2) / 153;
+        let day: u32 = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month: u32 = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year: i64 = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        return Date {
+            year: year as i32,
+            month,
+            day,
+        };
+    }
+
+    /// Get the number of days since 1970-01-01
+    pub fn days(&self) -> i64 {
+        // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year: i64 = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era: i64 = year.div_euclid(400);
+        let yoe: i64 = year - era * 400;
+        let month: i64 = self.month as i64;
+        let doy: i64 =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe: i64 = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        return era * 146097 + doe - 719468;
+    }
+
+    /// Get the date a number of days away from this one
+    pub fn add_days(&self, days: i64) -> Date {
+        return Date::from_days(self.days() + days);
+    }
+
+    /// Get the day of the week, from 0 for Monday to 6 for Sunday
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday
+        return (self.days() + 3).rem_euclid(7) as u32;
+    }
+
+    /// Parse a date in the form YYYY-MM-DD
+    pub fn parse(text: &str) -> Option<Date> {
+        let mut parts = text.trim().splitn(3, '-');
+        let year: i32 = parts.next()?.parse().ok()?;
+        let month: u32 = parts.next()?.parse().ok()?;
+        let day: u32 = parts.next()?.parse().ok()?;
+        return Date::new(year, month, day);
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day);
+    }
+}
+
+/// Get the number of days in a month
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    return match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        _ => 0,
+    };
+}
+
+/// Get the current time, in seconds since 1970-01-01 UTC
+pub fn unix_time() -> u64 {
+    return SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Read a date that is known to be valid
+    fn date(text: &str) -> Date {
+        return Date::parse(text).unwrap();
+    }
+
+    #[test]
+    fn dates_are_checked() -> () {
+        assert_eq!(
+            Date::new(2024, 2, 29),
+            Some(Date {
+                year: 2024,
+                month: 2,
+                day: 29
+            })
+        );
+        assert_eq!(Date::new(2026, 2, 29), None);
+        assert_eq!(Date::new(2026, 4, 31), None);
+        assert_eq!(Date::new(2026, 13, 1), None);
+        assert_eq!(Date::new(2026, 1, 0), None);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2026, 12), 31);
+    }
+
+    #[test]
+    fn days_roll_over() -> () {
+        assert_eq!(date("2026-01-31").add_days(1), date("2026-02-01"));
+        assert_eq!(date("2026-12-31").add_days(1), date("2027-01-01"));
+        assert_eq!(date("2027-01-01").add_days(-1), date("2026-12-31"));
+        assert_eq!(date("2024-02-28").add_days(1), date("2024-02-29"));
+        assert_eq!(date("2026-02-28").add_days(1), date("2026-03-01"));
+        assert_eq!(date("2026-10-19").add_days(365), date("2027-10-19"));
+        assert_eq!(date("1970-01-01").days(), 0);
+        assert_eq!(Date::from_days(-1), date("1969-12-31"));
+    }
+
+    #[test]
+    fn weekdays_start_on_monday() -> () {
+        // 2026-10-19 is a Monday and 1970-01-01 a Thursday
+        assert_eq!(date("2026-10-19").weekday(), 0);
+        assert_eq!(date("2026-10-25").weekday(), 6);
+        assert_eq!(date("1970-01-01").weekday(), 3);
+        assert_eq!(date("1969-12-29").weekday(), 0);
+    }
+
+    #[test]
+    fn dates_are_parsed_and_written() -> () {
+        let date: Date = date(" 2026-03-07 ");
+        assert_eq!(date.to_string(), "2026-03-07");
+        assert_eq!(Date::parse("2026-3-7"), Some(date));
+        assert_eq!(Date::parse("2026-02-30"), None);
+        assert_eq!(Date::parse("2026-03"), None);
+        assert_eq!(Date::parse("today"), None);
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+
+/// One meaning of a word
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Definition {
+    /// Such as "noun" or "verb", or empty if the dictionary doesn't say
+    pub part_of_speech: String,
+    pub text: String,
+}
+
+/// Definitions of words, read from an optional file described in docs/dictionary.md
+#[derive(Clone, Debug, Default)]
+pub struct Dictionary {
+    entries: HashMap<String, Vec<Definition>>,
+}
+
+/// Spell out the one-letter parts of speech WordNet uses
+fn part_of_speech_name(part_of_speech: &str) -> String {
+    return match part_of_speech {
+        "n" => String::from("noun"),
+        "v" => String::from("verb"),
+        "a" | "s" => String::from("adjective"),
+        "r" => String::from("adverb"),
+        _ => part_of_speech.to_string(),
+    };
+}
+
+impl Dictionary {
+    /// Read a dictionary from its text. Each line is a word, optionally its part of speech, and
+    /// a definition, separated by tabs. Blank lines and lines starting with `#` are skipped, as
+    /// are lines that don't fit.
+    pub fn parse(text: &str) -> Dictionary {
+        let mut entries: HashMap<String, Vec<Definition>> = HashMap::new();
+        for line in text.lines() {
+            let line: &str = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.splitn(3, '\t').map(|field| field.trim()).collect();
+            let (word, part_of_speech, text): (&str, &str, &str) = match fields.as_slice() {
+                [word, text] => (word, "", text),
+                [word, part_of_speech, text] => (word, part_of_speech, text),
+                _ => continue,
+            };
+            if word.is_empty() || text.is_empty() {
+                continue;
+            }
+            entries
+                .entry(word.to_lowercase())
+                .or_default()
+                .push(Definition {
+                    part_of_speech: part_of_speech_name(part_of_speech),
+                    text: text.to_string(),
+                });
+        }
+        return Dictionary { entries };
+    }
+
+    /// Load the dictionary at a path. An empty path means there is no dictionary, which gives
+    /// an empty one.
+    pub fn load(path: &str) -> Result<Dictionary, String> {
+        if path.trim().is_empty() {
+            return Ok(Dictionary::default());
+        }
+        return match read_to_string(path) {
+            Ok(text) => Ok(Dictionary::parse(&text)),
+            Err(_) => Err(format!("dictionary \"{}\" not found!", path)),
+        };
+    }
+
+    /// Get the definitions of a word, in the order the file has them
+    pub fn lookup(&self, word: &str) -> &[Definition] {
+        return self
+            .entries
+            .get(&word.to_lowercase())
+            .map_or(&[], |definitions| definitions.as_slice());
+    }
+
+    /// Get the number of words with definitions
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    /// Check if there are no definitions at all
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Make a definition
+    fn definition(part_of_speech: &str, text: &str) -> Definition {
+        return Definition {
+            part_of_speech: part_of_speech.to_string(),
+            text: text.to_string(),
+        };
+    }
+
+    #[test]
+    fn dictionaries_are_parsed() -> () {
+        let dictionary: Dictionary = Dictionary::parse(
+            "# word\tpart of speech\tdefinition\r\n\
+             \n\
+             Crane\tn\ta large long-necked bird\r\n\
+             crane\tv\tstretch out the neck\n\
+             crane\t \tlifting machine\n\
+             slate\ta fine-grained rock\n\
+             fjord\tn\ta long, narrow inlet\twith steep sides\n\
+             plumb\tperpendicular\tx\n\
+             quick\ts\tmoving fast\n\
+             nothing here\n\
+             \tn\tno word\n\
+             empty\tn\t\n",
+        );
+        assert_eq!(dictionary.len(), 5);
+        assert_eq!(
+            dictionary.lookup("CRANE"),
+            [
+                definition("noun", "a large long-necked bird"),
+                definition("verb", "stretch out the neck"),
+                definition("", "lifting machine"),
+            ]
+        );
+        assert_eq!(
+            dictionary.lookup("slate"),
+            [definition("", "a fine-grained rock")]
+        );
+        assert_eq!(
+            dictionary.lookup("fjord"),
+            [definition("noun", "a long, narrow inlet\twith steep sides")]
+        );
+        // Parts of speech that aren't WordNet's are kept as they are
+        assert_eq!(
+            dictionary.lookup("plumb"),
+            [definition("perpendicular", "x")]
+        );
+        assert_eq!(
+            dictionary.lookup("quick"),
+            [definition("adjective", "moving fast")]
+        );
+        assert!(dictionary.lookup("empty").is_empty());
+        assert!(dictionary.lookup("# word").is_empty());
+    }
+
+    #[test]
+    fn no_path_means_no_dictionary() -> () {
+        assert!(Dictionary::load("").unwrap().is_empty());
+        assert!(Dictionary::load("  ").unwrap().is_empty());
+        assert!(Dictionary::load("/nonexistent/rustle-dictionary.txt").is_err());
+        assert!(Dictionary::parse("").is_empty());
+    }
+}
@@ -0,0 +1,494 @@
+use crate::date::Date;
+use crate::game::{pattern_string, GameMode, MAX_GUESS_LIMIT, MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use crate::history::{parse_pattern, GameRecord, History};
+use crate::json::Json;
+use crate::stats::Stats;
+use std::collections::{HashMap, HashSet};
+use std::fs::{read_to_string, write};
+use std::path::Path;
+
+/// The columns of the games table in a CSV export
+const CSV_COLUMNS: [&str; 11] = [
+    "date",
+    "started",
+    "finished",
+    "duration",
+    "mode",
+    "hard_mode",
+    "seed",
+    "answer",
+    "max_guesses",
+    "won",
+    "guesses",
+];
+
+/// The header of the stats table in a CSV export, which comes after the games
+const CSV_STATS_HEADER: &str = "statistic,value";
+
+/// The file formats stats and history can be exported to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+impl Format {
+    /// Pick the format from a file's extension
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let extension: String = path.extension()?.to_str()?.to_lowercase();
+        return match extension.as_str() {
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        };
+    }
+}
+
+/// Stats and history, as exported from or imported to one machine
+#[derive(Clone, Debug, Default)]
+pub struct Backup {
+    pub stats: Stats,
+    pub history: History,
+}
+
+impl Backup {
+    /// Get the saved stats and history
+    pub fn load() -> Backup {
+        return Backup {
+            stats: Stats::load(),
+            history: History::load(),
+        };
+    }
+
+    /// Save the stats and history, replacing what was saved before
+    pub fn save(&self) -> Result<(), String> {
+        self.stats
+            .save()
+            .map_err(|error| format!("Failed to save stats: {}", error))?;
+        self.history
+            .save()
+            .map_err(|error| format!("Failed to save history: {}", error))?;
+        return Ok(());
+    }
+
+    /// Write as a document in a format
+    pub fn to_text(&self, format: Format) -> String {
+        return match format {
+            Format::Json => format!("{}\n", self.to_json()),
+            Format::Csv => self.to_csv(),
+        };
+    }
+
+    /// Read a document in a format
+    pub fn from_text(text: &str, format: Format) -> Result<Backup, String> {
+        return match format {
+            Format::Json => Backup::from_json(&Json::parse(text)?),
+            Format::Csv => Backup::from_csv(text),
+        };
+    }
+
+    /// Get as a JSON object of stats and games
+    fn to_json(&self) -> Json {
+        let stats: Vec<(String, Json)> = self
+            .stats
+            .to_values()
+            .into_iter()
+            .map(|(key, value)| {
+                let value: Json = match value.parse::<u64>() {
+                    Ok(n) => Json::from_u64(n),
+                    Err(_) => Json::String(value),
+                };
+                (key.to_string(), value)
+            })
+            .collect();
+        let games: Vec<Json> = self.history.games.iter().map(record_to_json).collect();
+        return Json::Object(vec![
+            (String::from("stats"), Json::Object(stats)),
+            (String::from("history"), Json::Array(games)),
+        ]);
+    }
+
+    /// Read a JSON object written by `to_json`
+    fn from_json(json: &Json) -> Result<Backup, String> {
+        let mut values: HashMap<String, String> = HashMap::new();
+        if let Some(Json::Object(fields)) = json.get("stats") {
+            for (key, value) in fields {
+                let text: String = match value {
+                    Json::String(text) => text.clone(),
+                    Json::Number(text) => text.clone(),
+                    _ => continue,
+                };
+                values.insert(key.clone(), text);
+            }
+        }
+        let mut games: Vec<GameRecord> = Vec::new();
+        let records: &[Json] = json
+            .get("history")
+            .and_then(Json::as_array)
+            .ok_or("No history found")?;
+        for (i, record) in records.iter().enumerate() {
+            let record: GameRecord =
+                record_from_json(record).ok_or(format!("Game {} can't be read", i + 1))?;
+            games.push(record);
+        }
+        return Ok(Backup {
+            stats: Stats::from_values(&values),
+            history: History { games },
+        });
+    }
+
+    /// Get as a CSV table of games, then a table of stats after a blank line
+    fn to_csv(&self) -> String {
+        let mut text: String = CSV_COLUMNS.join(",");
+        text.push('\n');
+        for record in &self.history.games {
+            let guesses: Vec<String> = record
+                .guesses
+                .iter()
+                .map(|(guess, colors)| format!("{}:{}", guess, pattern_string(colors)))
+                .collect();
+            let fields: [String; 11] = [
+                record.date.to_string(),
+                record.started.to_string(),
+                record.finished.to_string(),
+                record.duration().to_string(),
+                record.mode.name().to_string(),
+                record.hard_mode.to_string(),
+                record.seed.to_string(),
+                record.answer.clone(),
+                record.max_guesses.to_string(),
+                record.won.to_string(),
+                guesses.join(" "),
+            ];
+            let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            text.push_str(&fields.join(","));
+            text.push('\n');
+        }
+        text.push('\n');
+        text.push_str(CSV_STATS_HEADER);
+        text.push('\n');
+        for (key, value) in self.stats.to_values() {
+            text.push_str(&format!("{},{}\n", key, csv_field(&value)));
+        }
+        return text;
+    }
+
+    /// Read CSV tables written by `to_csv`. The games' columns may be in any order.
+    fn from_csv(text: &str) -> Result<Backup, String> {
+        let mut lines = text.lines().enumerate();
+        let header: Vec<String> = match lines.next() {
+            Some((_, line)) => split_csv_line(line),
+            None => return Err(String::from("The file is empty")),
+        };
+        let column = |name: &str| -> Result<usize, String> {
+            return header
+                .iter()
+                .position(|field| field == name)
+                .ok_or(format!("Missing column {}", name));
+        };
+        let columns: Vec<usize> = CSV_COLUMNS
+            .iter()
+            .filter(|name| **name != "duration")
+            .map(|name| column(name))
+            .collect::<Result<Vec<usize>, String>>()?;
+
+        let mut games: Vec<GameRecord> = Vec::new();
+        let mut values: HashMap<String, String> = HashMap::new();
+        let mut in_stats: bool = false;
+        for (i, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if line.trim() == CSV_STATS_HEADER {
+                in_stats = true;
+                continue;
+            }
+            let fields: Vec<String> = split_csv_line(line);
+            if in_stats {
+                if let [key, value] = fields.as_slice() {
+                    values.insert(key.clone(), value.clone());
+                }
+                continue;
+            }
+            let field = |n: usize| -> &str {
+                return fields.get(columns[n]).map(String::as_str).unwrap_or("");
+            };
+            let record: GameRecord =
+                csv_record(&field).ok_or(format!("Line {} can't be read", i + 1))?;
+            games.push(record);
+        }
+        return Ok(Backup {
+            stats: Stats::from_values(&values),
+            history: History { games },
+        });
+    }
+
+    /// Add another machine's stats and history. Games that are already here are skipped, and
+    /// the stats never go down, so streaks carry over. Returns the number of games added.
+    pub fn merge(&mut self, other: &Backup) -> usize {
+        let known: HashSet<(u64, u64, String, u64)> =
+            self.history.games.iter().map(record_key).collect();
+        let mut added: usize = 0;
+        for record in &other.history.games {
+            if !known.contains(&record_key(record)) {
+                self.history.games.push(record.clone());
+                added += 1;
+            }
+        }
+        self.history
+            .games
+            .sort_by_key(|record| (record.finished, record.started));
+
+        let mut stats: Stats = Stats::from_history(&self.history);
+        stats.merge(&self.stats);
+        stats.merge(&other.stats);
+        self.stats = stats;
+        return added;
+    }
+}
+
+/// Get what tells a game apart from every other one, to find games that were imported before
+fn record_key(record: &GameRecord) -> (u64, u64, String, u64) {
+    return (
+        record.started,
+        record.finished,
+        record.answer.clone(),
+        record.seed,
+    );
+}
+
+/// Check that an imported game makes sense, so that it can be replayed
+fn valid_record(record: &GameRecord) -> bool {
+    let length: usize = record.answer.chars().count();
+    return (MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&length)
+        && record.answer.chars().all(|c| c.is_ascii_lowercase())
+        && (1..=MAX_GUESS_LIMIT).contains(&record.max_guesses)
+        && record.guesses.len() <= record.max_guesses
+        && record.guesses.iter().all(|(guess, colors)| {
+            guess.chars().count() == length
+                && guess.chars().all(|c| c.is_ascii_lowercase())
+                && colors.len() == length
+        });
+}
+
+/// Get a game as a JSON object
+fn record_to_json(record: &GameRecord) -> Json {
+    let guesses: Vec<Json> = record
+        .guesses
+        .iter()
+        .map(|(guess, colors)| {
+            Json::Object(vec![
+                (String::from("word"), Json::String(guess.clone())),
+                (
+                    String::from("pattern"),
+                    Json::String(pattern_string(colors)),
+                ),
+            ])
+        })
+        .collect();
+    return Json::Object(vec![
+        (String::from("date"), Json::String(record.date.to_string())),
+        (String::from("started"), Json::from_u64(record.started)),
+        (String::from("finished"), Json::from_u64(record.finished)),
+        (
+            String::from("mode"),
+            Json::String(record.mode.name().to_string()),
+        ),
+        (String::from("hard_mode"), Json::Bool(record.hard_mode)),
+        (String::from("seed"), Json::from_u64(record.seed)),
+        (String::from("answer"), Json::String(record.answer.clone())),
+        (
+            String::from("max_guesses"),
+            Json::from_u64(record.max_guesses as u64),
+        ),
+        (String::from("won"), Json::Bool(record.won)),
+        (String::from("guesses"), Json::Array(guesses)),
+    ]);
+}
+
+/// Read a game written by `record_to_json`
+fn record_from_json(json: &Json) -> Option<GameRecord> {
+    let mut guesses: Vec<(String, Vec<usize>)> = Vec::new();
+    for guess in json.get("guesses")?.as_array()? {
+        guesses.push((
+            guess.get("word")?.as_str()?.to_string(),
+            parse_pattern(guess.get("pattern")?.as_str()?),
+        ));
+    }
+    let record: GameRecord = GameRecord {
+        date: Date::parse(json.get("date")?.as_str()?)?,
+        started: json.get("started")?.as_u64()?,
+        finished: json.get("finished")?.as_u64()?,
+        mode: GameMode::from_name(json.get("mode")?.as_str()?)?,
+        hard_mode: json.get("hard_mode")?.as_bool()?,
+        seed: json.get("seed")?.as_u64()?,
+        answer: json.get("answer")?.as_str()?.to_string(),
+        max_guesses: json.get("max_guesses")?.as_u64()? as usize,
+        won: json.get("won")?.as_bool()?,
+        guesses,
+    };
+    return valid_record(&record).then_some(record);
+}
+
+/// Read a game from a CSV row, given its fields in the order of `CSV_COLUMNS` without duration
+fn csv_record<'a>(field: &impl Fn(usize) -> &'a str) -> Option<GameRecord> {
+    let mut guesses: Vec<(String, Vec<usize>)> = Vec::new();
+    for guess in field(9).split_whitespace() {
+        let (word, pattern) = guess.split_once(':')?;
+        guesses.push((word.to_string(), parse_pattern(pattern)));
+    }
+    let record: GameRecord = GameRecord {
+        date: Date::parse(field(0))?,
+        started: field(1).parse().ok()?,
+        finished: field(2).parse().ok()?,
+        mode: GameMode::from_name(field(3))?,
+        hard_mode: field(4).parse().ok()?,
+        seed: field(5).parse().ok()?,
+        answer: field(6).to_string(),
+        max_guesses: field(7).parse().ok()?,
+        won: field(8).parse().ok()?,
+        guesses,
+    };
+    return valid_record(&record).then_some(record);
+}
+
+/// Quote a CSV field if it has anything in it that would break the row apart
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", text.replace('"', "\"\""));
+    }
+    return text.to_string();
+}
+
+/// Split a CSV row into its fields, removing any quotes around them
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields: Vec<String> = Vec::new();
+    let mut field: String = String::new();
+    let mut quoted: bool = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    return fields;
+}
+
+/// Export the saved stats and history to a file, in the format its extension asks for
+pub fn export_file(path: &Path) -> Result<(), String> {
+    let format: Format = Format::from_path(path).ok_or("The file must end in .json or .csv")?;
+    return write(path, Backup::load().to_text(format))
+        .map_err(|error| format!("Failed to write {}: {}", path.display(), error));
+}
+
+/// Merge the stats and history in a file into the saved ones. Returns the number of games added.
+pub fn import_file(path: &Path) -> Result<usize, String> {
+    let format: Format = Format::from_path(path).ok_or("The file must end in .json or .csv")?;
+    let text: String = read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+    let imported: Backup = Backup::from_text(&text, format)
+        .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+    let mut backup: Backup = Backup::load();
+    let added: usize = backup.merge(&imported);
+    backup.save()?;
+    return Ok(added);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{COLOR_GRAY, COLOR_GREEN, COLOR_YELLOW};
+
+    /// Make a finished game with a seed and times
+    fn record(seed: u64, started: u64) -> GameRecord {
+        return GameRecord {
+            date: Date::new(2026, 10, 19).unwrap(),
+            started,
+            finished: started + 60,
+            mode: GameMode::Practice,
+            hard_mode: false,
+            seed,
+            answer: String::from("crane"),
+            max_guesses: 6,
+            won: true,
+            guesses: vec![
+                (
+                    String::from("react"),
+                    vec![
+                        COLOR_YELLOW,
+                        COLOR_YELLOW,
+                        COLOR_GREEN,
+                        COLOR_YELLOW,
+                        COLOR_GRAY,
+                    ],
+                ),
+                (String::from("crane"), vec![COLOR_GREEN; 5]),
+            ],
+        };
+    }
+
+    #[test]
+    fn json_keeps_large_numbers() -> () {
+        let backup: Backup = Backup {
+            stats: Stats::default(),
+            history: History {
+                games: vec![record(u64::MAX, u64::MAX - 60), record(1 << 53 | 1, 0)],
+            },
+        };
+        let text: String = backup.to_text(Format::Json);
+        let mut imported: Backup = Backup::from_text(&text, Format::Json).unwrap();
+        assert_eq!(imported.history.games, backup.history.games);
+        // Importing the same backup again finds every game already there
+        assert_eq!(imported.merge(&backup), 0);
+        assert_eq!(imported.history.games.len(), 2);
+    }
+
+    #[test]
+    fn merging_skips_known_games() -> () {
+        let mut backup: Backup = Backup {
+            stats: Stats::default(),
+            history: History {
+                games: vec![record(1, 100), record(3, 300)],
+            },
+        };
+        let other: Backup = Backup {
+            stats: Stats {
+                played: 2,
+                max_streak: 10,
+                ..Stats::default()
+            },
+            history: History {
+                games: vec![record(3, 300), record(2, 200)],
+            },
+        };
+        assert_eq!(backup.merge(&other), 1);
+        let seeds: Vec<u64> = backup.history.games.iter().map(|game| game.seed).collect();
+        assert_eq!(seeds, vec![1, 2, 3]);
+        // The stats come from the merged history, but never go below either side's
+        assert_eq!(backup.stats.played, 3);
+        assert_eq!(backup.stats.current_streak, 3);
+        assert_eq!(backup.stats.max_streak, 10);
+        assert_eq!(backup.merge(&other), 0);
+        assert_eq!(backup.history.games.len(), 3);
+    }
+
+    #[test]
+    fn csv_round_trips() -> () {
+        let backup: Backup = Backup {
+            stats: Stats::default(),
+            history: History {
+                games: vec![record(1, 100), record(2, 200)],
+            },
+        };
+        let text: String = backup.to_text(Format::Csv);
+        let imported: Backup = Backup::from_text(&text, Format::Csv).unwrap();
+        assert_eq!(imported.history.games, backup.history.games);
+    }
+}
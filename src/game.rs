@@ -0,0 +1,1018 @@
+use crate::date::{unix_time, Date};
+use rand::distr::weighted::WeightedIndex;
+use rand::distr::Distribution;
+use rand::{rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+
+/// The color for a letter that has not yet been checked
+pub const COLOR_UNSET: usize = 0;
+/// The color for a letter that is not in the word
+pub const COLOR_GRAY: usize = 1;
+/// The color for a letter that is in the word, but not at that position
+pub const COLOR_YELLOW: usize = 2;
+/// The color for a letter that is in the word at that position
+pub const COLOR_GREEN: usize = 3;
+
+/// The default length of the word / width of the board
+pub const DEFAULT_WORD_LENGTH: usize = 5;
+/// The default number of allowed guesses / height of the board
+pub const DEFAULT_MAX_GUESSES: usize = 6;
+/// The shortest allowed word length
+pub const MIN_WORD_LENGTH: usize = 2;
+/// The longest allowed word length
+pub const MAX_WORD_LENGTH: usize = 12;
+/// The most guesses a game can allow
+pub const MAX_GUESS_LIMIT: usize = 12;
+
+/// The default number of seconds allowed for a timed game
+pub const DEFAULT_TIME_LIMIT: u64 = 180;
+/// The shortest allowed time limit, in seconds
+pub const MIN_TIME_LIMIT: u64 = 10;
+/// The longest allowed time limit, in seconds
+pub const MAX_TIME_LIMIT: u64 = 3600;
+/// The default number of words in a speed run
+pub const DEFAULT_SPEED_RUN_WORDS: usize = 5;
+/// The most words a speed run can have
+pub const MAX_SPEED_RUN_WORDS: usize = 50;
+
+/// The default list of valid guesses
+pub const DEFAULT_WORD_LIST: &str = "assets/lists/words.txt";
+/// The default list of possible answers
+pub const DEFAULT_ANSWER_LIST: &str = "assets/lists/answers.txt";
+
+/// The default number of recent games whose answers aren't picked again
+pub const DEFAULT_AVOID_RECENT: usize = 30;
+/// The most recent games whose answers can be avoided
+pub const MAX_AVOID_RECENT: usize = 1000;
+/// How many random seeds to try when looking for an answer that fits the difficulty and hasn't
+/// come up lately, before settling for whatever the last one gives
+const SEED_TRIES: usize = 1000;
+
+/// Upper bound (exclusive) for randomly generated seeds, kept short so they are easy to share
+pub const SEED_MAX: u64 = 1_000_000;
+
+/// Where an FNV-1a hash starts
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+/// All lowercase letters
+pub const LOWERCASE: &str = "qwertyuiopasdfghjklzxcvbnm"; // "Typo: In word 'qwertyuiopasdfghjklzxcvbnm'" SHUT UP
+
+/// The ways a game can be played
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameMode {
+    /// A random answer every game
+    Practice,
+    /// The same answer for everyone, once per day
+    Daily,
+    /// A random answer that has to be found before the clock runs out
+    Timed,
+    /// Several random answers in a row, solved as fast as possible
+    SpeedRun,
+    /// Random answers one after another until one isn't solved, with unused guesses carried over
+    Survival,
+}
+
+impl GameMode {
+    /// Every game mode, in the order they are listed to the player
+    pub const ALL: [GameMode; 5] = [
+        GameMode::Practice,
+        GameMode::Daily,
+        GameMode::Timed,
+        GameMode::SpeedRun,
+        GameMode::Survival,
+    ];
+
+    /// Get the name used for the mode on the command line and in saved data
+    pub fn name(&self) -> &'static str {
+        return match self {
+            GameMode::Practice => "practice",
+            GameMode::Daily => "daily",
+            GameMode::Timed => "timed",
+            GameMode::SpeedRun => "speedrun",
+            GameMode::Survival => "survival",
+        };
+    }
+
+    /// Get a short description of the mode
+    pub fn description(&self) -> &'static str {
+        return match self {
+            GameMode::Practice => "A random word every game (default)",
+            GameMode::Daily => "Today's word, the same for everyone",
+            GameMode::Timed => "A random word to find before the time limit",
+            GameMode::SpeedRun => "Several random words in a row, as fast as possible",
+            GameMode::Survival => "Random words until one is missed, unused guesses carry over",
+        };
+    }
+
+    /// Check if games in the mode are played against a clock
+    pub fn timed(&self) -> bool {
+        return matches!(self, GameMode::Timed | GameMode::SpeedRun);
+    }
+
+    /// Find a mode by its name
+    pub fn from_name(name: &str) -> Option<GameMode> {
+        return GameMode::ALL.into_iter().find(|mode| mode.name() == name);
+    }
+}
+
+/// How common the answers of new random games are. Only answer lists with a frequency column
+/// can tell common words from obscure ones; with any other list, or one where every answer is
+/// as common as the others, every answer fits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    /// Any answer
+    Any,
+    /// The more common half of the answers
+    Easy,
+    /// The less common half of the answers
+    Hard,
+}
+
+impl Difficulty {
+    /// Every difficulty, in the order they are listed to the player
+    pub const ALL: [Difficulty; 3] = [Difficulty::Any, Difficulty::Easy, Difficulty::Hard];
+
+    /// Get the name used for the difficulty on the command line and in saved settings
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Difficulty::Any => "any",
+            Difficulty::Easy => "easy",
+            Difficulty::Hard => "hard",
+        };
+    }
+
+    /// Find a difficulty by its name
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        return Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name() == name);
+    }
+}
+
+/// Options that decide how a game is set up
+#[derive(Clone, Debug)]
+pub struct GameOptions {
+    pub mode: GameMode,
+    pub word_length: usize,
+    pub max_guesses: usize,
+    pub hard_mode: bool,
+    /// Seconds allowed for a timed game
+    pub time_limit: u64,
+    /// Number of words in a speed run
+    pub speed_run_words: usize,
+    /// A seed to use for the next practice game instead of a random one
+    pub seed: Option<u64>,
+    pub word_list: String,
+    pub answer_list: String,
+    /// How common the answers of random games are
+    pub difficulty: Difficulty,
+    /// The number of recent games whose answers aren't picked for random games
+    pub avoid_recent: usize,
+}
+
+impl Default for GameOptions {
+    fn default() -> GameOptions {
+        return GameOptions {
+            mode: GameMode::Practice,
+            word_length: DEFAULT_WORD_LENGTH,
+            max_guesses: DEFAULT_MAX_GUESSES,
+            hard_mode: false,
+            time_limit: DEFAULT_TIME_LIMIT,
+            speed_run_words: DEFAULT_SPEED_RUN_WORDS,
+            seed: None,
+            word_list: String::from(DEFAULT_WORD_LIST),
+            answer_list: String::from(DEFAULT_ANSWER_LIST),
+            difficulty: Difficulty::Any,
+            avoid_recent: DEFAULT_AVOID_RECENT,
+        };
+    }
+}
+
+/// Why a game can't be played with some options
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptionsError {
+    /// The word length is out of range
+    WordLength,
+    /// The number of guesses is out of range
+    Guesses,
+    /// The time limit is out of range
+    TimeLimit,
+    /// The number of words in a speed run is out of range
+    SpeedRunWords,
+    /// Too many recent games are to be avoided
+    AvoidRecent,
+    /// A seed was given for a mode that picks its own
+    Seed(GameMode),
+    /// A word list couldn't be read
+    ListNotFound(String),
+    /// The answer list has no words of the length played
+    NoAnswers(String, usize),
+}
+
+impl Display for OptionsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            OptionsError::WordLength => write!(
+                f,
+                "Word length must be between {} and {}",
+                MIN_WORD_LENGTH, MAX_WORD_LENGTH
+            ),
+            OptionsError::Guesses => {
+                write!(f, "Guesses must be between 1 and {}", MAX_GUESS_LIMIT)
+            }
+            OptionsError::TimeLimit => write!(
+                f,
+                "Time limit must be between {} and {} seconds",
+                MIN_TIME_LIMIT, MAX_TIME_LIMIT
+            ),
+            OptionsError::SpeedRunWords => write!(
+                f,
+                "Speed runs must have between 1 and {} words",
+                MAX_SPEED_RUN_WORDS
+            ),
+            OptionsError::AvoidRecent => write!(
+                f,
+                "At most the last {} games can be avoided",
+                MAX_AVOID_RECENT
+            ),
+            OptionsError::Seed(mode) => write!(f, "A seed can't be used in {} mode", mode.name()),
+            OptionsError::ListNotFound(path) => write!(f, "word list \"{}\" not found!", path),
+            OptionsError::NoAnswers(path, word_length) => write!(
+                f,
+                "answer list \"{}\" has no {}-letter words!",
+                path, word_length
+            ),
+        };
+    }
+}
+
+impl From<OptionsError> for String {
+    fn from(error: OptionsError) -> String {
+        return error.to_string();
+    }
+}
+
+impl GameOptions {
+    /// Check that the options describe a game that can be played
+    pub fn validate(&self) -> Result<(), OptionsError> {
+        if self.word_length < MIN_WORD_LENGTH || self.word_length > MAX_WORD_LENGTH {
+            return Err(OptionsError::WordLength);
+        }
+        if self.max_guesses < 1 || self.max_guesses > MAX_GUESS_LIMIT {
+            return Err(OptionsError::Guesses);
+        }
+        if self.time_limit < MIN_TIME_LIMIT || self.time_limit > MAX_TIME_LIMIT {
+            return Err(OptionsError::TimeLimit);
+        }
+        if self.speed_run_words < 1 || self.speed_run_words > MAX_SPEED_RUN_WORDS {
+            return Err(OptionsError::SpeedRunWords);
+        }
+        if self.avoid_recent > MAX_AVOID_RECENT {
+            return Err(OptionsError::AvoidRecent);
+        }
+        if self.seed.is_some() && self.mode != GameMode::Practice {
+            return Err(OptionsError::Seed(self.mode));
+        }
+        return Ok(());
+    }
+
+    /// Get the seed for the next game, using up a seed that was asked for. Random seeds are
+    /// picked so the answer fits the difficulty and isn't one of the recent answers given.
+    pub fn take_seed(&mut self, today: Date, lists: &WordLists, recent: &[String]) -> u64 {
+        return match self.mode {
+            GameMode::Daily => daily_seed(today),
+            GameMode::Practice => self
+                .seed
+                .take()
+                .unwrap_or_else(|| lists.random_seed(self.difficulty, recent)),
+            GameMode::Timed | GameMode::SpeedRun | GameMode::Survival => {
+                lists.random_seed(self.difficulty, recent)
+            }
+        };
+    }
+}
+
+/// The valid guesses and possible answers for a word length
+#[derive(Clone, Debug)]
+pub struct WordLists {
+    pub words: Vec<String>,
+    pub answers: Vec<String>,
+    /// How common each answer is, from the answer list's optional second column. Empty if the
+    /// list doesn't have one.
+    pub frequencies: Vec<f64>,
+    /// Picks answers in proportion to their weight, if the list has frequencies
+    weights: Option<WeightedIndex<f64>>,
+    /// The frequency that splits the answers into common and obscure halves. None if the list
+    /// has no frequencies, or they're all the same so there's no obscure half.
+    median_frequency: Option<f64>,
+}
+
+/// Get how likely an answer is to be picked, from how common it is. The logarithm keeps the most
+/// common words from crowding out the rest.
+fn answer_weight(frequency: f64) -> f64 {
+    return 1.0 + frequency.max(0.0).ln_1p();
+}
+
+impl WordLists {
+    /// Load the word lists named in the options, keeping only words of the right length
+    pub fn load(options: &GameOptions) -> Result<WordLists, OptionsError> {
+        let answer_entries: Vec<(String, Option<f64>)> =
+            load_list(&options.answer_list, options.word_length)?;
+        if answer_entries.is_empty() {
+            return Err(OptionsError::NoAnswers(
+                options.answer_list.clone(),
+                options.word_length,
+            ));
+        }
+        let words: Vec<String> = load_list(&options.word_list, options.word_length)?
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect();
+        return Ok(WordLists::new(words, answer_entries));
+    }
+
+    /// Make word lists from an answer list's text, with the answers as the only valid guesses
+    #[cfg(test)]
+    pub(crate) fn from_answers(text: &str) -> WordLists {
+        return WordLists::new(Vec::new(), parse_list(text, DEFAULT_WORD_LENGTH));
+    }
+
+    /// Make word lists from the valid guesses and the answers with their frequencies. Every
+    /// answer is also a valid guess.
+    fn new(mut words: Vec<String>, answer_entries: Vec<(String, Option<f64>)>) -> WordLists {
+        let answers: Vec<String> = answer_entries
+            .iter()
+            .map(|(answer, _)| answer.clone())
+            .collect();
+        for answer in &answers {
+            if !words.contains(answer) {
+                words.push(answer.clone());
+            }
+        }
+        // Answers without a frequency in a list that has them are taken to be the rarest
+        let frequencies: Vec<f64> = if answer_entries.iter().any(|(_, f)| f.is_some()) {
+            answer_entries
+                .iter()
+                .map(|(_, frequency)| frequency.unwrap_or(0.0))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let weights: Option<WeightedIndex<f64>> = if frequencies.is_empty() {
+            None
+        } else {
+            WeightedIndex::new(frequencies.iter().map(|f| answer_weight(*f))).ok()
+        };
+        let mut sorted: Vec<f64> = frequencies.clone();
+        sorted.sort_by(f64::total_cmp);
+        // Hard answers are below the median, so there have to be some
+        let median_frequency: Option<f64> = sorted
+            .get(sorted.len() / 2)
+            .copied()
+            .filter(|median| sorted[0] < *median);
+        return WordLists {
+            words,
+            answers,
+            frequencies,
+            weights,
+            median_frequency,
+        };
+    }
+
+    /// Pick the index of the answer for a seed. The same seed and answer list always give the
+    /// same answer, and common answers come up more often if the list has frequencies.
+    pub fn pick_answer(&self, seed: u64) -> usize {
+        return match &self.weights {
+            Some(weights) => weights.sample(&mut ChaCha8Rng::seed_from_u64(seed)),
+            None => pick_answer(&self.answers, seed),
+        };
+    }
+
+    /// Describe the answers and their frequencies in a few characters: the number of answers
+    /// and a hash of them, such as `2309-6997292fdbae628b`. Lists that pick the same answer
+    /// from every seed have the same fingerprint.
+    pub fn fingerprint(&self) -> String {
+        let mut hash: u64 = FNV_OFFSET_BASIS;
+        for (i, answer) in self.answers.iter().enumerate() {
+            hash = fnv_hash(hash, answer.as_bytes());
+            if let Some(frequency) = self.frequencies.get(i) {
+                hash = fnv_hash(hash, &frequency.to_bits().to_le_bytes());
+            }
+            hash = fnv_hash(hash, b"\n");
+        }
+        return format!("{}-{:016x}", self.answers.len(), hash);
+    }
+
+    /// Check if an answer fits a difficulty
+    pub fn fits_difficulty(&self, index: usize, difficulty: Difficulty) -> bool {
+        let (Some(median), Some(frequency)) = (self.median_frequency, self.frequencies.get(index))
+        else {
+            return true;
+        };
+        return match difficulty {
+            Difficulty::Any => true,
+            Difficulty::Easy => *frequency >= median,
+            Difficulty::Hard => *frequency < median,
+        };
+    }
+
+    /// Generate a random seed whose answer fits a difficulty and isn't one of the recent
+    /// answers given. The seed still gives the same answer to anyone with the same list.
+    pub fn random_seed(&self, difficulty: Difficulty, recent: &[String]) -> u64 {
+        let mut seed: u64 = new_seed();
+        for _ in 0..SEED_TRIES {
+            let index: usize = self.pick_answer(seed);
+            if self.fits_difficulty(index, difficulty) && !recent.contains(&self.answers[index]) {
+                break;
+            }
+            seed = new_seed();
+        }
+        return seed;
+    }
+}
+
+/// Load a list of words of a given length, one per line. A word can be followed by how
+/// common it is, as a number after a space or tab.
+fn load_list(path: &str, word_length: usize) -> Result<Vec<(String, Option<f64>)>, OptionsError> {
+    return match read_to_string(path) {
+        Ok(text) => Ok(parse_list(&text, word_length)),
+        Err(_) => Err(OptionsError::ListNotFound(path.to_string())),
+    };
+}
+
+/// Read the words of a given length from the text of a word list, with their frequencies
+fn parse_list(text: &str, word_length: usize) -> Vec<(String, Option<f64>)> {
+    return text
+        .lines()
+        .filter_map(|line| {
+            let mut fields: std::str::SplitWhitespace = line.split_whitespace();
+            let word: String = fields.next()?.to_lowercase();
+            let frequency: Option<f64> = match fields.next() {
+                Some(field) => Some(field.parse::<f64>().ok().filter(|f| f.is_finite())?),
+                None => None,
+            };
+            if fields.next().is_some() {
+                return None;
+            }
+            Some((word, frequency))
+        })
+        .filter(|(word, _)| {
+            word.chars().count() == word_length && word.chars().all(|c| LOWERCASE.contains(c))
+        })
+        .collect();
+}
+
+/// Generate a random seed for a new game
+pub fn new_seed() -> u64 {
+    return rng().random_range(0..SEED_MAX);
+}
+
+/// Get the seed of the daily game for a date
+pub fn daily_seed(date: Date) -> u64 {
+    return date.year as u64 * 10000 + date.month as u64 * 100 + date.day as u64;
+}
+
+/// Pick the index of the answer for a seed. The same seed and answer list always give the same answer.
+pub fn pick_answer(answers: &[String], seed: u64) -> usize {
+    let mut seeded_rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(seed);
+    return seeded_rng.random_range(0..answers.len());
+}
+
+/// Add bytes to an FNV-1a hash, which unlike the standard library's hasher is the same on every
+/// machine and Rust version
+fn fnv_hash(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash = (hash ^ *byte as u64).wrapping_mul(0x100000001b3);
+    }
+    return hash;
+}
+
+/// Check a guess
+pub fn get_guess_status(
+    guess: &[char],
+    target_slice: &str,
+    output: &mut [usize],
+    letter_states: &mut HashMap<char, usize>, // grays: &mut Vec<char>
+) -> bool {
+    let target: String = target_slice.chars().collect();
+    let lowercase_letters: String = String::from(LOWERCASE);
+    let mut wins: usize = 0;
+    let mut green_count_map: HashMap<char, usize> = HashMap::new();
+    let mut yellow_count_map: HashMap<char, usize> = HashMap::new();
+    let mut total_count_map: HashMap<char, usize> = HashMap::new();
+    for c in lowercase_letters.chars() {
+        green_count_map.insert(c, 0);
+        yellow_count_map.insert(c, 0);
+        total_count_map.insert(c, target.chars().filter(|&ch| ch == c).count());
+    }
+    for i in 0..guess.len() {
+        let c: char = guess[i];
+        if target.chars().nth(i) == Some(c) {
+            output[i] = COLOR_GREEN;
+            wins += 1;
+            *green_count_map.get_mut(&c).unwrap() += 1;
+            letter_states.insert(c, COLOR_GREEN);
+        }
+    }
+    for i in 0..guess.len() {
+        let c: char = guess[i];
+        if target.chars().nth(i) != Some(c) {
+            if green_count_map[&c] + yellow_count_map[&c] < total_count_map[&c] {
+                output[i] = COLOR_YELLOW;
+                *yellow_count_map.get_mut(&c).unwrap() += 1;
+                if letter_states[&c] < COLOR_YELLOW {
+                    letter_states.insert(c, COLOR_YELLOW);
+                }
+            } else {
+                output[i] = COLOR_GRAY;
+                if !target.contains(c) && letter_states[&c] < COLOR_GRAY {
+                    letter_states.insert(c, COLOR_GRAY);
+                }
+            }
+        }
+    }
+    return wins == guess.len();
+}
+
+/// Score a guess the same way as `get_guess_status`, without allocating, for solvers that score
+/// many words. Both words must be lowercase ASCII of the same length.
+pub fn score_pattern(guess: &[u8], answer: &[u8]) -> u32 {
+    let mut unmatched: [u8; 26] = [0; 26];
+    let mut colors: [usize; MAX_WORD_LENGTH] = [COLOR_GRAY; MAX_WORD_LENGTH];
+    for i in 0..guess.len() {
+        if guess[i] == answer[i] {
+            colors[i] = COLOR_GREEN;
+        } else {
+            unmatched[(answer[i] - b'a') as usize] += 1;
+        }
+    }
+    for i in 0..guess.len() {
+        let c: usize = (guess[i] - b'a') as usize;
+        if colors[i] != COLOR_GREEN && unmatched[c] > 0 {
+            colors[i] = COLOR_YELLOW;
+            unmatched[c] -= 1;
+        }
+    }
+    return pattern_code(&colors[..guess.len()]);
+}
+
+/// Pack the colors of a scored row into one number, so patterns can be compared and counted quickly
+pub fn pattern_code(colors: &[usize]) -> u32 {
+    return colors.iter().fold(0, |code: u32, &color: &usize| {
+        code * 3
+            + match color {
+                COLOR_GREEN => 2,
+                COLOR_YELLOW => 1,
+                _ => 0,
+            }
+    });
+}
+
+/// Write the colors of a scored row as letters: G for green, Y for yellow and B for gray
+pub fn pattern_string(colors: &[usize]) -> String {
+    return colors
+        .iter()
+        .map(|&color| match color {
+            COLOR_GREEN => 'G',
+            COLOR_YELLOW => 'Y',
+            COLOR_GRAY => 'B',
+            _ => '.',
+        })
+        .collect();
+}
+
+/// A hint from an earlier row that a guess ignores in hard mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HardModeViolation {
+    /// A letter found in the right place has to stay there. The position counts from 0.
+    Misplaced(usize, char),
+    /// A letter found in the word has to be used again
+    Missing(char),
+}
+
+impl Display for HardModeViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            HardModeViolation::Misplaced(position, letter) => write!(
+                f,
+                "Letter {} must be {}",
+                position + 1,
+                letter.to_uppercase()
+            ),
+            HardModeViolation::Missing(letter) => {
+                write!(f, "Guess must contain {}", letter.to_uppercase())
+            }
+        };
+    }
+}
+
+/// Check that a guess uses every hint from the previous rows, as hard mode requires
+pub fn check_hard_mode(
+    guess: &[char],
+    board_chars: &[Vec<char>],
+    board_colors: &[Vec<usize>],
+) -> Result<(), HardModeViolation> {
+    let mut required_counts: HashMap<char, usize> = HashMap::new();
+    for (row_chars, row_colors) in board_chars.iter().zip(board_colors) {
+        let mut row_counts: HashMap<char, usize> = HashMap::new();
+        for i in 0..guess.len() {
+            if row_colors[i] == COLOR_GREEN && guess[i] != row_chars[i] {
+                return Err(HardModeViolation::Misplaced(i, row_chars[i]));
+            }
+            if row_colors[i] == COLOR_GREEN || row_colors[i] == COLOR_YELLOW {
+                *row_counts.entry(row_chars[i]).or_insert(0) += 1;
+            }
+        }
+        for (c, count) in row_counts {
+            let required: &mut usize = required_counts.entry(c).or_insert(0);
+            *required = (*required).max(count);
+        }
+    }
+    for c in LOWERCASE.chars() {
+        if let Some(required) = required_counts.get(&c)
+            && guess.iter().filter(|&&ch| ch == c).count() < *required
+        {
+            return Err(HardModeViolation::Missing(c));
+        }
+    }
+    return Ok(());
+}
+
+/// What happened when a guess was submitted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GuessResult {
+    /// The row isn't full yet
+    Incomplete,
+    /// The guess isn't in the word list
+    InvalidWord,
+    /// The guess ignores a hint while in hard mode
+    HardModeViolation(HardModeViolation),
+    /// The guess was scored and the game goes on
+    Continue,
+    /// The guess was the answer
+    Won,
+    /// The guess was wrong and there are no guesses left
+    Lost,
+}
+
+/// The state of one game
+#[derive(Clone, Debug)]
+pub struct Game {
+    pub mode: GameMode,
+    pub hard_mode: bool,
+    pub word_length: usize,
+    pub max_guesses: usize,
+    pub seed: u64,
+    pub answer: String,
+    pub board_chars: Vec<Vec<char>>,
+    pub board_colors: Vec<Vec<usize>>,
+    pub letter_states: HashMap<char, usize>,
+    /// The selected column of the current row, equal to the word length past the end
+    pub cur_x: usize,
+    /// The row being guessed
+    pub guess: usize,
+    /// Set once the game is over
+    pub locked: bool,
+    pub won: bool,
+    /// Set if the clock ran out before the game was won
+    pub timed_out: bool,
+    /// When the game started, in seconds since 1970-01-01 UTC
+    pub started: u64,
+}
+
+impl Game {
+    /// Start a game with the answer picked by a seed
+    pub fn new(options: &GameOptions, lists: &WordLists, seed: u64) -> Game {
+        let answer: String = lists.answers[lists.pick_answer(seed)].clone();
+        return Game::with_answer(options, answer, seed);
+    }
+
+    /// Start a game with a known answer
+    pub fn with_answer(options: &GameOptions, answer: String, seed: u64) -> Game {
+        let mut letter_states: HashMap<char, usize> = HashMap::new();
+        for c in LOWERCASE.chars() {
+            letter_states.insert(c, COLOR_UNSET);
+        }
+        return Game {
+            mode: options.mode,
+            hard_mode: options.hard_mode,
+            word_length: options.word_length,
+            max_guesses: options.max_guesses,
+            seed,
+            answer,
+            board_chars: vec![vec![' '; options.word_length]; options.max_guesses],
+            board_colors: vec![vec![COLOR_UNSET; options.word_length]; options.max_guesses],
+            letter_states,
+            cur_x: 0,
+            guess: 0,
+            locked: false,
+            won: false,
+            timed_out: false,
+            started: unix_time(),
+        };
+    }
+
+    /// Type a letter over the selected tile and select the next one, returning whether it was
+    /// placed. The cursor is past the end of the row when it equals the word length.
+    pub fn type_letter(&mut self, c: char) -> bool {
+        if self.locked || self.cur_x >= self.word_length || !LOWERCASE.contains(c) {
+            return false;
+        }
+        self.board_chars[self.guess][self.cur_x] = c;
+        self.cur_x += 1; // @rust you stupid language add ++ operator
+        return true;
+    }
+
+    /// Replace the current row with a whole word, returning whether every letter could be placed
+    pub fn enter_word(&mut self, word: &str) -> bool {
+        if self.locked {
+            return false;
+        }
+        self.board_chars[self.guess].fill(' ');
+        self.cur_x = 0;
+        return word.chars().all(|c| self.type_letter(c)) && self.cur_x == self.word_length;
+    }
+
+    /// Erase the letter before the cursor and move back onto it, returning whether the cursor
+    /// moved
+    pub fn backspace(&mut self) -> bool {
+        if self.locked || self.cur_x == 0 {
+            return false;
+        }
+        self.board_chars[self.guess][self.cur_x - 1] = ' ';
+        self.cur_x -= 1;
+        return true;
+    }
+
+    /// Erase the selected letter without moving, returning whether there was one
+    pub fn delete(&mut self) -> bool {
+        if self.locked
+            || self.cur_x >= self.word_length
+            || self.board_chars[self.guess][self.cur_x] == ' '
+        {
+            return false;
+        }
+        self.board_chars[self.guess][self.cur_x] = ' ';
+        return true;
+    }
+
+    /// Select a tile in the current row, or past the end of it, returning whether the cursor moved
+    pub fn move_cursor(&mut self, x: usize) -> bool {
+        let x: usize = x.min(self.word_length);
+        if self.locked || x == self.cur_x {
+            return false;
+        }
+        self.cur_x = x;
+        return true;
+    }
+
+    /// Get the position just after the last letter of the current row
+    pub fn row_end(&self) -> usize {
+        return self.board_chars[self.guess]
+            .iter()
+            .rposition(|&c| c != ' ')
+            .map_or(0, |x| x + 1);
+    }
+
+    /// Whether every tile of the current row has a letter
+    pub fn row_complete(&self) -> bool {
+        return self.board_chars[self.guess].iter().all(|&c| c != ' ');
+    }
+
+    /// Score the current row against the answer
+    pub fn submit(&mut self, words: &[String]) -> GuessResult {
+        if !self.row_complete() {
+            return GuessResult::Incomplete;
+        }
+        let guess_str: String = String::from_iter(self.board_chars[self.guess].iter());
+        if !words.contains(&guess_str) {
+            return GuessResult::InvalidWord;
+        }
+        if self.hard_mode
+            && let Err(violation) = check_hard_mode(
+                &self.board_chars[self.guess],
+                &self.board_chars[..self.guess],
+                &self.board_colors[..self.guess],
+            )
+        {
+            return GuessResult::HardModeViolation(violation);
+        }
+        let winner: bool = get_guess_status(
+            &self.board_chars[self.guess],
+            self.answer.as_str(),
+            &mut self.board_colors[self.guess],
+            &mut self.letter_states,
+        );
+        if winner {
+            self.locked = true;
+            self.won = true;
+            return GuessResult::Won;
+        } else if self.guess == self.max_guesses - 1 {
+            self.locked = true;
+            return GuessResult::Lost;
+        }
+        self.guess += 1;
+        self.cur_x = 0;
+        return GuessResult::Continue;
+    }
+
+    /// End the game as lost because the clock ran out, throwing away the unscored row
+    pub fn time_out(&mut self) -> () {
+        if self.locked {
+            return;
+        }
+        self.board_chars[self.guess].fill(' ');
+        self.cur_x = 0;
+        self.locked = true;
+        self.timed_out = true;
+    }
+
+    /// Get the number of guesses that have been scored
+    pub fn guesses_used(&self) -> usize {
+        if self.locked && !self.timed_out {
+            return self.guess + 1;
+        }
+        return self.guess;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Score a guess against an answer, returning the colors and whether it was right
+    fn score(guess: &str, answer: &str) -> (Vec<usize>, bool) {
+        let guess: Vec<char> = guess.chars().collect();
+        let mut colors: Vec<usize> = vec![COLOR_UNSET; guess.len()];
+        let mut letter_states: HashMap<char, usize> =
+            LOWERCASE.chars().map(|c| (c, COLOR_UNSET)).collect();
+        let won: bool = get_guess_status(&guess, answer, &mut colors, &mut letter_states);
+        return (colors, won);
+    }
+
+    #[test]
+    fn guesses_are_scored() -> () {
+        assert_eq!(score("crane", "crane"), (vec![COLOR_GREEN; 5], true));
+        assert_eq!(
+            score("react", "crane"),
+            (
+                vec![
+                    COLOR_YELLOW,
+                    COLOR_YELLOW,
+                    COLOR_GREEN,
+                    COLOR_YELLOW,
+                    COLOR_GRAY
+                ],
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn repeated_letters_are_only_counted_once() -> () {
+        // The answer has one E, already found by the green one
+        let (colors, _): (Vec<usize>, bool) = score("geese", "those");
+        assert_eq!(
+            colors,
+            vec![COLOR_GRAY, COLOR_GRAY, COLOR_GRAY, COLOR_GREEN, COLOR_GREEN]
+        );
+        // Only the first of two misplaced Ls is yellow
+        let (colors, _): (Vec<usize>, bool) = score("llama", "hello");
+        assert_eq!(
+            colors,
+            vec![
+                COLOR_YELLOW,
+                COLOR_YELLOW,
+                COLOR_GRAY,
+                COLOR_GRAY,
+                COLOR_GRAY
+            ]
+        );
+        // Agrees with the solvers' scoring
+        assert_eq!(pattern_code(&colors), score_pattern(b"llama", b"hello"));
+    }
+
+    #[test]
+    fn hard_mode_needs_every_hint() -> () {
+        let board_chars: Vec<Vec<char>> = vec!["crane".chars().collect()];
+        let board_colors: Vec<Vec<usize>> = vec![vec![
+            COLOR_GREEN,
+            COLOR_GRAY,
+            COLOR_YELLOW,
+            COLOR_GRAY,
+            COLOR_GRAY,
+        ]];
+        let check = |guess: &str| -> Result<(), HardModeViolation> {
+            let guess: Vec<char> = guess.chars().collect();
+            return check_hard_mode(&guess, &board_chars, &board_colors);
+        };
+        assert_eq!(check("cloak"), Ok(()));
+        assert_eq!(check("black"), Err(HardModeViolation::Misplaced(0, 'c')));
+        assert_eq!(check("coils"), Err(HardModeViolation::Missing('a')));
+        assert_eq!(
+            HardModeViolation::Misplaced(0, 'c').to_string(),
+            "Letter 1 must be C"
+        );
+        assert_eq!(
+            check_hard_mode(&['a', 'b'], &[], &[]),
+            Ok(()),
+            "the first guess can be anything"
+        );
+    }
+
+    #[test]
+    fn fingerprints_tell_lists_apart() -> () {
+        let lists: WordLists = WordLists::from_answers("crane\nslate\n");
+        assert!(lists.fingerprint().starts_with("2-"));
+        assert_eq!(
+            lists.fingerprint(),
+            WordLists::from_answers("crane\nslate\n").fingerprint()
+        );
+        for other in [
+            "slate\ncrane\n",
+            "crane\nslate\nfjord\n",
+            "crane 2\nslate 1\n",
+        ] {
+            assert_ne!(
+                lists.fingerprint(),
+                WordLists::from_answers(other).fingerprint()
+            );
+        }
+    }
+
+    #[test]
+    fn seeds_are_taken_once() -> () {
+        let lists: WordLists = WordLists::from_answers("crane\nslate\nfjord\n");
+        let today: Date = Date::new(2026, 10, 19).unwrap();
+        let mut options: GameOptions = GameOptions {
+            mode: GameMode::Daily,
+            ..GameOptions::default()
+        };
+        assert_eq!(options.take_seed(today, &lists, &[]), 20261019);
+
+        options.mode = GameMode::Practice;
+        options.seed = Some(123456);
+        assert_eq!(options.take_seed(today, &lists, &[]), 123456);
+        assert_eq!(options.seed, None);
+        assert!(options.take_seed(today, &lists, &[]) < SEED_MAX);
+
+        // The same seed always gives the same answer
+        let first: Game = Game::new(&options, &lists, 123456);
+        let second: Game = Game::new(&options, &lists, 123456);
+        assert_eq!(first.answer, second.answer);
+    }
+
+    #[test]
+    fn answer_lists_can_have_frequencies() -> () {
+        let words: WordLists = WordLists::from_answers(
+            "Crane 1000\nslate\t2.5\nfjord 1 extra\nquirk abc\nbrick -inf\nplumb\n",
+        );
+        assert_eq!(words.answers, vec!["crane", "slate", "plumb"]);
+        // Answers without a frequency count as the rarest
+        assert_eq!(words.frequencies, vec![1000.0, 2.5, 0.0]);
+        assert!(words.fits_difficulty(0, Difficulty::Easy));
+        assert!(words.fits_difficulty(2, Difficulty::Hard));
+        assert!(!words.fits_difficulty(2, Difficulty::Easy));
+
+        let plain: WordLists = WordLists::from_answers("crane\nslate\n");
+        assert!(plain.frequencies.is_empty());
+        assert!(plain.fits_difficulty(0, Difficulty::Hard));
+        assert_eq!(plain.pick_answer(42), pick_answer(&plain.answers, 42));
+    }
+
+    #[test]
+    fn flat_frequencies_fit_every_difficulty() -> () {
+        for text in ["crane 5\nslate 5\nfjord 5\n", "crane 5\n"] {
+            let words: WordLists = WordLists::from_answers(text);
+            for difficulty in Difficulty::ALL {
+                assert!(words.fits_difficulty(0, difficulty));
+            }
+        }
+        let words: WordLists = WordLists::from_answers("crane 5\nslate 5\nfjord 5\n");
+        let answers: Vec<usize> = (0..50)
+            .map(|_| words.pick_answer(words.random_seed(Difficulty::Hard, &[])))
+            .collect();
+        assert!(answers.iter().any(|&answer| answer != answers[0]));
+    }
+
+    #[test]
+    fn random_seeds_avoid_recent_answers() -> () {
+        let words: WordLists = WordLists::from_answers("crane 1000\nslate 500\nfjord 1\nquirk 1\n");
+        let recent: Vec<String> = vec![String::from("crane"), String::from("fjord")];
+        for _ in 0..50 {
+            let answer: &str =
+                &words.answers[words.pick_answer(words.random_seed(Difficulty::Any, &recent))];
+            assert!(
+                answer == "slate" || answer == "quirk",
+                "{} came up again",
+                answer
+            );
+            let answer: &str =
+                &words.answers[words.pick_answer(words.random_seed(Difficulty::Hard, &recent))];
+            assert_eq!(answer, "quirk");
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use gettextrs::gettext;
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4::accessible::Property;
+use gtk4::Orientation::{Horizontal, Vertical};
+use gtk4::{
+    Align, Box, Label, License, ScrolledWindow, ShortcutsGroup, ShortcutsSection,
+    ShortcutsShortcut, ShortcutsWindow, Widget, Window,
+};
+use libadwaita::prelude::AdwDialogExt;
+use libadwaita::{AboutDialog, Dialog, HeaderBar, ToolbarView};
+use rustle::text::n_;
+
+/// Where the list of valid guesses comes from
+const WORD_LIST_SOURCE: &str = "https://gist.github.com/dracos/dd0668f281e685bad51479e5acaadb93";
+
+/// Keyboard shortcuts shown in the shortcuts window, as (accelerator, title) pairs in groups.
+/// `a...z` is the shortcuts window's syntax for a range of keys.
+const SHORTCUT_GROUPS: [(&str, &[(&str, &str)]); 2] = [
+    (
+        n_("Playing"),
+        &[
+            ("a...z", n_("Type over the selected letter")),
+            ("BackSpace", n_("Erase the letter before the cursor")),
+            ("Delete", n_("Erase the selected letter")),
+            ("Left Right", n_("Move the cursor")),
+            ("Home End", n_("Move to the start or end of the row")),
+            ("Return", n_("Submit a guess")),
+        ],
+    ),
+    (
+        n_("General"),
+        &[
+            ("<Control>n", n_("New game")),
+            ("<Control>h", n_("History")),
+            ("<Control>comma", n_("Preferences")),
+            ("F1", n_("How to play")),
+            ("<Control>question", n_("Keyboard shortcuts")),
+            ("<Control>w", n_("Close window")),
+            ("<Control>q", n_("Quit")),
+        ],
+    ),
+];
+
+/// Create the window listing the keyboard shortcuts
+pub fn build_shortcuts_window(parent: &impl IsA<Window>) -> ShortcutsWindow {
+    let section: ShortcutsSection = ShortcutsSection::builder()
+        .section_name("shortcuts")
+        .build();
+    for (title, shortcuts) in SHORTCUT_GROUPS {
+        let group: ShortcutsGroup = ShortcutsGroup::builder().title(gettext(title)).build();
+        for (accelerator, title) in shortcuts {
+            let shortcut: ShortcutsShortcut = ShortcutsShortcut::builder()
+                .title(gettext(*title))
+                .accelerator(*accelerator)
+                .build();
+            group.add_shortcut(&shortcut);
+        }
+        section.add_group(&group);
+    }
+    let window: ShortcutsWindow = ShortcutsWindow::builder()
+        .transient_for(parent)
+        .modal(true)
+        .build();
+    window.add_section(&section);
+    return window;
+}
+
+/// Create a row of example tiles, with a color class for each letter or None for an unscored one
+fn example_row(word: &str, colors: &[Option<&str>], description: &str) -> Box {
+    let row: Box = Box::new(Horizontal, 4);
+    row.set_halign(Align::Center);
+    for (c, color) in word.chars().zip(colors) {
+        let tile: Label = Label::new(Some(&c.to_uppercase().to_string()));
+        tile.set_size_request(48, 48);
+        tile.add_css_class("tile");
+        if let Some(color) = color {
+            tile.add_css_class(color);
+        }
+        row.append(&tile);
+    }
+    row.update_property(&[Property::Label(description)]);
+    return row;
+}
+
+/// Create a paragraph of the rules
+fn paragraph(text: &str) -> Label {
+    let label: Label = Label::new(Some(text));
+    label.set_wrap(true);
+    label.set_xalign(0.0);
+    label.set_max_width_chars(40);
+    return label;
+}
+
+/// Create the content of the How to Play page
+fn how_to_play_content() -> Widget {
+    let content: Box = Box::new(Vertical, 12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(24);
+    content.set_margin_start(24);
+    content.set_margin_end(24);
+
+    content.append(&paragraph(&gettext(
+        "Guess the hidden word. Each guess must be a word from the word list, and after each \
+         guess the tiles change color to show how close it was.",
+    )));
+
+    let examples: [(&str, [Option<&str>; 5], String); 3] = [
+        (
+            "crane",
+            [Some("green"), None, None, None, None],
+            gettext("C is in the word and in the right place."),
+        ),
+        (
+            "pilot",
+            [None, None, Some("yellow"), None, None],
+            gettext("L is in the word but in the wrong place."),
+        ),
+        (
+            "vague",
+            [None, None, None, Some("gray"), None],
+            gettext("U is not in the word anywhere."),
+        ),
+    ];
+    for (word, colors, description) in examples {
+        content.append(&example_row(word, &colors, &description));
+        content.append(&paragraph(&description));
+    }
+
+    content.append(&paragraph(&gettext(
+        "A letter only lights up as many times as it appears in the word. In hard mode, every \
+         hint you've been shown must be used in later guesses.",
+    )));
+    content.append(&paragraph(&gettext(
+        "The daily game has the same word for everyone each day. Practice games have a seed \
+         that can be shared to play the same word again.",
+    )));
+
+    let scrolled_window: ScrolledWindow = ScrolledWindow::builder()
+        .child(&content)
+        .propagate_natural_height(true)
+        .build();
+    return scrolled_window.upcast();
+}
+
+/// Show the rules, with example rows of colored tiles
+pub fn show_how_to_play(parent: &impl IsA<Widget>) -> () {
+    let toolbar_view: ToolbarView = ToolbarView::new();
+    toolbar_view.add_top_bar(&HeaderBar::new());
+    toolbar_view.set_content(Some(&how_to_play_content()));
+    let dialog: Dialog = Dialog::builder()
+        .title(gettext("How to Play"))
+        .content_width(420)
+        .child(&toolbar_view)
+        .build();
+    dialog.present(Some(parent));
+}
+
+/// Show the about dialog
+pub fn show_about(parent: &impl IsA<Widget>) -> () {
+    let dialog: AboutDialog = AboutDialog::builder()
+        .application_name("Rustle!")
+        .application_icon("dev.droc101.rustle")
+        .developer_name("droc101")
+        .version(env!("CARGO_PKG_VERSION"))
+        .comments(gettext(
+            "Wordle clone written in Rust using GTK4 and libadwaita",
+        ))
+        .license_type(License::MitX11)
+        .copyright("© 2025 droc101")
+        .build();
+    // Translators: replace with your names, one per line
+    let translators: String = gettext("translator-credits");
+    if translators != "translator-credits" {
+        dialog.set_translator_credits(&translators);
+    }
+    dialog.add_credit_section(
+        Some(&gettext("Word List")),
+        &[&format!("dracos {}", WORD_LIST_SOURCE)],
+    );
+    dialog.present(Some(parent));
+}
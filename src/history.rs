@@ -0,0 +1,322 @@
+use crate::date::{unix_time, Date};
+use crate::game::{
+    pattern_string, Game, GameMode, GameOptions, COLOR_GRAY, COLOR_GREEN, COLOR_UNSET, COLOR_YELLOW,
+};
+use crate::storage::data_dir;
+use std::fs::{create_dir_all, read_to_string, write, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// A finished game, as kept in the history
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameRecord {
+    /// The local date the game finished on
+    pub date: Date,
+    /// When the game started, in seconds since 1970-01-01 UTC
+    pub started: u64,
+    /// When the game finished, in seconds since 1970-01-01 UTC
+    pub finished: u64,
+    pub mode: GameMode,
+    pub hard_mode: bool,
+    pub seed: u64,
+    pub answer: String,
+    pub max_guesses: usize,
+    pub won: bool,
+    /// Every guess, with the colors it was given
+    pub guesses: Vec<(String, Vec<usize>)>,
+}
+
+/// Read the colors of a scored row written by `pattern_string`
+pub fn parse_pattern(pattern: &str) -> Vec<usize> {
+    return pattern
+        .chars()
+        .map(|c| match c {
+            'G' => COLOR_GREEN,
+            'Y' => COLOR_YELLOW,
+            'B' => COLOR_GRAY,
+            _ => COLOR_UNSET,
+        })
+        .collect();
+}
+
+impl GameRecord {
+    /// Make a record of a game that just finished
+    pub fn from_game(game: &Game, today: Date) -> GameRecord {
+        let guesses: Vec<(String, Vec<usize>)> = (0..game.guesses_used())
+            .map(|row| {
+                (
+                    game.board_chars[row].iter().collect(),
+                    game.board_colors[row].clone(),
+                )
+            })
+            .collect();
+        return GameRecord {
+            date: today,
+            started: game.started,
+            finished: unix_time(),
+            mode: game.mode,
+            hard_mode: game.hard_mode,
+            seed: game.seed,
+            answer: game.answer.clone(),
+            max_guesses: game.max_guesses,
+            won: game.won,
+            guesses,
+        };
+    }
+
+    /// Get how long the game took, in seconds
+    pub fn duration(&self) -> u64 {
+        return self.finished.saturating_sub(self.started);
+    }
+
+    /// Rebuild the game as it was after a number of guesses, to show it again
+    pub fn replay(&self, guesses: usize) -> Game {
+        let options: GameOptions = GameOptions {
+            mode: self.mode,
+            word_length: self.answer.chars().count(),
+            max_guesses: self.max_guesses,
+            // The guesses were already checked when they were played
+            hard_mode: false,
+            ..GameOptions::default()
+        };
+        let mut game: Game = Game::with_answer(&options, self.answer.clone(), self.seed);
+        game.started = self.started;
+        for (guess, _) in self.guesses.iter().take(guesses) {
+            game.enter_word(guess);
+            game.submit(std::slice::from_ref(guess));
+        }
+        game.hard_mode = self.hard_mode;
+        return game;
+    }
+
+    /// Write the record as one line of tab-separated fields
+    pub fn to_line(&self) -> String {
+        let guesses: Vec<String> = self
+            .guesses
+            .iter()
+            .map(|(guess, colors)| format!("{}:{}", guess, pattern_string(colors)))
+            .collect();
+        return [
+            self.date.to_string(),
+            self.started.to_string(),
+            self.finished.to_string(),
+            self.mode.name().to_string(),
+            self.hard_mode.to_string(),
+            self.seed.to_string(),
+            self.answer.clone(),
+            self.max_guesses.to_string(),
+            self.won.to_string(),
+            guesses.join(","),
+        ]
+        .join("\t");
+    }
+
+    /// Read a record written by `to_line`
+    pub fn from_line(line: &str) -> Option<GameRecord> {
+        let fields: Vec<&str> = line.trim_end_matches(['\r', '\n']).split('\t').collect();
+        if fields.len() != 10 {
+            return None;
+        }
+        let mut guesses: Vec<(String, Vec<usize>)> = Vec::new();
+        for guess in fields[9].split(',').filter(|guess| !guess.is_empty()) {
+            let (word, pattern) = guess.split_once(':')?;
+            guesses.push((word.to_string(), parse_pattern(pattern)));
+        }
+        return Some(GameRecord {
+            date: Date::parse(fields[0])?,
+            started: fields[1].parse().ok()?,
+            finished: fields[2].parse().ok()?,
+            mode: GameMode::from_name(fields[3])?,
+            hard_mode: fields[4].parse().ok()?,
+            seed: fields[5].parse().ok()?,
+            answer: fields[6].to_string(),
+            max_guesses: fields[7].parse().ok()?,
+            won: fields[8].parse().ok()?,
+            guesses,
+        });
+    }
+}
+
+/// Which games to show from the history
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HistoryFilter {
+    /// Only games finished on or after this date
+    pub since: Option<Date>,
+    pub mode: Option<GameMode>,
+    /// Only won games, or only lost ones
+    pub won: Option<bool>,
+}
+
+impl HistoryFilter {
+    /// Check if a game passes the filter
+    pub fn matches(&self, record: &GameRecord) -> bool {
+        return self.since.is_none_or(|since| record.date >= since)
+            && self.mode.is_none_or(|mode| record.mode == mode)
+            && self.won.is_none_or(|won| record.won == won);
+    }
+}
+
+/// Every finished game, oldest first
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    pub games: Vec<GameRecord>,
+}
+
+impl History {
+    /// Get the file the history is saved in
+    pub fn path() -> PathBuf {
+        return data_dir().join("history.txt");
+    }
+
+    /// Load the saved history, skipping any lines that can't be read
+    pub fn load() -> History {
+        let games: Vec<GameRecord> = match read_to_string(History::path()) {
+            Ok(text) => text.lines().filter_map(GameRecord::from_line).collect(),
+            Err(_) => Vec::new(),
+        };
+        return History { games };
+    }
+
+    /// Save the whole history
+    pub fn save(&self) -> io::Result<()> {
+        let path: PathBuf = History::path();
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let mut text: String = String::new();
+        for record in &self.games {
+            text.push_str(&record.to_line());
+            text.push('\n');
+        }
+        return write(path, text);
+    }
+
+    /// Add a game to the end of the saved history, without rewriting the rest
+    pub fn append(record: &GameRecord) -> io::Result<()> {
+        let path: PathBuf = History::path();
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let mut file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        return writeln!(file, "{}", record.to_line());
+    }
+
+    /// Get the answers of the last few games, newest first
+    pub fn recent_answers(&self, count: usize) -> Vec<String> {
+        return self
+            .games
+            .iter()
+            .rev()
+            .take(count)
+            .map(|record| record.answer.clone())
+            .collect();
+    }
+
+    /// Get the games that pass a filter, newest first
+    pub fn filtered(&self, filter: &HistoryFilter) -> Vec<&GameRecord> {
+        return self
+            .games
+            .iter()
+            .rev()
+            .filter(|record| filter.matches(record))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Make a record of a lost hard mode game with two guesses
+    fn record() -> GameRecord {
+        return GameRecord {
+            date: Date::new(2026, 10, 19).unwrap(),
+            started: 1_792_400_000,
+            finished: 1_792_400_095,
+            mode: GameMode::Daily,
+            hard_mode: true,
+            seed: 20261019,
+            answer: String::from("crane"),
+            max_guesses: 2,
+            won: false,
+            guesses: vec![
+                (
+                    String::from("slate"),
+                    vec![COLOR_GRAY, COLOR_GRAY, COLOR_GREEN, COLOR_GRAY, COLOR_GREEN],
+                ),
+                (
+                    String::from("trace"),
+                    vec![
+                        COLOR_GRAY,
+                        COLOR_GREEN,
+                        COLOR_GREEN,
+                        COLOR_YELLOW,
+                        COLOR_GREEN,
+                    ],
+                ),
+            ],
+        };
+    }
+
+    #[test]
+    fn records_round_trip() -> () {
+        let record: GameRecord = record();
+        let line: String = record.to_line();
+        assert!(line.starts_with("2026-10-19\t1792400000\t1792400095\tdaily\ttrue\t"));
+        assert!(line.ends_with("\tslate:BBGBG,trace:BGGYG"));
+        assert_eq!(GameRecord::from_line(&line), Some(record.clone()));
+        assert_eq!(
+            GameRecord::from_line(&format!("{}\r\n", line)),
+            Some(record.clone())
+        );
+        assert_eq!(record.duration(), 95);
+
+        let unplayed: GameRecord = GameRecord {
+            guesses: Vec::new(),
+            ..record
+        };
+        assert_eq!(GameRecord::from_line(&unplayed.to_line()), Some(unplayed));
+    }
+
+    #[test]
+    fn bad_lines_are_skipped() -> () {
+        let line: String = record().to_line();
+        assert_eq!(GameRecord::from_line(""), None);
+        assert_eq!(
+            GameRecord::from_line(&line.replace("daily", "weekly")),
+            None
+        );
+        assert_eq!(GameRecord::from_line(&line.replace("true", "yes")), None);
+        assert_eq!(
+            GameRecord::from_line(&line.replace("slate:", "slate")),
+            None
+        );
+        assert_eq!(GameRecord::from_line(&format!("{}\textra", line)), None);
+        assert_eq!(
+            GameRecord::from_line(&line.replacen("2026-10-19", "2026-13-01", 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn records_replay() -> () {
+        let record: GameRecord = record();
+        let game: Game = record.replay(1);
+        assert_eq!(game.guess, 1);
+        assert!(!game.locked);
+        assert_eq!(game.board_chars[0], vec!['s', 'l', 'a', 't', 'e']);
+        assert_eq!(game.board_colors[0], record.guesses[0].1);
+        assert_eq!(game.board_colors[1], vec![COLOR_UNSET; 5]);
+        assert_eq!(game.started, record.started);
+        assert!(game.hard_mode);
+
+        let game: Game = record.replay(record.guesses.len());
+        assert!(game.locked);
+        assert!(!game.won);
+        assert_eq!(game.board_colors[1], record.guesses[1].1);
+        assert_eq!(
+            GameRecord::from_game(&game, record.date).guesses,
+            record.guesses
+        );
+    }
+}
@@ -0,0 +1,368 @@
+use crate::i18n::fill;
+use crate::settings::AppSettings;
+use crate::window::{
+    build_grid, build_keyboard, definitions_text, resize_grid, resize_keyboard, update_board,
+    update_keyboard, KeyboardLayout,
+};
+use gettextrs::{gettext, ngettext};
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4::gdk::{Key, ModifierType};
+use gtk4::glib::{self, Propagation, WeakRef};
+use gtk4::Orientation::{Horizontal, Vertical};
+use gtk4::{
+    Align, Box, Button, DropDown, EventControllerKey, Grid, Label, ListBox, ListBoxRow,
+    ScrolledWindow, SelectionMode, Stack, Widget,
+};
+use libadwaita::prelude::{ActionRowExt, AdwDialogExt};
+use libadwaita::{
+    ActionRow, Dialog, HeaderBar, NavigationPage, NavigationView, StatusPage, ToolbarView,
+};
+use rustle::date::Date;
+use rustle::dictionary::Dictionary;
+use rustle::game::{Game, GameMode};
+use rustle::history::{GameRecord, History, HistoryFilter};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// Size of the tiles of a replayed board, in pixels
+const REPLAY_TILE_SIZE: i32 = 40;
+/// Size of the keys under a replayed board, in pixels
+const REPLAY_KEY_SIZE: (i32, i32) = (26, 36);
+
+/// How far back the date filter reaches, in days, with None for any time
+const DATE_RANGES: [Option<i64>; 4] = [None, Some(0), Some(6), Some(29)];
+
+/// Get the label shown for a game mode
+pub fn mode_label(mode: GameMode) -> String {
+    return match mode {
+        GameMode::Practice => gettext("Practice"),
+        GameMode::Daily => gettext("Daily"),
+        GameMode::Timed => gettext("Timed"),
+        GameMode::SpeedRun => gettext("Speed Run"),
+        GameMode::Survival => gettext("Survival"),
+    };
+}
+
+/// Get a short description of a game mode
+pub fn mode_description(mode: GameMode) -> String {
+    return match mode {
+        GameMode::Practice => gettext("A random word every game (default)"),
+        GameMode::Daily => gettext("Today's word, the same for everyone"),
+        GameMode::Timed => gettext("A random word to find before the time limit"),
+        GameMode::SpeedRun => gettext("Several random words in a row, as fast as possible"),
+        GameMode::Survival => {
+            gettext("Random words until one is missed, unused guesses carry over")
+        }
+    };
+}
+
+/// Write a number of seconds as minutes and seconds, such as "2:05"
+pub fn format_duration(seconds: u64) -> String {
+    if seconds >= 3600 {
+        return format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        );
+    }
+    return format!("{}:{:02}", seconds / 60, seconds % 60);
+}
+
+/// Write a time as a local date and time
+fn format_time(time: u64) -> String {
+    return glib::DateTime::from_unix_local(time as i64)
+        .and_then(|time| time.format("%x %H:%M"))
+        .map(|text| text.to_string())
+        .unwrap_or_default();
+}
+
+/// Describe how a game ended, such as "Won in 4 guesses"
+fn result_label(record: &GameRecord) -> String {
+    if !record.won {
+        return gettext("Lost");
+    }
+    let guesses: usize = record.guesses.len();
+    return fill(
+        &ngettext("Won in {} guess", "Won in {} guesses", guesses as u32),
+        &[&guesses.to_string()],
+    );
+}
+
+/// Create the row listing a game
+fn record_row(record: &GameRecord) -> ActionRow {
+    let row: ActionRow = ActionRow::builder()
+        .title(record.answer.to_uppercase())
+        .subtitle(format!(
+            "{} · {} · {}",
+            format_time(record.finished),
+            mode_label(record.mode),
+            result_label(record)
+        ))
+        .activatable(true)
+        .build();
+    let duration: Label = Label::new(Some(&format_duration(record.duration())));
+    duration.add_css_class("dim-label");
+    duration.add_css_class("numeric");
+    row.add_suffix(&duration);
+    row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+    return row;
+}
+
+/// Create a page showing a game's board, which can be stepped through guess by guess
+fn replay_page(record: GameRecord, dictionary: &Dictionary) -> NavigationPage {
+    let title: String = record.answer.to_uppercase();
+    let word_length: usize = record.answer.chars().count();
+    let grid: Grid = build_grid(word_length, record.max_guesses);
+    resize_grid(&grid, word_length, record.max_guesses, REPLAY_TILE_SIZE);
+    grid.set_halign(Align::Center);
+    grid.update_property(&[gtk::accessible::Property::Label(&gettext("Board"))]);
+
+    let layout: KeyboardLayout = KeyboardLayout::from_name(&AppSettings::load().keyboard_layout())
+        .unwrap_or(KeyboardLayout::Qwerty);
+    let keyboard_rows: Vec<Box> = build_keyboard(layout);
+    resize_keyboard(&keyboard_rows, REPLAY_KEY_SIZE.0, REPLAY_KEY_SIZE.1);
+    let keyboard: Box = Box::new(Vertical, 6);
+    for keyboard_row in &keyboard_rows {
+        keyboard.append(keyboard_row);
+    }
+    keyboard.update_property(&[gtk::accessible::Property::Label(&gettext("Keyboard"))]);
+
+    let first: Button = Button::from_icon_name("go-first-symbolic");
+    first.set_tooltip_text(Some(&gettext("First Guess")));
+    let previous: Button = Button::from_icon_name("go-previous-symbolic");
+    previous.set_tooltip_text(Some(&gettext("Previous Guess")));
+    let next: Button = Button::from_icon_name("go-next-symbolic");
+    next.set_tooltip_text(Some(&gettext("Next Guess")));
+    let last: Button = Button::from_icon_name("go-last-symbolic");
+    last.set_tooltip_text(Some(&gettext("Last Guess")));
+    let step_label: Label = Label::new(None);
+    step_label.add_css_class("numeric");
+    step_label.set_width_chars(14);
+    let controls: Box = Box::new(Horizontal, 6);
+    controls.set_halign(Align::Center);
+    controls.append(&first);
+    controls.append(&previous);
+    controls.append(&step_label);
+    controls.append(&next);
+    controls.append(&last);
+
+    let mut details: Vec<String> = vec![
+        format_time(record.started),
+        fill(&gettext("Seed {}"), &[&record.seed.to_string()]),
+        format_duration(record.duration()),
+    ];
+    if record.hard_mode {
+        details.push(gettext("Hard"));
+    }
+    let details_label: Label = Label::new(Some(&details.join(" · ")));
+    details_label.add_css_class("dim-label");
+
+    let content: Box = Box::new(Vertical, 18);
+    content.set_margin_top(12);
+    content.set_margin_bottom(24);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.append(&details_label);
+    if let Some(definitions) = definitions_text(dictionary.lookup(&record.answer)) {
+        let definitions_label: Label = Label::new(Some(&definitions));
+        definitions_label.set_wrap(true);
+        definitions_label.set_max_width_chars(50);
+        definitions_label.set_justify(gtk::Justification::Center);
+        definitions_label.set_selectable(true);
+        content.append(&definitions_label);
+    }
+    content.append(&grid);
+    content.append(&controls);
+    content.append(&keyboard);
+
+    let guesses: usize = record.guesses.len();
+    let step: Rc<Cell<usize>> = Rc::new(Cell::new(guesses));
+    let step_2: Rc<Cell<usize>> = step.clone();
+    // The buttons call this, so it only holds on to them weakly
+    let buttons: [WeakRef<Button>; 4] = [
+        first.downgrade(),
+        previous.downgrade(),
+        next.downgrade(),
+        last.downgrade(),
+    ];
+    // Show the board as it was after a number of guesses
+    let show: Rc<dyn Fn(usize)> = Rc::new(move |shown: usize| {
+        let shown: usize = shown.min(guesses);
+        step_2.set(shown);
+        let game: Game = record.replay(shown);
+        update_board(&game.board_chars, &game.board_colors, usize::MAX, 0, &grid);
+        update_keyboard(&game.letter_states, &keyboard_rows, layout);
+        step_label.set_text(&fill(
+            &gettext("Guess {} of {}"),
+            &[&shown.to_string(), &guesses.to_string()],
+        ));
+        for (i, button) in buttons.iter().enumerate() {
+            if let Some(button) = button.upgrade() {
+                button.set_sensitive(if i < 2 { shown > 0 } else { shown < guesses });
+            }
+        }
+    });
+    show(guesses);
+
+    let show_2: Rc<dyn Fn(usize)> = show.clone();
+    first.connect_clicked(move |_| show_2(0));
+    let show_2: Rc<dyn Fn(usize)> = show.clone();
+    let step_2: Rc<Cell<usize>> = step.clone();
+    previous.connect_clicked(move |_| show_2(step_2.get().saturating_sub(1)));
+    let show_2: Rc<dyn Fn(usize)> = show.clone();
+    let step_2: Rc<Cell<usize>> = step.clone();
+    next.connect_clicked(move |_| show_2(step_2.get() + 1));
+    let show_2: Rc<dyn Fn(usize)> = show.clone();
+    last.connect_clicked(move |_| show_2(guesses));
+
+    let keys: EventControllerKey = EventControllerKey::new();
+    keys.connect_key_pressed(move |_, k: Key, _, state: ModifierType| {
+        if state.intersects(ModifierType::CONTROL_MASK | ModifierType::ALT_MASK) {
+            return Propagation::Proceed;
+        }
+        let shown: usize = match k {
+            Key::Left | Key::KP_Left => step.get().saturating_sub(1),
+            Key::Right | Key::KP_Right => step.get() + 1,
+            Key::Home | Key::KP_Home => 0,
+            Key::End | Key::KP_End => guesses,
+            _ => return Propagation::Proceed,
+        };
+        show(shown);
+        return Propagation::Stop;
+    });
+    content.add_controller(keys);
+
+    let scrolled_window: ScrolledWindow = ScrolledWindow::builder()
+        .child(&content)
+        .propagate_natural_height(true)
+        .build();
+    let toolbar_view: ToolbarView = ToolbarView::new();
+    toolbar_view.add_top_bar(&HeaderBar::new());
+    toolbar_view.set_content(Some(&scrolled_window));
+    return NavigationPage::new(&toolbar_view, &title);
+}
+
+/// Show the history of finished games, which can be filtered and opened to replay them along
+/// with the answer's definitions
+pub fn show_history(parent: &impl IsA<Widget>, dictionary: Rc<Dictionary>) -> () {
+    let history: Rc<History> = Rc::new(History::load());
+
+    let date_filter: DropDown = DropDown::from_strings(&[
+        &gettext("Any Time"),
+        &gettext("Today"),
+        &gettext("Last 7 Days"),
+        &gettext("Last 30 Days"),
+    ]);
+    let mut mode_labels: Vec<String> = vec![gettext("All Modes")];
+    mode_labels.extend(GameMode::ALL.iter().map(|&mode| mode_label(mode)));
+    let mode_labels: Vec<&str> = mode_labels.iter().map(|label| label.as_str()).collect();
+    let mode_filter: DropDown = DropDown::from_strings(&mode_labels);
+    let result_filter: DropDown =
+        DropDown::from_strings(&[&gettext("All Results"), &gettext("Won"), &gettext("Lost")]);
+    let filters: Box = Box::new(Horizontal, 6);
+    filters.set_halign(Align::Center);
+    filters.set_margin_top(6);
+    filters.set_margin_bottom(6);
+    filters.append(&date_filter);
+    filters.append(&mode_filter);
+    filters.append(&result_filter);
+
+    let list: ListBox = ListBox::new();
+    list.add_css_class("boxed-list");
+    list.set_selection_mode(SelectionMode::None);
+    list.set_valign(Align::Start);
+    list.set_margin_top(6);
+    list.set_margin_bottom(24);
+    list.set_margin_start(12);
+    list.set_margin_end(12);
+    let scrolled_window: ScrolledWindow =
+        ScrolledWindow::builder().child(&list).vexpand(true).build();
+    let empty: StatusPage = StatusPage::builder()
+        .icon_name("document-open-recent-symbolic")
+        .title(gettext("No Games"))
+        .build();
+    let stack: Stack = Stack::new();
+    let stack_2: Stack = stack.clone();
+    stack.add_named(&scrolled_window, Some("list"));
+    stack.add_named(&empty, Some("empty"));
+
+    // The games currently listed, in the order of the rows
+    let shown: Rc<RefCell<Vec<GameRecord>>> = Rc::new(RefCell::new(Vec::new()));
+    let shown_2: Rc<RefCell<Vec<GameRecord>>> = shown.clone();
+    // The item picked in each filter: date, mode and result
+    let selected: Rc<Cell<[u32; 3]>> = Rc::new(Cell::new([0; 3]));
+    let selected_2: Rc<Cell<[u32; 3]>> = selected.clone();
+    let list_2: ListBox = list.clone();
+    let no_games: bool = history.games.is_empty();
+    let refresh: Rc<dyn Fn()> = Rc::new(move || {
+        let [date, mode, result]: [u32; 3] = selected_2.get();
+        let filter: HistoryFilter = HistoryFilter {
+            since: DATE_RANGES
+                .get(date as usize)
+                .copied()
+                .flatten()
+                .map(|days| Date::today().add_days(-days)),
+            mode: match mode as usize {
+                0 => None,
+                index => GameMode::ALL.get(index - 1).copied(),
+            },
+            won: match result {
+                1 => Some(true),
+                2 => Some(false),
+                _ => None,
+            },
+        };
+        let records: Vec<GameRecord> = history.filtered(&filter).into_iter().cloned().collect();
+        list_2.remove_all();
+        for record in &records {
+            list_2.append(&record_row(record));
+        }
+        stack_2.set_visible_child_name(if records.is_empty() { "empty" } else { "list" });
+        empty.set_description(Some(&if no_games {
+            gettext("Finished games will be listed here")
+        } else {
+            gettext("No games match the filters")
+        }));
+        *shown_2.borrow_mut() = records;
+    });
+    refresh();
+    for (i, filter) in [&date_filter, &mode_filter, &result_filter]
+        .into_iter()
+        .enumerate()
+    {
+        let refresh_2: Rc<dyn Fn()> = refresh.clone();
+        let selected_2: Rc<Cell<[u32; 3]>> = selected.clone();
+        filter.connect_selected_notify(move |filter: &DropDown| {
+            let mut values: [u32; 3] = selected_2.get();
+            values[i] = filter.selected();
+            selected_2.set(values);
+            refresh_2();
+        });
+    }
+
+    let toolbar_view: ToolbarView = ToolbarView::new();
+    toolbar_view.add_top_bar(&HeaderBar::new());
+    toolbar_view.add_top_bar(&filters);
+    toolbar_view.set_content(Some(&stack));
+    let navigation_view: NavigationView = NavigationView::new();
+    navigation_view.add(&NavigationPage::new(&toolbar_view, &gettext("History")));
+
+    list.connect_row_activated(move |list: &ListBox, row: &ListBoxRow| {
+        let record: Option<GameRecord> = shown.borrow().get(row.index() as usize).cloned();
+        let navigation_view: Option<NavigationView> =
+            list.ancestor(NavigationView::static_type()).and_downcast();
+        if let (Some(record), Some(navigation_view)) = (record, navigation_view) {
+            navigation_view.push(&replay_page(record, &dictionary));
+        }
+    });
+
+    let dialog: Dialog = Dialog::builder()
+        .title(gettext("History"))
+        .content_width(520)
+        .content_height(640)
+        .child(&navigation_view)
+        .build();
+    dialog.present(Some(parent));
+}
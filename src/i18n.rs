@@ -0,0 +1,57 @@
+use gettextrs::{bind_textdomain_codeset, bindtextdomain, setlocale, textdomain, LocaleCategory};
+use std::env;
+use std::path::PathBuf;
+
+/// The gettext domain of the app's messages
+const DOMAIN: &str = "rustle";
+
+/// Languages the interface can be shown in, as (code, name) pairs. Names are in their own
+/// language, so they can be found whatever the current one is.
+pub const LANGUAGES: [(&str, &str); 3] = [("en", "English"), ("de", "Deutsch"), ("es", "Español")];
+
+/// Get the directory the compiled catalogs are in
+fn locale_dir() -> PathBuf {
+    return match env::var_os("RUSTLE_LOCALE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from("locale"),
+    };
+}
+
+/// Set up translations, in the given language or the system's if it's empty.
+/// Must be called before any other threads are started.
+pub fn init(language: &str) -> () {
+    if !language.is_empty() {
+        // SAFETY: nothing else is running yet to read the environment at the same time
+        unsafe {
+            env::set_var("LANGUAGE", language);
+        }
+    }
+    setlocale(LocaleCategory::LcAll, "");
+    // gettext ignores LANGUAGE in the plain C locale, which has no translations at all
+    let messages_locale: Option<Vec<u8>> = setlocale(LocaleCategory::LcMessages, "");
+    if !language.is_empty() && matches!(messages_locale.as_deref(), Some(b"C") | Some(b"POSIX")) {
+        setlocale(LocaleCategory::LcMessages, "C.UTF-8");
+    }
+    if let Err(error) = bindtextdomain(DOMAIN, locale_dir()) {
+        eprintln!("Failed to load translations: {}", error);
+        return;
+    }
+    let _ = bind_textdomain_codeset(DOMAIN, "UTF-8");
+    let _ = textdomain(DOMAIN);
+}
+
+/// Put values into a translated message in place of each `{}`, in order
+pub fn fill(message: &str, values: &[&str]) -> String {
+    let mut text: String = String::new();
+    let mut rest: &str = message;
+    for value in values {
+        let Some(index) = rest.find("{}") else {
+            break;
+        };
+        text.push_str(&rest[..index]);
+        text.push_str(value);
+        rest = &rest[index + 2..];
+    }
+    text.push_str(rest);
+    return text;
+}
@@ -0,0 +1,458 @@
+use std::fmt::{Display, Formatter, Write};
+
+/// How deeply arrays and objects can be nested, so a hostile document can't overflow the stack
+const MAX_DEPTH: usize = 128;
+
+/// A JSON value, with object keys kept in the order they were written
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// A number as it's written, so whole numbers too big for an `f64` keep every digit
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Make a number from a whole number
+    pub fn from_u64(n: u64) -> Json {
+        return Json::Number(n.to_string());
+    }
+
+    /// Get a value of an object by its key
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        return match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        };
+    }
+
+    /// Get the value as a string
+    pub fn as_str(&self) -> Option<&str> {
+        return match self {
+            Json::String(text) => Some(text),
+            _ => None,
+        };
+    }
+
+    /// Get the value as a whole number that isn't negative
+    pub fn as_u64(&self) -> Option<u64> {
+        return match self {
+            Json::Number(text) => match text.parse::<u64>() {
+                Ok(n) => Some(n),
+                // Such as 1e3 or 5.0
+                Err(_) => text
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|n| *n >= 0.0 && n.fract() == 0.0 && *n < u64::MAX as f64)
+                    .map(|n| n as u64),
+            },
+            _ => None,
+        };
+    }
+
+    /// Get the value as a boolean
+    pub fn as_bool(&self) -> Option<bool> {
+        return match self {
+            Json::Bool(b) => Some(*b),
+            _ => None,
+        };
+    }
+
+    /// Get the items of an array
+    pub fn as_array(&self) -> Option<&[Json]> {
+        return match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        };
+    }
+
+    /// Read a JSON document
+    pub fn parse(text: &str) -> Result<Json, String> {
+        let mut parser: Parser = Parser {
+            chars: text.chars().collect(),
+            i: 0,
+            depth: 0,
+        };
+        let value: Json = parser.value()?;
+        parser.skip_whitespace();
+        if parser.i < parser.chars.len() {
+            return Err(parser.error("Unexpected text after the end"));
+        }
+        return Ok(value);
+    }
+
+    /// Write the value, indented by a number of levels
+    fn write_indented(&self, out: &mut String, level: usize) -> () {
+        let indent: String = "  ".repeat(level + 1);
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(b) => out.push_str(&b.to_string()),
+            Json::Number(text) => out.push_str(text),
+            Json::String(text) => write_string(out, text),
+            Json::Array(items) if items.is_empty() => out.push_str("[]"),
+            Json::Array(items) => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    out.push_str(&indent);
+                    item.write_indented(out, level + 1);
+                    out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&"  ".repeat(level));
+                out.push(']');
+            }
+            Json::Object(fields) if fields.is_empty() => out.push_str("{}"),
+            Json::Object(fields) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    out.push_str(&indent);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write_indented(out, level + 1);
+                    out.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&"  ".repeat(level));
+                out.push('}');
+            }
+        }
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut out: String = String::new();
+        self.write_indented(&mut out, 0);
+        return f.write_str(&out);
+    }
+}
+
+/// Write a string with quotes, escaping anything JSON doesn't allow as it is
+fn write_string(out: &mut String, text: &str) -> () {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Reads a JSON document one character at a time
+struct Parser {
+    chars: Vec<char>,
+    i: usize,
+    /// The number of arrays and objects the current position is inside
+    depth: usize,
+}
+
+impl Parser {
+    /// Describe a problem at the current position
+    fn error(&self, message: &str) -> String {
+        return format!("{} at character {}", message, self.i + 1);
+    }
+
+    /// Move past any whitespace
+    fn skip_whitespace(&mut self) -> () {
+        while self.i < self.chars.len() && self.chars[self.i].is_whitespace() {
+            self.i += 1;
+        }
+    }
+
+    /// Move past a character that must come next
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.chars.get(self.i) != Some(&c) {
+            return Err(self.error(&format!("Expected '{}'", c)));
+        }
+        self.i += 1;
+        return Ok(());
+    }
+
+    /// Move past a word such as `true`, if it comes next
+    fn keyword(&mut self, word: &str) -> bool {
+        let end: usize = self.i + word.chars().count();
+        if end <= self.chars.len() && self.chars[self.i..end].iter().copied().eq(word.chars()) {
+            self.i = end;
+            return true;
+        }
+        return false;
+    }
+
+    /// Read any value
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.chars.get(self.i) {
+            Some(&c @ ('{' | '[')) => {
+                if self.depth >= MAX_DEPTH {
+                    return Err(self.error("Nested too deeply"));
+                }
+                self.depth += 1;
+                let value: Result<Json, String> = if c == '{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+                self.depth -= 1;
+                return value;
+            }
+            Some('"') => return Ok(Json::String(self.string()?)),
+            Some('-' | '0'..='9') => return self.number(),
+            _ => {}
+        }
+        if self.keyword("true") {
+            return Ok(Json::Bool(true));
+        }
+        if self.keyword("false") {
+            return Ok(Json::Bool(false));
+        }
+        if self.keyword("null") {
+            return Ok(Json::Null);
+        }
+        return Err(self.error("Expected a value"));
+    }
+
+    /// Read an object
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect('{')?;
+        let mut fields: Vec<(String, Json)> = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.i) == Some(&'}') {
+            self.i += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key: String = self.string()?;
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.chars.get(self.i) {
+                Some(',') => self.i += 1,
+                Some('}') => {
+                    self.i += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    /// Read an array
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect('[')?;
+        let mut items: Vec<Json> = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.i) == Some(&']') {
+            self.i += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.get(self.i) {
+                Some(',') => self.i += 1,
+                Some(']') => {
+                    self.i += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+    }
+
+    /// Read a string, including its quotes
+    fn string(&mut self) -> Result<String, String> {
+        if self.chars.get(self.i) != Some(&'"') {
+            return Err(self.error("Expected a string"));
+        }
+        self.i += 1;
+        let mut text: String = String::new();
+        loop {
+            let Some(&c) = self.chars.get(self.i) else {
+                return Err(self.error("Unterminated string"));
+            };
+            self.i += 1;
+            match c {
+                '"' => return Ok(text),
+                '\\' => {
+                    let Some(&escaped) = self.chars.get(self.i) else {
+                        return Err(self.error("Unterminated string"));
+                    };
+                    self.i += 1;
+                    match escaped {
+                        'n' => text.push('\n'),
+                        'r' => text.push('\r'),
+                        't' => text.push('\t'),
+                        'b' => text.push('\u{8}'),
+                        'f' => text.push('\u{c}'),
+                        'u' => {
+                            let mut code: u32 = self.hex_code()?;
+                            // Characters outside the basic plane are escaped as a surrogate pair
+                            if (0xd800..0xdc00).contains(&code)
+                                && self.chars.get(self.i) == Some(&'\\')
+                                && self.chars.get(self.i + 1) == Some(&'u')
+                            {
+                                let start: usize = self.i;
+                                self.i += 2;
+                                let low: u32 = self.hex_code()?;
+                                if (0xdc00..0xe000).contains(&low) {
+                                    code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                                } else {
+                                    // Not a pair, so the second escape is read on its own
+                                    self.i = start;
+                                }
+                            }
+                            text.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        other => text.push(other),
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+    }
+
+    /// Read the four hex digits of a `\u` escape
+    fn hex_code(&mut self) -> Result<u32, String> {
+        let hex: String = self.chars.iter().skip(self.i).take(4).collect();
+        // from_str_radix would also take a sign
+        if hex.len() != 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(self.error("Invalid escape"));
+        }
+        self.i += 4;
+        return Ok(u32::from_str_radix(&hex, 16).unwrap_or(0xfffd));
+    }
+
+    /// Read a number
+    fn number(&mut self) -> Result<Json, String> {
+        let start: usize = self.i;
+        while self.i < self.chars.len()
+            && matches!(self.chars[self.i], '-' | '+' | '.' | 'e' | 'E' | '0'..='9')
+        {
+            self.i += 1;
+        }
+        let text: String = self.chars[start..self.i].iter().collect();
+        if text.parse::<f64>().is_err() {
+            return Err(self.error("Invalid number"));
+        }
+        return Ok(Json::Number(text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Read a document as a whole number
+    fn number(text: &str) -> Option<u64> {
+        return Json::parse(text).ok()?.as_u64();
+    }
+
+    #[test]
+    fn documents_round_trip() -> () {
+        let value: Json = Json::Object(vec![
+            (
+                String::from("name"),
+                Json::String(String::from("Ünï \"cödé\" ✓")),
+            ),
+            (
+                String::from("escapes"),
+                Json::String(String::from("back\\slash\nline\ttab\r\u{1}")),
+            ),
+            (
+                String::from("items"),
+                Json::Array(vec![
+                    Json::Null,
+                    Json::Bool(true),
+                    Json::Bool(false),
+                    Json::from_u64(u64::MAX),
+                    Json::Number(String::from("-2.5e3")),
+                    Json::Array(Vec::new()),
+                    Json::Object(Vec::new()),
+                ]),
+            ),
+        ]);
+        let text: String = value.to_string();
+        assert!(text.contains(r#""back\\slash\nline\ttab\r\u0001""#));
+        assert!(text.contains("18446744073709551615"));
+        assert_eq!(Json::parse(&text), Ok(value));
+    }
+
+    #[test]
+    fn escapes_are_read() -> () {
+        let value: Json = Json::parse(r#" { "a" : "\u00e9\"\/\b\f" , "b":[ 1 , 2 ] } "#).unwrap();
+        assert_eq!(
+            value.get("a").and_then(Json::as_str),
+            Some("é\"/\u{8}\u{c}")
+        );
+        assert_eq!(
+            value.get("b").and_then(Json::as_array).map(<[Json]>::len),
+            Some(2)
+        );
+        assert_eq!(value.get("c"), None);
+    }
+
+    #[test]
+    fn surrogate_pairs_are_combined() -> () {
+        let value: Json =
+            Json::parse(r#"["\ud83d\ude00", "\ud83d", "\ud83d\u00e9", "\ude00x"]"#).unwrap();
+        let strings: Vec<&str> = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Json::as_str)
+            .collect();
+        assert_eq!(strings, ["😀", "\u{fffd}", "\u{fffd}é", "\u{fffd}x"]);
+    }
+
+    #[test]
+    fn nesting_is_limited() -> () {
+        let nested: String = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(Json::parse(&nested).is_ok());
+        let too_deep: String = format!("{{\"a\":{}}}", nested);
+        assert!(Json::parse(&too_deep).is_err());
+        assert!(Json::parse(&"[".repeat(100_000)).is_err());
+    }
+
+    #[test]
+    fn whole_numbers_are_read() -> () {
+        assert_eq!(number("18446744073709551615"), Some(u64::MAX));
+        assert_eq!(number("9007199254740993"), Some(9007199254740993));
+        assert_eq!(number("1e3"), Some(1000));
+        assert_eq!(number("5.0"), Some(5));
+        assert_eq!(number("5.5"), None);
+        assert_eq!(number("-1"), None);
+        assert_eq!(number("\"5\""), None);
+    }
+
+    #[test]
+    fn bad_documents_are_refused() -> () {
+        for text in [
+            "",
+            "\"unterminated",
+            "\"bad \\u12\"",
+            "\"bad \\u+123\"",
+            "[1,]",
+            "[1 2]",
+            "{\"a\" 1}",
+            "{\"a\":1,}",
+            "{1:2}",
+            "1.2.3",
+            "nul",
+            "true false",
+            "{} x",
+        ] {
+            assert!(Json::parse(text).is_err(), "{:?} was read", text);
+        }
+    }
+}
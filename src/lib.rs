@@ -0,0 +1,22 @@
+pub mod achievements;
+pub mod archive;
+pub mod args;
+pub mod clock;
+pub mod date;
+pub mod dictionary;
+pub mod export;
+pub mod game;
+pub mod history;
+pub mod json;
+pub mod profiles;
+pub mod race;
+pub mod referee;
+pub mod saved_game;
+pub mod scoreboard;
+pub mod share;
+pub mod solver;
+pub mod speedrun;
+pub mod stats;
+pub mod storage;
+pub mod survival;
+pub mod text;
@@ -1,588 +1,430 @@
+mod achievements_view;
+mod animation;
+mod archive_view;
+mod help;
+mod history_view;
+mod i18n;
+mod preferences;
+mod profiles_view;
+mod race_view;
+mod settings;
+mod theme;
+mod tutorial;
+mod window;
+
+use crate::history_view::mode_description;
+use crate::i18n::fill;
+use crate::settings::AppSettings;
+use crate::theme::Theme;
+use crate::window::{options_error_message, GameWindow};
+use gettextrs::{gettext, ngettext};
 use gtk::prelude::*;
 use gtk4 as gtk;
-use gtk4::gdk::Key;
-use gtk4::glib::{ExitCode, Propagation};
-use gtk4::Orientation::{Horizontal, Vertical};
-use gtk4::{
-    gdk, AlertDialog, Align, Box, Button, CssProvider, EventControllerKey, Grid, Label,
-    Settings, Widget,
-};
-use libadwaita::prelude::{AdwApplicationWindowExt, BinExt};
-use libadwaita::{
-    Application, ApplicationWindow, Bin, HeaderBar, Toast, ToastOverlay, ToastPriority,
-};
-use rand::{rng, Rng};
-use std::cell::{Ref, RefCell, RefMut};
-use std::collections::HashMap;
-use std::fs::read_to_string;
+use gtk4::gio::{ApplicationCommandLine, ApplicationFlags, SimpleAction};
+use gtk4::glib;
+use gtk4::glib::{ExitCode, OptionArg, OptionFlags, Variant, VariantDict, VariantTy};
+use libadwaita::Application;
+use rustle::export::{export_file, import_file};
+use rustle::game::{Difficulty, GameMode, GameOptions, WordLists};
+use rustle::share::Palette;
+use rustle::stats::Stats;
+use std::cell::{RefCell, RefMut};
+use std::path::Path;
 use std::rc::Rc;
 
-/// The color for a letter that has not yet been checked
-const COLOR_UNSET: usize = 0;
-/// The color for a letter that is not in the word
-const COLOR_GRAY: usize = 1;
-/// The color for a letter that is in the word, but not at that position
-const COLOR_YELLOW: usize = 2;
-/// The color for a letter that is in the word at that position
-const COLOR_GREEN: usize = 3;
-
-/// The length of the word / width of the board
-const WORD_LENGTH: usize = 5;
-/// The number of allowed guesses / height of the board
-const MAX_GUESSES: usize = 6;
-
-/// All lowercase letters
-const LOWERCASE: &str = "qwertyuiopasdfghjklzxcvbnm"; // "Typo: In word 'qwertyuiopasdfghjklzxcvbnm'" SHUT UP
-
-/// Keys on the top row of the qwerty keyboard
-const KEYBOARD_ROW1: &str = "qwertyuiop";
-/// Keys on the middle row of the qwerty keyboard
-const KEYBOARD_ROW2: &str = "asdfghjkl";
-/// Keys on the bottom row of the qwerty keyboard
-const KEYBOARD_ROW3: &str = "zxcvbnm";
+/// Names of the command line options that change how a game is set up
+const GAME_OPTION_NAMES: [&str; 12] = [
+    "mode",
+    "length",
+    "guesses",
+    "hard",
+    "seed",
+    "word-list",
+    "answer-list",
+    "difficulty",
+    "avoid-recent",
+    "daily",
+    "time-limit",
+    "words",
+];
+
+/// Register the command line options
+fn add_options(app: &Application) -> () {
+    let mode_names: Vec<&str> = GameMode::ALL.iter().map(|mode| mode.name()).collect();
+    let mode_help: String = fill(&gettext("Game mode ({})"), &[&mode_names.join(", ")]);
+    app.add_main_option(
+        "mode",
+        glib::Char::from(b'm'),
+        OptionFlags::NONE,
+        OptionArg::String,
+        &mode_help,
+        Some("MODE"),
+    );
+    app.add_main_option(
+        "length",
+        glib::Char::from(b'l'),
+        OptionFlags::NONE,
+        OptionArg::Int,
+        &gettext("Number of letters in the word"),
+        Some("LENGTH"),
+    );
+    app.add_main_option(
+        "guesses",
+        glib::Char::from(b'g'),
+        OptionFlags::NONE,
+        OptionArg::Int,
+        &gettext("Number of guesses allowed"),
+        Some("GUESSES"),
+    );
+    app.add_main_option(
+        "hard",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::None,
+        &gettext("Hard mode: revealed hints must be used in later guesses"),
+        None,
+    );
+    app.add_main_option(
+        "seed",
+        glib::Char::from(b's'),
+        OptionFlags::NONE,
+        OptionArg::String,
+        &gettext("Play the game with the given seed"),
+        Some("SEED"),
+    );
+    app.add_main_option(
+        "word-list",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::String,
+        &gettext("File of valid guesses, one per line"),
+        Some("FILE"),
+    );
+    app.add_main_option(
+        "answer-list",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::String,
+        &gettext("File of possible answers, one per line"),
+        Some("FILE"),
+    );
+    app.add_main_option(
+        "difficulty",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::String,
+        &gettext("Answers of random games: any, easy (common) or hard (obscure)"),
+        Some("LEVEL"),
+    );
+    app.add_main_option(
+        "avoid-recent",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::Int,
+        &gettext("Don't pick the answers of this many recent games (default 30)"),
+        Some("GAMES"),
+    );
+    app.add_main_option(
+        "daily",
+        glib::Char::from(b'd'),
+        OptionFlags::NONE,
+        OptionArg::None,
+        &gettext("Play today's daily game (same as --mode daily)"),
+        None,
+    );
+    app.add_main_option(
+        "time-limit",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::Int,
+        &gettext("Time allowed in timed mode (default 180)"),
+        Some("SECONDS"),
+    );
+    app.add_main_option(
+        "words",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::Int,
+        &gettext("Number of words in speedrun mode (default 5)"),
+        Some("COUNT"),
+    );
+    app.add_main_option(
+        "stats",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::None,
+        &gettext("Print your statistics and exit"),
+        None,
+    );
+    app.add_main_option(
+        "export",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::String,
+        &gettext("Save your statistics and history to a .json or .csv file and exit"),
+        Some("FILE"),
+    );
+    app.add_main_option(
+        "import",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::String,
+        &gettext("Add the statistics and history in a .json or .csv file and exit"),
+        Some("FILE"),
+    );
+
+    let mut modes: String = gettext("Modes:");
+    for mode in GameMode::ALL {
+        modes.push_str(&format!(
+            "\n  {:<12}{}",
+            mode.name(),
+            mode_description(mode)
+        ));
+    }
+    app.set_option_context_description(Some(&modes));
+}
 
-/// Check a guess
-fn get_guess_status(
-    guess: [char; WORD_LENGTH],
-    target_slice: &str,
-    output: &mut [usize; WORD_LENGTH],
-    letter_states: &mut HashMap<char, usize>, // grays: &mut Vec<char>
-) -> bool {
-    let target: String = target_slice.chars().collect();
-    let lowercase_letters: String = String::from(LOWERCASE);
-    let mut wins: usize = 0;
-    let mut green_count_map: HashMap<char, usize> = HashMap::new();
-    let mut yellow_count_map: HashMap<char, usize> = HashMap::new();
-    let mut total_count_map: HashMap<char, usize> = HashMap::new();
-    for c in lowercase_letters.chars() {
-        green_count_map.insert(c, 0);
-        yellow_count_map.insert(c, 0);
-        total_count_map.insert(c, target.chars().filter(|&ch| ch == c).count());
+/// Read the game options given on the command line
+fn options_from_dict(dict: &VariantDict) -> Result<GameOptions, String> {
+    let mut options: GameOptions = AppSettings::load().game_options();
+    if let Ok(Some(mode)) = dict.lookup::<String>("mode") {
+        options.mode = match GameMode::from_name(mode.trim()) {
+            Some(mode) => mode,
+            None => return Err(fill(&gettext("Unknown mode \"{}\""), &[&mode])),
+        };
     }
-    for i in 0..WORD_LENGTH {
-        let c: char = guess[i];
-        if target.chars().nth(i) == Some(c) {
-            output[i] = COLOR_GREEN;
-            wins += 1;
-            *green_count_map.get_mut(&c).unwrap() += 1;
-            letter_states.insert(c, COLOR_GREEN);
-        }
+    if dict.contains("daily") {
+        options.mode = GameMode::Daily;
     }
-    for i in 0..WORD_LENGTH {
-        let c: char = guess[i];
-        if target.chars().nth(i) != Some(c) {
-            if green_count_map[&c] + yellow_count_map[&c] < total_count_map[&c] {
-                output[i] = COLOR_YELLOW;
-                *yellow_count_map.get_mut(&c).unwrap() += 1;
-                if letter_states[&c] < COLOR_YELLOW {
-                    letter_states.insert(c, COLOR_YELLOW);
-                }
-            } else {
-                output[i] = COLOR_GRAY;
-                if !target.contains(c) && letter_states[&c] < COLOR_GRAY {
-                    letter_states.insert(c, COLOR_GRAY);
-                }
-            }
-        }
+    if let Ok(Some(length)) = dict.lookup::<i32>("length") {
+        options.word_length = length.max(0) as usize;
     }
-    return wins == WORD_LENGTH;
-}
-
-/// Update the colors & letters of the board
-fn update_board(
-    board_chars: [[char; WORD_LENGTH]; MAX_GUESSES],
-    board_colors: [[usize; WORD_LENGTH]; MAX_GUESSES],
-    cur_row: usize,
-    cur_col: usize,
-    grid_ref: Ref<Grid>,
-) -> () {
-    for row in 0..MAX_GUESSES {
-        for chr in 0..WORD_LENGTH {
-            let mut c: char = board_chars[row][chr];
-            let color: usize = board_colors[row][chr];
-            let w: Widget = (*grid_ref).child_at(chr as i32, row as i32).unwrap();
-            let l: Label = w.downcast::<Label>().ok().unwrap();
-            l.remove_css_class("green");
-            l.remove_css_class("yellow");
-            l.remove_css_class("gray");
-            l.remove_css_class("cursor");
-            if color == COLOR_GREEN {
-                l.add_css_class("green");
-            } else if color == COLOR_YELLOW {
-                l.add_css_class("yellow");
-            } else if color == COLOR_GRAY {
-                l.add_css_class("gray");
-            } else if row == cur_row && chr == cur_col {
-                l.add_css_class("cursor");
-                c = '_';
-            }
-            l.set_text(&*c.to_string().to_uppercase());
-        }
+    if let Ok(Some(guesses)) = dict.lookup::<i32>("guesses") {
+        options.max_guesses = guesses.max(0) as usize;
     }
-}
-
-/// Update the colors on a row of the keyboard
-fn update_keyboard_row(letter_states: HashMap<char, usize>, keyboard_row: Box, chars: &str) -> () {
-    let mut child = keyboard_row.first_child().expect("Keyboard key missing!");
-
-    for c in chars.chars() {
-        if let Ok(l) = child.clone().downcast::<Label>() {
-            if let Some(color) = letter_states.get(&c) {
-                l.remove_css_class("green");
-                l.remove_css_class("yellow");
-                l.remove_css_class("gray");
-                l.remove_css_class("cursor");
-                if *color == COLOR_GREEN {
-                    l.add_css_class("green");
-                } else if *color == COLOR_YELLOW {
-                    l.add_css_class("yellow");
-                } else if *color == COLOR_GRAY {
-                    l.add_css_class("gray");
-                }
-            }
-        }
-        let next_child: Option<Widget> = child.next_sibling();
-        if next_child.is_some() {
-            child = next_child.unwrap();
-        } else {
-            break;
+    if let Ok(Some(time_limit)) = dict.lookup::<i32>("time-limit") {
+        options.time_limit = time_limit.max(0) as u64;
+    }
+    if let Ok(Some(words)) = dict.lookup::<i32>("words") {
+        options.speed_run_words = words.max(0) as usize;
+    }
+    if dict.contains("hard") {
+        options.hard_mode = true;
+    }
+    if let Ok(Some(seed)) = dict.lookup::<String>("seed") {
+        match seed.trim().parse::<u64>() {
+            Ok(seed) => options.seed = Some(seed),
+            Err(_) => return Err(fill(&gettext("Invalid seed \"{}\""), &[&seed])),
         }
     }
+    if let Ok(Some(word_list)) = dict.lookup::<String>("word-list") {
+        options.word_list = word_list;
+    }
+    if let Ok(Some(answer_list)) = dict.lookup::<String>("answer-list") {
+        options.answer_list = answer_list;
+    }
+    if let Ok(Some(difficulty)) = dict.lookup::<String>("difficulty") {
+        options.difficulty = match Difficulty::from_name(difficulty.trim()) {
+            Some(difficulty) => difficulty,
+            None => return Err(fill(&gettext("Unknown difficulty \"{}\""), &[&difficulty])),
+        };
+    }
+    if let Ok(Some(avoid_recent)) = dict.lookup::<i32>("avoid-recent") {
+        options.avoid_recent = avoid_recent.max(0) as usize;
+    }
+    options
+        .validate()
+        .map_err(|error| options_error_message(&error))?;
+    return Ok(options);
 }
 
-/// Update the colors on the keyboard
-fn update_keyboard(
-    letter_states: HashMap<char, usize>,
-    keyboard_row1: Box,
-    keyboard_row2: Box,
-    keyboard_row3: Box,
-) -> () {
-    update_keyboard_row(letter_states.clone(), keyboard_row1, KEYBOARD_ROW1);
-    update_keyboard_row(letter_states.clone(), keyboard_row2, KEYBOARD_ROW2);
-    update_keyboard_row(letter_states.clone(), keyboard_row3, KEYBOARD_ROW3);
-}
-
-/// Create a row of keys
-fn build_keyboard_row(keys: &str) -> Box {
-    let keyboard_row: Box = Box::new(Horizontal, 4);
-    keyboard_row.set_halign(Align::Center);
-    for c in keys.chars() {
-        let key: Label = Label::builder().build();
-        key.set_text(&*c.to_uppercase().to_string());
-        key.set_size_request(40, 60);
-        key.add_css_class("key");
-        keyboard_row.append(&key);
+/// Show the saved palette in every open window
+fn apply_palette_to_windows() -> () {
+    let Some(app) = gtk::gio::Application::default().and_downcast::<Application>() else {
+        return;
+    };
+    for window in app.windows() {
+        theme::apply_palette(&window, theme::load_palette(), theme::load_patterns());
     }
-    return keyboard_row;
 }
 
 fn main() -> ExitCode {
-    if !std::fs::exists("assets/lists/words.txt").expect("Failed to check if file exists") {
-        println!("words list not found!");
-        return ExitCode::FAILURE;
-    }
-    if !std::fs::exists("assets/lists/answers.txt").expect("Failed to check if file exists") {
-        println!("answer list not found!");
-        return ExitCode::FAILURE;
-    }
+    i18n::init(&AppSettings::load().language());
 
     let app: Application = Application::builder()
         .application_id("dev.droc101.rustle")
+        .flags(ApplicationFlags::HANDLES_COMMAND_LINE)
         .build();
 
-    app.connect_startup(|_| {
-        let provider: CssProvider = CssProvider::new();
-        provider.load_from_string(include_str!("../assets/style/style.css"));
-
-        gtk::style_context_add_provider_for_display(
-            &gdk::Display::default().expect("Could not connect to a display."),
-            &provider,
-            gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
-        );
-
-        let settings: Settings = Settings::default().unwrap();
-        if settings.is_gtk_application_prefer_dark_theme() {
-            let dark_provider: CssProvider = CssProvider::new();
-            dark_provider.load_from_string(include_str!("../assets/style/style.dark.css"));
-            gtk::style_context_add_provider_for_display(
-                &gdk::Display::default().expect("Could not connect to a display."),
-                &dark_provider,
-                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
-            );
-        }
-    });
-
-    app.connect_activate(|app: &Application| -> () {
-        let lowercase_letters: String = String::from(LOWERCASE);
-        let words: Vec<String> = read_to_string("assets/lists/words.txt")
-            .unwrap()
-            .lines()
-            .map(String::from)
-            .collect();
-        let answers: Vec<String> = read_to_string("assets/lists/answers.txt")
-            .unwrap()
-            .lines()
-            .map(String::from)
-            .collect();
-        let board_chars: [[char; WORD_LENGTH]; MAX_GUESSES] = [[' '; WORD_LENGTH]; MAX_GUESSES];
-        let board_colors: [[usize; WORD_LENGTH]; MAX_GUESSES] =
-            [[COLOR_UNSET; WORD_LENGTH]; MAX_GUESSES];
-        let mut letter_states: HashMap<char, usize> = HashMap::new();
-        let cur_x: usize = 0;
-        let guess: usize = 0;
-        let answer_index: usize = rng().random_range(0..answers.len());
-        let answer: String = answers[answer_index].clone();
-        let locked: bool = false;
+    add_options(&app);
 
-        for c in LOWERCASE.chars() {
-            letter_states.insert(c, COLOR_UNSET);
+    app.connect_handle_local_options(|_, options: &VariantDict| -> i32 {
+        if options.contains("stats") {
+            print!("{}", Stats::load().summary());
+            return 0;
         }
-
-        let window: ApplicationWindow = ApplicationWindow::builder()
-            .application(app)
-            .default_width(800)
-            .default_height(810)
-            .title("Rustle!")
-            .resizable(false)
-            .build();
-
-        let outermost_box: Box = Box::new(Vertical, 0);
-
-        let header: HeaderBar = HeaderBar::builder().show_start_title_buttons(true).build();
-        outermost_box.append(&header);
-
-        let outer_box: Box = Box::new(Horizontal, 6);
-        outer_box.set_hexpand(false);
-        outer_box.set_halign(Align::Center);
-
-        let main_box: Box = Box::new(Vertical, 6);
-        main_box.set_vexpand(true);
-        main_box.set_hexpand(false);
-        main_box.set_margin_top(10);
-
-        let title: Label = Label::builder().build();
-        title.set_text("Rustle!");
-        title.add_css_class("title_text");
-        title.set_margin_bottom(10);
-        main_box.append(&title);
-
-        let grid_box: Box = Box::new(Horizontal, 6);
-        grid_box.set_halign(Align::Center);
-
-        let grid: Grid = Grid::builder().build();
-        grid.set_column_homogeneous(true);
-        grid.set_row_homogeneous(true);
-        grid.set_column_spacing(4);
-        grid.set_row_spacing(4);
-        grid.set_hexpand(false);
-        for _ in 0..MAX_GUESSES {
-            grid.insert_row(0);
-            for _ in 0..WORD_LENGTH {
-                grid.insert_column(0);
+        if let Ok(Some(path)) = options.lookup::<String>("export") {
+            if let Err(message) = export_file(Path::new(&path)) {
+                eprintln!("{}", message);
+                return 1;
             }
+            println!("{}", fill(&gettext("Exported to {}"), &[&path]));
+            return 0;
         }
-        for y in 0i32..MAX_GUESSES as i32 {
-            for x in 0i32..WORD_LENGTH as i32 {
-                let label: Label = Label::builder().build();
-                label.set_size_request(60, 60);
-                label.add_css_class("tile");
-                label.set_text("");
-                grid.attach(&label, x, y, 1, 1);
-            }
+        if let Ok(Some(path)) = options.lookup::<String>("import") {
+            return match import_file(Path::new(&path)) {
+                Ok(added) => {
+                    let message: String = ngettext(
+                        "Imported {} new game from {}",
+                        "Imported {} new games from {}",
+                        added as u32,
+                    );
+                    println!("{}", fill(&message, &[&added.to_string(), &path]));
+                    0
+                }
+                Err(message) => {
+                    eprintln!("{}", message);
+                    1
+                }
+            };
         }
-        grid_box.append(&grid);
-        main_box.append(&grid_box);
-
-        let keyboard_bin: Bin = Bin::builder().build();
-        keyboard_bin.set_margin_top(40);
-        keyboard_bin.set_widget_name("keyboard");
-        main_box.append(&keyboard_bin);
-
-        let kb_box: Box = Box::new(Vertical, 8);
-
-        let keyboard_row_1: Box = build_keyboard_row(KEYBOARD_ROW1);
-        kb_box.append(&keyboard_row_1);
-
-        let keyboard_row_2: Box = build_keyboard_row(KEYBOARD_ROW2);
-        kb_box.append(&keyboard_row_2);
-
-        let keyboard_row_3: Box = build_keyboard_row(KEYBOARD_ROW3);
-        kb_box.append(&keyboard_row_3);
-
-        keyboard_bin.set_child(Some(&kb_box));
-
-        let new_game: Button = Button::builder().label("Play Again").build();
-        new_game.add_css_class("new_game");
-        new_game.set_visible(false);
-        new_game.set_size_request(200, -1);
-        new_game.set_hexpand(false);
-        main_box.set_homogeneous(false);
-        main_box.append(&new_game);
-
-        let toast_overlay: ToastOverlay = ToastOverlay::new();
-        toast_overlay.set_child(Some(&outermost_box));
-
-        outer_box.append(&main_box);
-        outermost_box.append(&outer_box);
-        window.set_content(Some(&toast_overlay));
-
-        //#region refcells
-        let grid_rc: Rc<RefCell<Grid>> = Rc::new(RefCell::new(grid.clone()));
-        let new_game_rc: Rc<RefCell<Button>> = Rc::new(RefCell::new(new_game.clone()));
-        let keyboard_row_1_rc: Rc<RefCell<Box>> = Rc::new(RefCell::new(keyboard_row_1.clone()));
-        let keyboard_row_2_rc: Rc<RefCell<Box>> = Rc::new(RefCell::new(keyboard_row_2.clone()));
-        let keyboard_row_3_rc: Rc<RefCell<Box>> = Rc::new(RefCell::new(keyboard_row_3.clone()));
-        let letter_states_rc: Rc<RefCell<HashMap<char, usize>>> =
-            Rc::new(RefCell::new(letter_states.clone()));
-        let window_rc: Rc<RefCell<ApplicationWindow>> = Rc::new(RefCell::new(window.clone()));
-        let cur_x_rc: Rc<RefCell<usize>> = Rc::new(RefCell::new(cur_x));
-        let board_chars_rc: Rc<RefCell<[[char; WORD_LENGTH]; MAX_GUESSES]>> =
-            Rc::new(RefCell::new(board_chars));
-        let board_colors_rc: Rc<RefCell<[[usize; WORD_LENGTH]; MAX_GUESSES]>> =
-            Rc::new(RefCell::new(board_colors));
-        let guess_rc: Rc<RefCell<usize>> = Rc::new(RefCell::new(guess));
-        let locked_rc: Rc<RefCell<bool>> = Rc::new(RefCell::new(locked));
-        let answer_rc: Rc<RefCell<String>> = Rc::new(RefCell::new(answer));
-        let answers_rc: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(answers));
-        let toast_overlay_rc: Rc<RefCell<ToastOverlay>> = Rc::new(RefCell::new(toast_overlay));
+        if let Err(message) = options_from_dict(options) {
+            eprintln!("{}", message);
+            return 1;
+        }
+        return -1;
+    });
 
-        let grid_rc_2: Rc<RefCell<Grid>> = grid_rc.clone();
-        let new_game_rc_2: Rc<RefCell<Button>> = new_game_rc.clone();
-        let keyboard_row_1_rc_2: Rc<RefCell<Box>> = keyboard_row_1_rc.clone();
-        let keyboard_row_2_rc_2: Rc<RefCell<Box>> = keyboard_row_2_rc.clone();
-        let keyboard_row_3_rc_2: Rc<RefCell<Box>> = keyboard_row_3_rc.clone();
-        let letter_states_rc_2: Rc<RefCell<HashMap<char, usize>>> = letter_states_rc.clone();
-        let window_rc_2: Rc<RefCell<ApplicationWindow>> = window_rc.clone();
-        let cur_x_rc_2: Rc<RefCell<usize>> = cur_x_rc.clone();
-        let board_chars_rc_2: Rc<RefCell<[[char; WORD_LENGTH]; MAX_GUESSES]>> =
-            board_chars_rc.clone();
-        let board_colors_rc_2: Rc<RefCell<[[usize; WORD_LENGTH]; MAX_GUESSES]>> =
-            board_colors_rc.clone();
-        let guess_rc_2: Rc<RefCell<usize>> = guess_rc.clone();
-        let locked_rc_2: Rc<RefCell<bool>> = locked_rc.clone();
-        let answer_rc_2: Rc<RefCell<String>> = answer_rc.clone();
+    app.connect_startup(|app: &Application| {
+        theme::load_styles();
 
-        let answer_index_rc: Rc<RefCell<usize>> = Rc::new(RefCell::new(answer_index));
+        let theme_action: SimpleAction = SimpleAction::new_stateful(
+            "theme",
+            Some(VariantTy::STRING),
+            &theme::load_theme().name().to_variant(),
+        );
+        // Menus and the preferences dialog both change the state, which is then applied here
+        theme_action.connect_change_state(|action: &SimpleAction, state: Option<&Variant>| {
+            let Some(theme) = state
+                .and_then(|state| state.str())
+                .and_then(Theme::from_name)
+            else {
+                return;
+            };
+            action.set_state(&theme.name().to_variant());
+            theme::set_theme(theme);
+        });
+        app.add_action(&theme_action);
 
-        //#endregion
+        let palette_action: SimpleAction = SimpleAction::new_stateful(
+            "palette",
+            Some(VariantTy::STRING),
+            &theme::load_palette().name().to_variant(),
+        );
+        palette_action.connect_change_state(|action: &SimpleAction, state: Option<&Variant>| {
+            let Some(palette) = state
+                .and_then(|state| state.str())
+                .and_then(Palette::from_name)
+            else {
+                return;
+            };
+            action.set_state(&palette.name().to_variant());
+            theme::save_palette(palette, theme::load_patterns());
+            apply_palette_to_windows();
+        });
+        app.add_action(&palette_action);
+
+        let patterns_action: SimpleAction =
+            SimpleAction::new_stateful("patterns", None, &theme::load_patterns().to_variant());
+        patterns_action.connect_change_state(|action: &SimpleAction, state: Option<&Variant>| {
+            let Some(patterns) = state.and_then(|state| state.get::<bool>()) else {
+                return;
+            };
+            action.set_state(&patterns.to_variant());
+            theme::save_palette(theme::load_palette(), patterns);
+            apply_palette_to_windows();
+        });
+        app.add_action(&patterns_action);
 
-        update_board(board_chars, board_colors, guess, cur_x, grid_rc.borrow());
+        animation::set_allowed(AppSettings::load().animations());
 
-        new_game.connect_clicked(move |_| {
-            let new_game_val: Ref<Button> = new_game_rc_2.borrow();
-            let mut locked_val: RefMut<bool> = locked_rc_2.borrow_mut();
-            let mut cur_x_val: RefMut<usize> = cur_x_rc_2.borrow_mut();
-            let mut board_chars_val: RefMut<[[char; WORD_LENGTH]; MAX_GUESSES]> =
-                board_chars_rc_2.borrow_mut();
-            let mut board_colors_val: RefMut<[[usize; WORD_LENGTH]; MAX_GUESSES]> =
-                board_colors_rc_2.borrow_mut();
-            let mut guess_val: RefMut<usize> = guess_rc_2.borrow_mut();
-            let mut letter_states_val: RefMut<HashMap<char, usize>> =
-                letter_states_rc_2.borrow_mut();
-            let grid_val: Ref<Grid> = grid_rc_2.borrow();
-            let keyboard_row_1_val: Ref<Box> = keyboard_row_1_rc_2.borrow();
-            let keyboard_row_2_val: Ref<Box> = keyboard_row_2_rc_2.borrow();
-            let keyboard_row_3_val: Ref<Box> = keyboard_row_3_rc_2.borrow();
-            let window_val: Ref<ApplicationWindow> = window_rc_2.borrow();
-            let mut answer_index_val: RefMut<usize> = answer_index_rc.borrow_mut();
-            let mut answer_val: RefMut<String> = answer_rc.borrow_mut();
-            let answers_val: Ref<Vec<String>> = answers_rc.borrow();
-            new_game_val.set_visible(false);
-            for i in 0..MAX_GUESSES {
-                for j in 0..WORD_LENGTH {
-                    board_chars_val[i][j] = ' ';
-                    board_colors_val[i][j] = COLOR_UNSET;
-                }
-            }
-            for c in LOWERCASE.chars() {
-                letter_states_val.insert(c, COLOR_UNSET);
+        let about_action: SimpleAction = SimpleAction::new("about", None);
+        about_action.connect_activate(|_, _| {
+            let app: Option<Application> = gtk::gio::Application::default().and_downcast();
+            if let Some(window) = app.and_then(|app| app.active_window()) {
+                help::show_about(&window);
             }
-
-            update_board(
-                *board_chars_val,
-                *board_colors_val,
-                *guess_val,
-                *cur_x_val,
-                grid_val,
-            );
-            update_keyboard(
-                letter_states_val.clone(),
-                keyboard_row_1_val.clone(),
-                keyboard_row_2_val.clone(),
-                keyboard_row_3_val.clone(),
-            );
-
-            window_val.grab_focus();
-
-            *locked_val = false;
-            *cur_x_val = 0;
-            *guess_val = 0;
-
-            *answer_index_val = rng().random_range(0..answers_val.len());
-            *answer_val = answers_val[*answer_index_val].clone();
         });
+        app.add_action(&about_action);
 
-        let k: EventControllerKey = EventControllerKey::builder().build();
-        k.connect_key_pressed(move |_, k: Key, _, _| {
-            let mut cur_x_val: RefMut<usize> = cur_x_rc.borrow_mut();
-            let mut board_chars_val: RefMut<[[char; WORD_LENGTH]; MAX_GUESSES]> =
-                board_chars_rc.borrow_mut();
-            let mut board_colors_val: RefMut<[[usize; WORD_LENGTH]; MAX_GUESSES]> =
-                board_colors_rc.borrow_mut();
-            let grid_val: Ref<Grid> = grid_rc.borrow();
-            let new_game_val: Ref<Button> = new_game_rc.borrow();
-            let keyboard_row_1_val: Ref<Box> = keyboard_row_1_rc.borrow();
-            let keyboard_row_2_val: Ref<Box> = keyboard_row_2_rc.borrow();
-            let keyboard_row_3_val: Ref<Box> = keyboard_row_3_rc.borrow();
-            let window_val: Ref<ApplicationWindow> = window_rc.borrow();
-            let mut guess_val: RefMut<usize> = guess_rc.borrow_mut();
-            let mut letter_states_val: RefMut<HashMap<char, usize>> = letter_states_rc.borrow_mut();
-            let mut locked_val: RefMut<bool> = locked_rc.borrow_mut();
-            let answer_val: Ref<String> = answer_rc_2.borrow();
-            let toast_overlay_val: Ref<ToastOverlay> = toast_overlay_rc.borrow();
-
-            if *locked_val {
-                return Propagation::Proceed;
+        let quit_action: SimpleAction = SimpleAction::new("quit", None);
+        quit_action.connect_activate(|_, _| {
+            if let Some(app) = gtk::gio::Application::default() {
+                app.quit();
             }
+        });
+        app.add_action(&quit_action);
+
+        app.set_accels_for_action("win.new-game", &["<Control>n"]);
+        app.set_accels_for_action("win.history", &["<Control>h"]);
+        app.set_accels_for_action("win.preferences", &["<Control>comma"]);
+        app.set_accels_for_action("win.how-to-play", &["F1"]);
+        app.set_accels_for_action("win.show-help-overlay", &["<Control>question"]);
+        app.set_accels_for_action("window.close", &["<Control>w"]);
+        app.set_accels_for_action("app.quit", &["<Control>q"]);
+    });
 
-            if k == Key::BackSpace {
-                if *cur_x_val != 0 {
-                    board_chars_val[*guess_val][*cur_x_val - 1] = ' ';
-                    *cur_x_val -= 1;
+    let game_window_rc: Rc<RefCell<Option<Rc<GameWindow>>>> = Rc::new(RefCell::new(None));
+    let game_window_rc_2: Rc<RefCell<Option<Rc<GameWindow>>>> = game_window_rc.clone();
+
+    // Every invocation ends up here, including ones forwarded from a second instance
+    app.connect_command_line(
+        move |app: &Application, command_line: &ApplicationCommandLine| -> i32 {
+            let dict: VariantDict = command_line.options_dict();
+            let options: GameOptions = match options_from_dict(&dict) {
+                Ok(options) => options,
+                Err(message) => {
+                    command_line.printerr_literal(&format!("{}\n", message));
+                    return 1;
+                }
+            };
+            let lists: WordLists = match WordLists::load(&options) {
+                Ok(lists) => lists,
+                Err(error) => {
+                    command_line.printerr_literal(&format!("{}\n", options_error_message(&error)));
+                    return 1;
                 }
-                update_board(
-                    *board_chars_val,
-                    *board_colors_val,
-                    *guess_val,
-                    *cur_x_val,
-                    grid_val,
-                );
-                update_keyboard(
-                    letter_states_val.clone(),
-                    keyboard_row_1_val.clone(),
-                    keyboard_row_2_val.clone(),
-                    keyboard_row_3_val.clone(),
-                );
-                return Propagation::Stop;
-            } else if k == Key::Return || k == Key::KP_Enter {
-                if *cur_x_val == WORD_LENGTH {
-                    let guess_str: String = String::from_iter(board_chars_val[*guess_val].iter());
-                    if words.contains(&guess_str) {
-                        let winner: bool = get_guess_status(
-                            board_chars_val[*guess_val],
-                            answer_val.as_str(),
-                            &mut board_colors_val[*guess_val],
-                            &mut *letter_states_val,
-                        );
-                        if winner {
-                            update_board(
-                                *board_chars_val,
-                                *board_colors_val,
-                                *guess_val,
-                                *cur_x_val,
-                                grid_val,
-                            );
-                            update_keyboard(
-                                letter_states_val.clone(),
-                                keyboard_row_1_val.clone(),
-                                keyboard_row_2_val.clone(),
-                                keyboard_row_3_val.clone(),
-                            );
-                            *locked_val = true;
-                            new_game_val.set_visible(true);
-                            let message: String = format!("Guessed in {} tries", *guess_val + 1);
-                            AlertDialog::builder()
-                                .message("You Win!")
-                                .detail(message)
-                                .build()
-                                .show(Some(&*window_val));
-                            return Propagation::Stop;
-                        } else if *guess_val == MAX_GUESSES - 1 {
-                            update_board(
-                                *board_chars_val,
-                                *board_colors_val,
-                                *guess_val,
-                                *cur_x_val,
-                                grid_val,
-                            );
-                            update_keyboard(
-                                letter_states_val.clone(),
-                                keyboard_row_1_val.clone(),
-                                keyboard_row_2_val.clone(),
-                                keyboard_row_3_val.clone(),
-                            );
-                            let message: String = format!("The word was \"{}\"", *answer_val);
-                            AlertDialog::builder()
-                                .detail(message)
-                                .message("You Lose!")
-                                .build()
-                                .show(Some(&*window_val));
-                            *locked_val = true;
-                            new_game_val.set_visible(true);
-                            return Propagation::Stop;
-                        }
-                        *guess_val += 1;
-                        *cur_x_val = 0;
-                        update_board(
-                            *board_chars_val,
-                            *board_colors_val,
-                            *guess_val,
-                            *cur_x_val,
-                            grid_val,
-                        );
-                        update_keyboard(
-                            letter_states_val.clone(),
-                            keyboard_row_1_val.clone(),
-                            keyboard_row_2_val.clone(),
-                            keyboard_row_3_val.clone(),
-                        );
-                    } else {
-                        toast_overlay_val.dismiss_all();
-                        let toast: Toast = Toast::new("Invalid Word!");
-                        toast.set_timeout(2);
-                        toast.set_priority(ToastPriority::High);
-                        toast_overlay_val.add_toast(toast);
+            };
+
+            let mut game_window_val: RefMut<Option<Rc<GameWindow>>> = game_window_rc.borrow_mut();
+            match &*game_window_val {
+                Some(game_window) => {
+                    if GAME_OPTION_NAMES.iter().any(|name| dict.contains(name)) {
+                        game_window.set_options(options, lists);
                     }
-                } else {
-                    toast_overlay_val.dismiss_all();
-                    let toast: Toast = Toast::new("Not enough letters!");
-                    toast.set_timeout(2);
-                    toast.set_priority(ToastPriority::High);
-                    toast_overlay_val.add_toast(toast);
+                    game_window.window.present();
                 }
-                return Propagation::Stop;
-            } else {
-                if *cur_x_val + 1 <= WORD_LENGTH {
-                    let unicode: Option<char> = k.to_unicode();
-                    if unicode.is_some() {
-                        let mut chr: String = String::from(unicode.unwrap());
-                        chr = chr.to_lowercase();
-                        if chr.len() == 1 && lowercase_letters.contains(chr.as_str()) {
-                            board_chars_val[*guess_val][*cur_x_val] = chr.pop().unwrap();
-                            *cur_x_val += 1; // @rust you stupid language add ++ operator
-                            update_board(
-                                *board_chars_val,
-                                *board_colors_val,
-                                *guess_val,
-                                *cur_x_val,
-                                grid_val,
-                            );
-                            update_keyboard(
-                                letter_states_val.clone(),
-                                keyboard_row_1_val.clone(),
-                                keyboard_row_2_val.clone(),
-                                keyboard_row_3_val.clone(),
-                            );
-                            return Propagation::Stop;
-                        }
+                None => *game_window_val = Some(GameWindow::new(app, options, lists)),
+            }
+            return 0;
+        },
+    );
+
+    app.connect_activate(move |app: &Application| -> () {
+        let mut game_window_val: RefMut<Option<Rc<GameWindow>>> = game_window_rc_2.borrow_mut();
+        match &*game_window_val {
+            Some(game_window) => game_window.window.present(),
+            None => {
+                let options: GameOptions = AppSettings::load().game_options();
+                match WordLists::load(&options) {
+                    Ok(lists) => *game_window_val = Some(GameWindow::new(app, options, lists)),
+                    Err(error) => {
+                        eprintln!("{}", options_error_message(&error));
+                        app.quit();
                     }
                 }
             }
-            return Propagation::Proceed;
-        });
-        window.add_controller(k);
-
-        window.present();
+        }
     });
 
     return app.run();
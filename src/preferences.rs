@@ -0,0 +1,338 @@
+use crate::animation;
+use crate::i18n::LANGUAGES;
+use crate::settings::AppSettings;
+use crate::theme::{self, Theme};
+use crate::window::KeyboardLayout;
+use gettextrs::gettext;
+use gtk4::gio::prelude::*;
+use gtk4::prelude::*;
+use gtk4::{StringList, Widget};
+use libadwaita::prelude::{
+    ActionRowExt, AdwDialogExt, ComboRowExt, EntryRowExt, PreferencesDialogExt,
+    PreferencesGroupExt, PreferencesPageExt, PreferencesRowExt,
+};
+use libadwaita::{
+    ComboRow, EntryRow, PreferencesDialog, PreferencesGroup, PreferencesPage, SpinRow, SwitchRow,
+};
+use rustle::game::{
+    Difficulty, GameOptions, MAX_AVOID_RECENT, MAX_GUESS_LIMIT, MAX_SPEED_RUN_WORDS,
+    MAX_TIME_LIMIT, MAX_WORD_LENGTH, MIN_TIME_LIMIT, MIN_WORD_LENGTH,
+};
+use rustle::share::Palette;
+use std::rc::Rc;
+
+/// Create a row for picking one of a list of choices
+fn combo_row(title: &str, labels: &[String], selected: usize) -> ComboRow {
+    let labels: Vec<&str> = labels.iter().map(|label| label.as_str()).collect();
+    let row: ComboRow = ComboRow::builder()
+        .title(title)
+        .model(&StringList::new(&labels))
+        .build();
+    row.set_selected(selected as u32);
+    return row;
+}
+
+/// Create a row for the path of a word list, saved when the apply button is pressed
+fn list_row(title: &str, path: &str, save: impl Fn(&str) + 'static) -> EntryRow {
+    let row: EntryRow = EntryRow::builder()
+        .title(title)
+        .text(path)
+        .show_apply_button(true)
+        .build();
+    row.connect_apply(move |row: &EntryRow| save(row.text().trim()));
+    return row;
+}
+
+/// Show the preferences dialog. `game_changed` is called when a setting that applies from the
+/// next game is changed.
+pub fn show_preferences(parent: &impl IsA<Widget>, game_changed: impl Fn() + 'static) -> () {
+    let settings: Rc<AppSettings> = Rc::new(AppSettings::load());
+    let game_changed: Rc<dyn Fn()> = Rc::new(game_changed);
+    let options: GameOptions = settings.game_options();
+
+    let game_group: PreferencesGroup = PreferencesGroup::builder()
+        .title(gettext("Game"))
+        .description(gettext("Changes apply from the next game"))
+        .build();
+
+    let length_row: SpinRow =
+        SpinRow::with_range(MIN_WORD_LENGTH as f64, MAX_WORD_LENGTH as f64, 1.0);
+    length_row.set_title(&gettext("Word Length"));
+    length_row.set_value(options.word_length as f64);
+    let settings_2: Rc<AppSettings> = settings.clone();
+    let game_changed_2: Rc<dyn Fn()> = game_changed.clone();
+    length_row.connect_value_notify(move |row: &SpinRow| {
+        settings_2.set_word_length(row.value() as usize);
+        game_changed_2();
+    });
+    game_group.add(&length_row);
+
+    let guesses_row: SpinRow = SpinRow::with_range(1.0, MAX_GUESS_LIMIT as f64, 1.0);
+    guesses_row.set_title(&gettext("Guesses"));
+    guesses_row.set_value(options.max_guesses as f64);
+    let settings_2: Rc<AppSettings> = settings.clone();
+    let game_changed_2: Rc<dyn Fn()> = game_changed.clone();
+    guesses_row.connect_value_notify(move |row: &SpinRow| {
+        settings_2.set_max_guesses(row.value() as usize);
+        game_changed_2();
+    });
+    game_group.add(&guesses_row);
+
+    let hard_row: SwitchRow = SwitchRow::builder()
+        .title(gettext("Hard Mode"))
+        .subtitle(gettext("Revealed hints must be used in later guesses"))
+        .active(options.hard_mode)
+        .build();
+    let settings_2: Rc<AppSettings> = settings.clone();
+    let game_changed_2: Rc<dyn Fn()> = game_changed.clone();
+    hard_row.connect_active_notify(move |row: &SwitchRow| {
+        settings_2.set_hard_mode(row.is_active());
+        game_changed_2();
+    });
+    game_group.add(&hard_row);
+
+    let layout: KeyboardLayout =
+        KeyboardLayout::from_name(&settings.keyboard_layout()).unwrap_or(KeyboardLayout::Qwerty);
+    let layout_labels: Vec<String> = KeyboardLayout::ALL.iter().map(|l| l.label()).collect();
+    let layout_row: ComboRow = combo_row(
+        &gettext("Keyboard Layout"),
+        &layout_labels,
+        KeyboardLayout::ALL
+            .iter()
+            .position(|&l| l == layout)
+            .unwrap_or(0),
+    );
+    let settings_2: Rc<AppSettings> = settings.clone();
+    let game_changed_2: Rc<dyn Fn()> = game_changed.clone();
+    layout_row.connect_selected_notify(move |row: &ComboRow| {
+        if let Some(layout) = KeyboardLayout::ALL.get(row.selected() as usize) {
+            settings_2.set_keyboard_layout(layout.name());
+            game_changed_2();
+        }
+    });
+    game_group.add(&layout_row);
+
+    let difficulty_labels: Vec<String> = vec![gettext("Any"), gettext("Easy"), gettext("Hard")];
+    let difficulty_row: ComboRow = combo_row(
+        &gettext("Difficulty"),
+        &difficulty_labels,
+        Difficulty::ALL
+            .iter()
+            .position(|&d| d == options.difficulty)
+            .unwrap_or(0),
+    );
+    difficulty_row.set_subtitle(&gettext(
+        "How common the answers of random games are, if the answer list has word frequencies",
+    ));
+    let settings_2: Rc<AppSettings> = settings.clone();
+    let game_changed_2: Rc<dyn Fn()> = game_changed.clone();
+    difficulty_row.connect_selected_notify(move |row: &ComboRow| {
+        if let Some(difficulty) = Difficulty::ALL.get(row.selected() as usize) {
+            settings_2.set_difficulty(*difficulty);
+            game_changed_2();
+        }
+    });
+    game_group.add(&difficulty_row);
+
+    let avoid_row: SpinRow = SpinRow::with_range(0.0, MAX_AVOID_RECENT as f64, 1.0);
+    avoid_row.set_title(&gettext("Avoid Repeats"));
+    avoid_row.set_subtitle(&gettext(
+        "Random games don't pick the answers of this many recent games",
+    ));
+    avoid_row.set_value(options.avoid_recent as f64);
+    let settings_2: Rc<AppSettings> = settings.clone();
+    let game_changed_2: Rc<dyn Fn()> = game_changed.clone();
+    avoid_row.connect_value_notify(move |row: &SpinRow| {
+        settings_2.set_avoid_recent(row.value() as usize);
+        game_changed_2();
+    });
+    game_group.add(&avoid_row);
+
+    let clock_group: PreferencesGroup = PreferencesGroup::builder()
+        .title(gettext("Timed Games"))
+        .build();
+
+    let time_limit_row: SpinRow =
+        SpinRow::with_range(MIN_TIME_LIMIT as f64, MAX_TIME_LIMIT as f64, 10.0);
+    time_limit_row.set_title(&gettext("Time Limit"));
+    time_limit_row.set_subtitle(&gettext("Seconds to find the word in timed mode"));
+    time_limit_row.set_value(options.time_limit as f64);
+    let settings_2: Rc<AppSettings> = settings.clone();
+    let game_changed_2: Rc<dyn Fn()> = game_changed.clone();
+    time_limit_row.connect_value_notify(move |row: &SpinRow| {
+        settings_2.set_time_limit(row.value() as u64);
+        game_changed_2();
+    });
+    clock_group.add(&time_limit_row);
+
+    let words_row: SpinRow = SpinRow::with_range(1.0, MAX_SPEED_RUN_WORDS as f64, 1.0);
+    words_row.set_title(&gettext("Speed Run Words"));
+    words_row.set_subtitle(&gettext("Words to solve in a speed run"));
+    words_row.set_value(options.speed_run_words as f64);
+    let settings_2: Rc<AppSettings> = settings.clone();
+    let game_changed_2: Rc<dyn Fn()> = game_changed.clone();
+    words_row.connect_value_notify(move |row: &SpinRow| {
+        settings_2.set_speed_run_words(row.value() as usize);
+        game_changed_2();
+    });
+    clock_group.add(&words_row);
+
+    let pause_row: SwitchRow = SwitchRow::builder()
+        .title(gettext("Pause When Unfocused"))
+        .subtitle(gettext(
+            "Stop the clock while the window is in the background",
+        ))
+        .active(settings.pause_unfocused())
+        .build();
+    let settings_2: Rc<AppSettings> = settings.clone();
+    pause_row.connect_active_notify(move |row: &SwitchRow| {
+        settings_2.set_pause_unfocused(row.is_active());
+    });
+    clock_group.add(&pause_row);
+
+    let appearance_group: PreferencesGroup = PreferencesGroup::builder()
+        .title(gettext("Appearance"))
+        .build();
+
+    // Theme and colors go through the app's actions, so the main menu stays in step
+    let app: Option<gtk4::gio::Application> = gtk4::gio::Application::default();
+
+    let theme: Theme = theme::load_theme();
+    let theme_labels: Vec<String> = Theme::ALL.iter().map(|t| t.label()).collect();
+    let theme_row: ComboRow = combo_row(
+        &gettext("Theme"),
+        &theme_labels,
+        Theme::ALL.iter().position(|&t| t == theme).unwrap_or(0),
+    );
+    let app_2: Option<gtk4::gio::Application> = app.clone();
+    theme_row.connect_selected_notify(move |row: &ComboRow| {
+        if let (Some(app), Some(theme)) = (&app_2, Theme::ALL.get(row.selected() as usize)) {
+            app.change_action_state("theme", &theme.name().to_variant());
+        }
+    });
+    appearance_group.add(&theme_row);
+
+    let palette: Palette = theme::load_palette();
+    let palette_labels: Vec<String> = Palette::ALL
+        .iter()
+        .map(|&p| theme::palette_label(p))
+        .collect();
+    let palette_row: ComboRow = combo_row(
+        &gettext("Tile Colors"),
+        &palette_labels,
+        Palette::ALL.iter().position(|&p| p == palette).unwrap_or(0),
+    );
+    let app_2: Option<gtk4::gio::Application> = app.clone();
+    palette_row.connect_selected_notify(move |row: &ComboRow| {
+        if let (Some(app), Some(palette)) = (&app_2, Palette::ALL.get(row.selected() as usize)) {
+            app.change_action_state("palette", &palette.name().to_variant());
+        }
+    });
+    appearance_group.add(&palette_row);
+
+    let patterns_row: SwitchRow = SwitchRow::builder()
+        .title(gettext("Show Patterns"))
+        .subtitle(gettext(
+            "Stripes for right letters and dots for misplaced ones",
+        ))
+        .active(settings.patterns())
+        .build();
+    patterns_row.connect_active_notify(move |row: &SwitchRow| {
+        if let Some(app) = &app {
+            app.change_action_state("patterns", &row.is_active().to_variant());
+        }
+    });
+    appearance_group.add(&patterns_row);
+
+    let animations_row: SwitchRow = SwitchRow::builder()
+        .title(gettext("Animations"))
+        .subtitle(gettext(
+            "Only shown if animations are also turned on in the system settings",
+        ))
+        .active(settings.animations())
+        .build();
+    let settings_2: Rc<AppSettings> = settings.clone();
+    animations_row.connect_active_notify(move |row: &SwitchRow| {
+        settings_2.set_animations(row.is_active());
+        animation::set_allowed(row.is_active());
+    });
+    appearance_group.add(&animations_row);
+
+    // The language is only read at startup, and is kept apart from the word lists so the
+    // interface and the words can be in different languages
+    let mut language_labels: Vec<String> = vec![gettext("System Default")];
+    language_labels.extend(LANGUAGES.iter().map(|(_, name)| name.to_string()));
+    let language: String = settings.language();
+    let language_row: ComboRow = combo_row(
+        &gettext("Language"),
+        &language_labels,
+        LANGUAGES
+            .iter()
+            .position(|(code, _)| *code == language)
+            .map_or(0, |index| index + 1),
+    );
+    language_row.set_subtitle(&gettext("Applies after restarting"));
+    let settings_2: Rc<AppSettings> = settings.clone();
+    language_row.connect_selected_notify(move |row: &ComboRow| {
+        let code: &str = match row.selected() as usize {
+            0 => "",
+            index => LANGUAGES.get(index - 1).map_or("", |(code, _)| code),
+        };
+        settings_2.set_language(code);
+    });
+    appearance_group.add(&language_row);
+
+    let lists_group: PreferencesGroup = PreferencesGroup::builder()
+        .title(gettext("Word Lists"))
+        .description(gettext(
+            "Files with one word per line. Changes apply from the next game.",
+        ))
+        .build();
+    let settings_2: Rc<AppSettings> = settings.clone();
+    let game_changed_2: Rc<dyn Fn()> = game_changed.clone();
+    lists_group.add(&list_row(
+        &gettext("Valid Guesses"),
+        &options.word_list,
+        move |path| {
+            settings_2.set_word_list(path);
+            game_changed_2();
+        },
+    ));
+    let settings_2: Rc<AppSettings> = settings.clone();
+    let game_changed_2: Rc<dyn Fn()> = game_changed.clone();
+    lists_group.add(&list_row(
+        &gettext("Possible Answers"),
+        &options.answer_list,
+        move |path| {
+            settings_2.set_answer_list(path);
+            game_changed_2();
+        },
+    ));
+
+    let dictionary_group: PreferencesGroup = PreferencesGroup::builder()
+        .title(gettext("Dictionary"))
+        .description(gettext(
+            "An optional file of definitions to show after each game, one per line as the word, \
+             part of speech and definition separated by tabs. Changes apply from the next game.",
+        ))
+        .build();
+    let settings_2: Rc<AppSettings> = settings.clone();
+    dictionary_group.add(&list_row(
+        &gettext("Definitions"),
+        &settings.dictionary(),
+        move |path| {
+            settings_2.set_dictionary(path);
+            game_changed();
+        },
+    ));
+
+    let page: PreferencesPage = PreferencesPage::new();
+    page.add(&game_group);
+    page.add(&clock_group);
+    page.add(&appearance_group);
+    page.add(&lists_group);
+    page.add(&dictionary_group);
+    let dialog: PreferencesDialog = PreferencesDialog::new();
+    dialog.add(&page);
+    dialog.present(Some(parent));
+}
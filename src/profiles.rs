@@ -0,0 +1,302 @@
+use crate::storage::{app_dir, load_key_values, save_key_values};
+use std::collections::HashMap;
+use std::fs::{read_dir, remove_dir_all, remove_file};
+use std::io;
+use std::path::PathBuf;
+
+/// The id of the profile that keeps its data straight in the app's directory, where it was
+/// before there were profiles
+pub const DEFAULT_PROFILE: &str = "default";
+/// The name of the default profile until it's renamed
+pub const DEFAULT_PROFILE_NAME: &str = "Player";
+
+/// One player's stats, history, settings and unfinished game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Profile {
+    /// The name of the profile's directory, which never changes
+    pub id: String,
+    pub name: String,
+}
+
+/// Get the directory a profile's data is kept in
+pub fn profile_dir(id: &str) -> PathBuf {
+    if id == DEFAULT_PROFILE {
+        return app_dir();
+    }
+    return app_dir().join("profiles").join(id);
+}
+
+/// Every profile on this machine, and the one being played
+#[derive(Clone, Debug)]
+pub struct Profiles {
+    /// The profiles, sorted by name
+    pub profiles: Vec<Profile>,
+    /// The id of the profile being played
+    pub active: String,
+}
+
+impl Profiles {
+    /// Get the file the profiles are saved in, which is shared by all of them
+    pub fn path() -> PathBuf {
+        return app_dir().join("profiles.txt");
+    }
+
+    /// Load the saved profiles. There's always at least one.
+    pub fn load() -> Profiles {
+        let values: HashMap<String, String> = load_key_values(&Profiles::path());
+        let mut profiles: Profiles = Profiles {
+            profiles: values
+                .iter()
+                .filter_map(|(key, name)| {
+                    let id: &str = key.strip_prefix("profile.")?;
+                    Some(Profile {
+                        id: id.to_string(),
+                        name: name.clone(),
+                    })
+                })
+                .collect(),
+            active: values.get("active").cloned().unwrap_or_default(),
+        };
+        if profiles.profiles.is_empty() {
+            profiles.profiles.push(Profile {
+                id: DEFAULT_PROFILE.to_string(),
+                name: DEFAULT_PROFILE_NAME.to_string(),
+            });
+        }
+        // Sorted first, so a missing active profile always falls back to the same one
+        profiles.sort();
+        if profiles.get(&profiles.active).is_none() {
+            profiles.active = profiles.profiles[0].id.clone();
+        }
+        return profiles;
+    }
+
+    /// Save the profiles
+    pub fn save(&self) -> io::Result<()> {
+        let mut values: Vec<(String, String)> = vec![(String::from("active"), self.active.clone())];
+        for profile in &self.profiles {
+            values.push((format!("profile.{}", profile.id), profile.name.clone()));
+        }
+        let values: Vec<(&str, String)> = values
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect();
+        return save_key_values(&Profiles::path(), &values);
+    }
+
+    /// Get the id of the saved active profile
+    pub fn active_id() -> String {
+        return Profiles::load().active;
+    }
+
+    /// Get the profile being played
+    pub fn active(&self) -> &Profile {
+        return self.get(&self.active).unwrap_or(&self.profiles[0]);
+    }
+
+    /// Look up a profile by its id
+    pub fn get(&self, id: &str) -> Option<&Profile> {
+        return self.profiles.iter().find(|profile| profile.id == id);
+    }
+
+    /// Put the profiles in order of their names
+    fn sort(&mut self) -> () {
+        self.profiles
+            .sort_by_key(|profile| (profile.name.to_lowercase(), profile.id.clone()));
+    }
+
+    /// Check a name for a profile, other than the one with the id given, returning it trimmed
+    fn check_name(&self, name: &str, id: Option<&str>) -> Result<String, String> {
+        let name: &str = name.trim();
+        if name.is_empty() {
+            return Err(String::from("A profile needs a name"));
+        }
+        // Names are saved one per line, so they can't break lines
+        if name.chars().any(char::is_control) {
+            return Err(String::from("A profile name can't have control characters"));
+        }
+        let taken: bool = self.profiles.iter().any(|profile| {
+            Some(profile.id.as_str()) != id && profile.name.to_lowercase() == name.to_lowercase()
+        });
+        if taken {
+            return Err(format!("There is already a profile called \"{}\"", name));
+        }
+        return Ok(name.to_string());
+    }
+
+    /// Add a profile, returning its id
+    pub fn add(&mut self, name: &str) -> Result<String, String> {
+        let name: String = self.check_name(name, None)?;
+        // Skip ids whose directory is still there, in case a deleted profile's data couldn't
+        // all be removed
+        let id: String = (1..)
+            .map(|n: u64| n.to_string())
+            .find(|id| self.get(id).is_none() && !profile_dir(id).exists())
+            .unwrap_or_default();
+        self.profiles.push(Profile {
+            id: id.clone(),
+            name,
+        });
+        self.sort();
+        return Ok(id);
+    }
+
+    /// Give a profile a new name
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<(), String> {
+        let name: String = self.check_name(name, Some(id))?;
+        let Some(profile) = self.profiles.iter_mut().find(|profile| profile.id == id) else {
+            return Err(String::from("No such profile"));
+        };
+        profile.name = name;
+        self.sort();
+        return Ok(());
+    }
+
+    /// Remove a profile and delete everything saved for it. The active profile can't be removed.
+    pub fn remove(&mut self, id: &str) -> Result<(), String> {
+        if id == self.active {
+            return Err(String::from("The profile being played can't be deleted"));
+        }
+        if self.get(id).is_none() {
+            return Err(String::from("No such profile"));
+        }
+        delete_profile_data(id)
+            .map_err(|error| format!("Failed to delete the profile's data: {}", error))?;
+        self.profiles.retain(|profile| profile.id != id);
+        return Ok(());
+    }
+}
+
+/// Delete the files saved for a profile
+fn delete_profile_data(id: &str) -> io::Result<()> {
+    let dir: PathBuf = profile_dir(id);
+    if id != DEFAULT_PROFILE {
+        return match remove_dir_all(&dir) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        };
+    }
+    // The default profile shares its directory with the other profiles
+    let Ok(entries) = read_dir(&dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        if entry.file_type()?.is_file() && entry.path() != Profiles::path() {
+            remove_file(entry.path())?;
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_dir;
+    use std::fs::{create_dir_all, write};
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    /// Point the app's data at an empty temporary directory. The lock it returns keeps other
+    /// tests from using the directory at the same time.
+    fn temp_data_dir() -> MutexGuard<'static, ()> {
+        static LOCK: Mutex<()> = Mutex::new(());
+        let guard: MutexGuard<()> = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let dir: PathBuf =
+            std::env::temp_dir().join(format!("rustle-profiles-{}", std::process::id()));
+        let _ = remove_dir_all(&dir);
+        // No other test reads the environment
+        unsafe {
+            std::env::set_var("XDG_DATA_HOME", &dir);
+        }
+        return guard;
+    }
+
+    /// Get the names of the profiles, in order
+    fn names(profiles: &Profiles) -> Vec<&str> {
+        return profiles
+            .profiles
+            .iter()
+            .map(|profile| profile.name.as_str())
+            .collect();
+    }
+
+    #[test]
+    fn profiles_are_added_and_renamed() -> () {
+        let _lock: MutexGuard<()> = temp_data_dir();
+        let mut profiles: Profiles = Profiles::load();
+        assert_eq!(names(&profiles), ["Player"]);
+        assert_eq!(profiles.active, DEFAULT_PROFILE);
+
+        assert_eq!(profiles.add(" Zoe "), Ok(String::from("1")));
+        assert_eq!(profiles.add("alice"), Ok(String::from("2")));
+        assert!(profiles.add("ZOE").is_err(), "names are unique");
+        assert!(profiles.add("  ").is_err());
+        assert!(profiles.add("two\nlines").is_err());
+        assert_eq!(names(&profiles), ["alice", "Player", "Zoe"]);
+
+        assert_eq!(profiles.rename("2", "Bob"), Ok(()));
+        assert_eq!(
+            profiles.rename("2", "bob"),
+            Ok(()),
+            "a profile keeps its own name"
+        );
+        assert!(profiles.rename("1", "Player").is_err());
+        assert!(profiles.rename("9", "Nobody").is_err());
+        assert_eq!(names(&profiles), ["bob", "Player", "Zoe"]);
+
+        profiles.active = String::from("1");
+        profiles.save().unwrap();
+        let loaded: Profiles = Profiles::load();
+        assert_eq!(loaded.profiles, profiles.profiles);
+        assert_eq!(loaded.active().name, "Zoe");
+        assert_eq!(data_dir(), app_dir().join("profiles").join("1"));
+    }
+
+    #[test]
+    fn a_missing_active_profile_falls_back() -> () {
+        let _lock: MutexGuard<()> = temp_data_dir();
+        let mut profiles: Profiles = Profiles::load();
+        profiles.add("Zoe").unwrap();
+        let id: String = profiles.add("Alice").unwrap();
+        profiles.active = String::from("7");
+        profiles.save().unwrap();
+        // The first by name
+        for _ in 0..10 {
+            assert_eq!(Profiles::load().active, id);
+        }
+    }
+
+    #[test]
+    fn removing_a_profile_deletes_its_data() -> () {
+        let _lock: MutexGuard<()> = temp_data_dir();
+        let mut profiles: Profiles = Profiles::load();
+        let id: String = profiles.add("Zoe").unwrap();
+        create_dir_all(profile_dir(&id)).unwrap();
+        write(profile_dir(&id).join("stats.txt"), "played=1\n").unwrap();
+
+        profiles.active = id.clone();
+        assert!(profiles.remove(&id).is_err(), "the active profile stays");
+        profiles.active = String::from(DEFAULT_PROFILE);
+        assert_eq!(profiles.remove(&id), Ok(()));
+        assert!(!profile_dir(&id).exists());
+        assert_eq!(names(&profiles), ["Player"]);
+        assert!(profiles.remove(&id).is_err());
+    }
+
+    #[test]
+    fn removing_the_default_profile_keeps_the_others() -> () {
+        let _lock: MutexGuard<()> = temp_data_dir();
+        let mut profiles: Profiles = Profiles::load();
+        let id: String = profiles.add("Zoe").unwrap();
+        profiles.active = id.clone();
+        profiles.save().unwrap();
+        write(app_dir().join("stats.txt"), "played=1\n").unwrap();
+        create_dir_all(profile_dir(&id)).unwrap();
+        write(profile_dir(&id).join("stats.txt"), "played=2\n").unwrap();
+
+        assert_eq!(profiles.remove(DEFAULT_PROFILE), Ok(()));
+        assert!(!app_dir().join("stats.txt").exists());
+        assert!(Profiles::path().exists());
+        assert!(profile_dir(&id).join("stats.txt").exists());
+        assert_eq!(names(&profiles), ["Zoe"]);
+    }
+}
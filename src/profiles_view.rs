@@ -0,0 +1,163 @@
+use crate::i18n::fill;
+use gettextrs::gettext;
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4::glib::WeakRef;
+use gtk4::{Align, Button, Label, Widget};
+use libadwaita::prelude::{
+    AdwDialogExt, AlertDialogExt, AlertDialogExtManual, EntryRowExt, PreferencesDialogExt,
+    PreferencesGroupExt, PreferencesPageExt,
+};
+use libadwaita::{
+    AlertDialog, EntryRow, PreferencesDialog, PreferencesGroup, PreferencesPage,
+    ResponseAppearance, Toast,
+};
+use rustle::profiles::Profiles;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+/// The dialog for adding, renaming and deleting profiles
+struct ProfilesDialog {
+    /// Kept weakly, as the dialog's own rows keep this alive
+    dialog: WeakRef<PreferencesDialog>,
+    group: PreferencesGroup,
+    /// The rows of the listed profiles
+    rows: RefCell<Vec<EntryRow>>,
+    /// Called after the profiles have changed
+    changed: Box<dyn Fn()>,
+}
+
+impl ProfilesDialog {
+    /// List the saved profiles, replacing the rows already there
+    fn refresh(self: &Rc<Self>) -> () {
+        for row in self.rows.take() {
+            self.group.remove(&row);
+        }
+        let profiles: Profiles = Profiles::load();
+        let mut rows: Vec<EntryRow> = Vec::new();
+        for profile in &profiles.profiles {
+            let row: EntryRow = EntryRow::builder()
+                .title(gettext("Name"))
+                .text(&profile.name)
+                .show_apply_button(true)
+                .build();
+            let id: String = profile.id.clone();
+            let this: Weak<ProfilesDialog> = Rc::downgrade(self);
+            row.connect_apply(move |row: &EntryRow| {
+                if let Some(this) = this.upgrade() {
+                    let name: String = row.text().to_string();
+                    this.change(|profiles: &mut Profiles| profiles.rename(&id, &name));
+                }
+            });
+            if profile.id == profiles.active {
+                let playing: Label = Label::new(Some(&gettext("Playing")));
+                playing.add_css_class("dim-label");
+                row.add_suffix(&playing);
+            } else {
+                let delete: Button = Button::from_icon_name("user-trash-symbolic");
+                delete.add_css_class("flat");
+                delete.set_valign(Align::Center);
+                delete.set_tooltip_text(Some(&gettext("Delete Profile")));
+                let id: String = profile.id.clone();
+                let name: String = profile.name.clone();
+                let this: Weak<ProfilesDialog> = Rc::downgrade(self);
+                delete.connect_clicked(move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.confirm_delete(&id, &name);
+                    }
+                });
+                row.add_suffix(&delete);
+            }
+            self.group.add(&row);
+            rows.push(row);
+        }
+        *self.rows.borrow_mut() = rows;
+    }
+
+    /// Make a change to the saved profiles, returning whether it worked. The player is told
+    /// if it didn't.
+    fn change(self: &Rc<Self>, change: impl FnOnce(&mut Profiles) -> Result<(), String>) -> bool {
+        let mut profiles: Profiles = Profiles::load();
+        let result: Result<(), String> =
+            change(&mut profiles).and_then(|_| profiles.save().map_err(|error| error.to_string()));
+        if let Err(message) = result {
+            if let Some(dialog) = self.dialog.upgrade() {
+                dialog.add_toast(Toast::new(&message));
+            }
+            return false;
+        }
+        self.refresh();
+        (self.changed)();
+        return true;
+    }
+
+    /// Ask before deleting a profile and everything saved for it
+    fn confirm_delete(self: &Rc<Self>, id: &str, name: &str) -> () {
+        let alert: AlertDialog = AlertDialog::new(
+            Some(&fill(&gettext("Delete “{}”?"), &[name])),
+            Some(&gettext(
+                "Their statistics, history, achievements and settings will be deleted for good",
+            )),
+        );
+        alert.add_responses(&[
+            ("cancel", &gettext("Cancel")),
+            ("delete", &gettext("Delete")),
+        ]);
+        alert.set_response_appearance("delete", ResponseAppearance::Destructive);
+        alert.set_default_response(Some("cancel"));
+        alert.set_close_response("cancel");
+        let id: String = id.to_string();
+        let this: Weak<ProfilesDialog> = Rc::downgrade(self);
+        alert.connect_response(None, move |_, response: &str| {
+            if response == "delete"
+                && let Some(this) = this.upgrade()
+            {
+                this.change(|profiles: &mut Profiles| profiles.remove(&id));
+            }
+        });
+        alert.present(self.dialog.upgrade().as_ref());
+    }
+}
+
+/// Show the dialog for managing profiles. `changed` is called whenever one is added, renamed or
+/// deleted.
+pub fn show_profiles(parent: &impl IsA<Widget>, changed: impl Fn() + 'static) -> () {
+    let group: PreferencesGroup = PreferencesGroup::builder()
+        .title(gettext("Profiles"))
+        .description(gettext(
+            "Each profile has its own statistics, history, achievements and settings",
+        ))
+        .build();
+    let new_group: PreferencesGroup = PreferencesGroup::new();
+    let new_row: EntryRow = EntryRow::builder()
+        .title(gettext("New Profile"))
+        .show_apply_button(true)
+        .build();
+    new_group.add(&new_row);
+
+    let page: PreferencesPage = PreferencesPage::new();
+    page.add(&group);
+    page.add(&new_group);
+    let dialog: PreferencesDialog = PreferencesDialog::builder()
+        .title(gettext("Profiles"))
+        .build();
+    dialog.add(&page);
+
+    let profiles_dialog: Rc<ProfilesDialog> = Rc::new(ProfilesDialog {
+        dialog: dialog.downgrade(),
+        group,
+        rows: RefCell::new(Vec::new()),
+        changed: Box::new(changed),
+    });
+    profiles_dialog.refresh();
+    let profiles_dialog_2: Rc<ProfilesDialog> = profiles_dialog.clone();
+    new_row.connect_apply(move |row: &EntryRow| {
+        let name: String = row.text().to_string();
+        let added: bool =
+            profiles_dialog_2.change(|profiles: &mut Profiles| profiles.add(&name).map(|_| ()));
+        if added {
+            row.set_text("");
+        }
+    });
+    dialog.present(Some(parent));
+}
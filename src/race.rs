@@ -0,0 +1,1235 @@
+use crate::game::{new_seed, pattern_string, GameMode, GameOptions, WordLists};
+use crate::history::parse_pattern;
+use rand::{rng, Rng};
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The version of the race protocol in docs/race.md. It changes whenever a message is added or
+/// changes meaning, and hosts turn away players on any other version.
+pub const PROTOCOL_VERSION: u32 = 3;
+/// The port races are hosted on unless another is picked
+pub const DEFAULT_PORT: u16 = 7373;
+/// The longest name a player can have, in characters
+pub const MAX_NAME_LENGTH: usize = 24;
+/// How long a player keeps trying to get back into a race after losing the connection
+pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long to wait between attempts to reconnect
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+/// How often the host pings every player
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+/// How long either side waits without hearing anything before taking the connection to be lost,
+/// for when the other machine goes away without closing it, such as by going to sleep
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long to wait for the host when connecting
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The settings of one race, which every player's game is set up from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RaceSettings {
+    /// Counts up from 1 with each race the host starts
+    pub round: u32,
+    /// Picks the answer from the answer list, so every player gets the same one
+    pub seed: u64,
+    pub word_length: usize,
+    pub max_guesses: usize,
+    pub hard_mode: bool,
+    /// The fingerprint of the host's answer list, which a player's list has to match to get the
+    /// same answer from the seed
+    pub answers: String,
+}
+
+impl RaceSettings {
+    /// Get the options of a player's game in the race, keeping their own word lists
+    pub fn game_options(&self, options: &GameOptions) -> GameOptions {
+        return GameOptions {
+            mode: GameMode::Practice,
+            word_length: self.word_length,
+            max_guesses: self.max_guesses,
+            hard_mode: self.hard_mode,
+            seed: None,
+            ..options.clone()
+        };
+    }
+}
+
+/// A message between a race's host and a player, sent as one line of text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Message {
+    /// Player to host, first: who is connecting. The token stays the same when reconnecting.
+    Hello {
+        version: u32,
+        token: String,
+        name: String,
+    },
+    /// Player to host: the colors a row of the player's own board was scored
+    Score {
+        round: u32,
+        row: usize,
+        pattern: String,
+    },
+    /// Host to player: the player is in the race, with an id
+    Welcome { version: u32, id: usize },
+    /// Host to player: a player is in the race
+    Player { id: usize, name: String },
+    /// Host to player: a player lost their connection, and may come back
+    Left { id: usize },
+    /// Host to player: a race has started
+    Race(RaceSettings),
+    /// Host to player: a player scored a row of the current race
+    Progress { id: usize, pattern: String },
+    /// Host to player: a player solved the word first, ending the race
+    Winner { id: usize, guesses: usize },
+    /// Host to player: the race ended without anyone solving the word
+    End,
+    /// Host to player: something was wrong, and the connection is being closed
+    Error(String),
+    /// Host to player: the player should answer with `Pong`, to show they're still there
+    Ping,
+    /// Player to host: the answer to `Ping`
+    Pong,
+    /// Either way: the sender is leaving for good
+    Bye,
+}
+
+/// Check that a pattern only has the letters `pattern_string` writes
+fn valid_pattern(pattern: &str) -> bool {
+    return !pattern.is_empty() && pattern.chars().all(|c| matches!(c, 'G' | 'Y' | 'B'));
+}
+
+/// Tidy up a name given by a player, dropping control characters and shortening it if needed
+pub fn clean_name(name: &str) -> String {
+    return name
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>()
+        .trim()
+        .chars()
+        .take(MAX_NAME_LENGTH)
+        .collect::<String>()
+        .trim()
+        .to_string();
+}
+
+impl Message {
+    /// Read a message from a line. Anything that isn't a known message is None, so it can be
+    /// ignored.
+    pub fn parse(line: &str) -> Option<Message> {
+        let line: &str = line.trim_end_matches(['\r', '\n']);
+        let (command, rest): (&str, &str) = line.split_once(' ').unwrap_or((line, ""));
+        let mut fields = rest.split(' ');
+        let mut number = || -> Option<u64> { fields.next()?.parse().ok() };
+        let message: Message = match command {
+            "hello" => {
+                let mut parts = rest.splitn(3, ' ');
+                Message::Hello {
+                    version: parts.next()?.parse().ok()?,
+                    token: parts.next()?.to_string(),
+                    name: clean_name(parts.next().unwrap_or("")),
+                }
+            }
+            "score" => {
+                let round: u32 = number()? as u32;
+                let row: usize = number()? as usize;
+                let pattern: String = fields.next()?.to_string();
+                if !valid_pattern(&pattern) {
+                    return None;
+                }
+                Message::Score {
+                    round,
+                    row,
+                    pattern,
+                }
+            }
+            "welcome" => Message::Welcome {
+                version: number()? as u32,
+                id: number()? as usize,
+            },
+            "player" => {
+                let (id, name): (&str, &str) = rest.split_once(' ')?;
+                Message::Player {
+                    id: id.parse().ok()?,
+                    name: clean_name(name),
+                }
+            }
+            "left" => Message::Left {
+                id: number()? as usize,
+            },
+            "race" => Message::Race(RaceSettings {
+                round: number()? as u32,
+                seed: number()?,
+                word_length: number()? as usize,
+                max_guesses: number()? as usize,
+                hard_mode: number()? != 0,
+                answers: fields.next()?.to_string(),
+            }),
+            "progress" => {
+                let id: usize = number()? as usize;
+                let pattern: String = fields.next()?.to_string();
+                if !valid_pattern(&pattern) {
+                    return None;
+                }
+                Message::Progress { id, pattern }
+            }
+            "winner" => Message::Winner {
+                id: number()? as usize,
+                guesses: number()? as usize,
+            },
+            "end" => Message::End,
+            "error" => Message::Error(rest.to_string()),
+            "ping" => Message::Ping,
+            "pong" => Message::Pong,
+            "bye" => Message::Bye,
+            _ => return None,
+        };
+        return Some(message);
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Message::Hello {
+                version,
+                token,
+                name,
+            } => write!(f, "hello {} {} {}", version, token, name),
+            Message::Score {
+                round,
+                row,
+                pattern,
+            } => write!(f, "score {} {} {}", round, row, pattern),
+            Message::Welcome { version, id } => write!(f, "welcome {} {}", version, id),
+            Message::Player { id, name } => write!(f, "player {} {}", id, name),
+            Message::Left { id } => write!(f, "left {}", id),
+            Message::Race(settings) => write!(
+                f,
+                "race {} {} {} {} {} {}",
+                settings.round,
+                settings.seed,
+                settings.word_length,
+                settings.max_guesses,
+                settings.hard_mode as u8,
+                settings.answers
+            ),
+            Message::Progress { id, pattern } => write!(f, "progress {} {}", id, pattern),
+            Message::Winner { id, guesses } => write!(f, "winner {} {}", id, guesses),
+            Message::End => write!(f, "end"),
+            Message::Error(message) => write!(f, "error {}", message),
+            Message::Ping => write!(f, "ping"),
+            Message::Pong => write!(f, "pong"),
+            Message::Bye => write!(f, "bye"),
+        };
+    }
+}
+
+/// Send a message down a connection
+fn send(stream: &mut TcpStream, message: &Message) -> std::io::Result<()> {
+    writeln!(stream, "{}", message)?;
+    return stream.flush();
+}
+
+/// A player in a race, as the host sees them
+struct HostPlayer {
+    id: usize,
+    token: String,
+    name: String,
+    /// The player's connection, while they're connected
+    stream: Option<TcpStream>,
+    /// Counts the player's connections, so a dropped one that was replaced isn't mistaken for
+    /// the current one
+    connection: u64,
+    /// The patterns of the rows the player has scored in the current race
+    rows: Vec<String>,
+    /// When the player lost their connection, if they aren't connected
+    left: Option<Instant>,
+}
+
+impl HostPlayer {
+    /// Check if the player has solved the word or run out of guesses
+    fn finished(&self, max_guesses: usize) -> bool {
+        return self.rows.len() >= max_guesses
+            || self
+                .rows
+                .last()
+                .is_some_and(|pattern| pattern.chars().all(|c| c == 'G'));
+    }
+}
+
+/// How long the host waits for players, which tests shorten
+#[derive(Clone, Copy, Debug)]
+struct HostTiming {
+    /// How often every player is pinged
+    heartbeat_interval: Duration,
+    /// How long a player can go without sending anything before they're taken to be gone
+    heartbeat_timeout: Duration,
+    /// How long a player who lost their connection has to come back
+    reconnect_timeout: Duration,
+}
+
+/// The timing races are normally hosted with
+const HOST_TIMING: HostTiming = HostTiming {
+    heartbeat_interval: HEARTBEAT_INTERVAL,
+    heartbeat_timeout: HEARTBEAT_TIMEOUT,
+    reconnect_timeout: RECONNECT_TIMEOUT,
+};
+
+/// Everything the host keeps track of, shared between the connections' threads
+struct HostState {
+    options: GameOptions,
+    /// The fingerprint of the host's answer list
+    answers: String,
+    timing: HostTiming,
+    players: Vec<HostPlayer>,
+    /// The settings of the latest race, if one has started
+    settings: Option<RaceSettings>,
+    /// Whether the latest race is still being played
+    running: bool,
+    /// The id of the player who won the latest race and their number of guesses
+    winner: Option<(usize, usize)>,
+    /// Counts every connection made, for telling them apart
+    connections: u64,
+    /// Set once the host has stopped
+    closed: bool,
+}
+
+impl HostState {
+    /// Send a message to one player, if they're connected
+    fn send_to(&mut self, index: usize, message: &Message) -> () {
+        if let Some(stream) = self.players[index].stream.as_mut() {
+            // A failed write shows up as a dropped connection on the reading side
+            let _ = send(stream, message);
+        }
+    }
+
+    /// Send a message to every connected player
+    fn broadcast(&mut self, message: &Message) -> () {
+        for index in 0..self.players.len() {
+            self.send_to(index, message);
+        }
+    }
+
+    /// Let a player into the race, or back into it, returning their index and connection number
+    fn join(
+        &mut self,
+        mut stream: TcpStream,
+        version: u32,
+        token: &str,
+        name: &str,
+    ) -> Option<(usize, u64)> {
+        let refusal: Option<String> =
+            if version != PROTOCOL_VERSION {
+                Some(format!(
+                    "The host speaks version {} of the race protocol, not {}",
+                    PROTOCOL_VERSION, version
+                ))
+            } else if self.closed {
+                Some(String::from("The race is over"))
+            } else if name.is_empty() {
+                Some(String::from("Players need a name"))
+            } else if self.players.iter().any(|player| {
+                player.token != token && player.stream.is_some() && player.name == name
+            }) {
+                Some(format!("Someone called {} is already in the race", name))
+            } else if self.players.iter().any(|player| {
+                player.token == token
+                    && player
+                        .left
+                        .is_some_and(|left| left.elapsed() > self.timing.reconnect_timeout)
+            }) {
+                Some(String::from("It's too late to get back into the race"))
+            } else {
+                None
+            };
+        if let Some(refusal) = refusal {
+            let _ = send(&mut stream, &Message::Error(refusal));
+            return None;
+        }
+
+        self.connections += 1;
+        let index: usize = match self.players.iter().position(|player| player.token == token) {
+            Some(index) => {
+                if let Some(old) = self.players[index].stream.take() {
+                    let _ = old.shutdown(Shutdown::Both);
+                }
+                self.players[index].name = name.to_string();
+                index
+            }
+            None => {
+                self.players.push(HostPlayer {
+                    id: self.players.len(),
+                    token: token.to_string(),
+                    name: name.to_string(),
+                    stream: None,
+                    connection: 0,
+                    rows: Vec::new(),
+                    left: None,
+                });
+                self.players.len() - 1
+            }
+        };
+        let id: usize = self.players[index].id;
+        self.broadcast(&Message::Player {
+            id,
+            name: name.to_string(),
+        });
+        self.players[index].stream = Some(stream);
+        self.players[index].connection = self.connections;
+        self.players[index].left = None;
+
+        // Tell the player everything they missed
+        let mut replay: Vec<Message> = vec![Message::Welcome {
+            version: PROTOCOL_VERSION,
+            id,
+        }];
+        for player in self.players.iter().filter(|player| player.stream.is_some()) {
+            replay.push(Message::Player {
+                id: player.id,
+                name: player.name.clone(),
+            });
+        }
+        if let Some(settings) = &self.settings {
+            replay.push(Message::Race(settings.clone()));
+            for player in &self.players {
+                for pattern in &player.rows {
+                    replay.push(Message::Progress {
+                        id: player.id,
+                        pattern: pattern.clone(),
+                    });
+                }
+            }
+            match self.winner {
+                Some((id, guesses)) => replay.push(Message::Winner { id, guesses }),
+                None if !self.running => replay.push(Message::End),
+                None => {}
+            }
+        }
+        for message in &replay {
+            self.send_to(index, message);
+        }
+        return Some((index, self.connections));
+    }
+
+    /// Record a row a player scored, ending the race if they solved the word
+    fn score(&mut self, index: usize, round: u32, row: usize, pattern: String) -> () {
+        let Some(settings) = self.settings.clone() else {
+            return;
+        };
+        let player: &HostPlayer = &self.players[index];
+        // Rows sent again after reconnecting, or from an earlier race, are ignored
+        if !self.running
+            || round != settings.round
+            || row != player.rows.len() + 1
+            || pattern.chars().count() != settings.word_length
+            || player.finished(settings.max_guesses)
+        {
+            return;
+        }
+        let solved: bool = pattern.chars().all(|c| c == 'G');
+        self.players[index].rows.push(pattern.clone());
+        let id: usize = self.players[index].id;
+        self.broadcast(&Message::Progress { id, pattern });
+        if solved {
+            self.running = false;
+            self.winner = Some((id, row));
+            self.broadcast(&Message::Winner { id, guesses: row });
+        } else {
+            self.check_end();
+        }
+    }
+
+    /// End the race if every connected player has finished without solving the word
+    fn check_end(&mut self) -> () {
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        let max_guesses: usize = settings.max_guesses;
+        let everyone_finished: bool = self
+            .players
+            .iter()
+            .filter(|player| player.stream.is_some())
+            .all(|player| player.finished(max_guesses));
+        if self.running && everyone_finished {
+            self.running = false;
+            self.broadcast(&Message::End);
+        }
+    }
+
+    /// Handle a connection closing. A player who said goodbye is removed, and anyone else is
+    /// kept so they can reconnect.
+    fn disconnect(&mut self, index: usize, connection: u64, goodbye: bool) -> () {
+        let player: &mut HostPlayer = &mut self.players[index];
+        if player.connection != connection || player.stream.is_none() {
+            return;
+        }
+        // Closing it tells the player, if they're still there, that they need to reconnect
+        if let Some(stream) = player.stream.take() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        player.left = Some(Instant::now());
+        if goodbye {
+            // The token is forgotten, so the same name can join again as someone new
+            player.token.clear();
+        }
+        let id: usize = player.id;
+        self.broadcast(&Message::Left { id });
+        self.check_end();
+    }
+}
+
+/// Speak to one player until their connection closes
+fn serve(stream: TcpStream, state: Arc<Mutex<HostState>>) -> () {
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    let _ = reader.set_read_timeout(Some(CONNECT_TIMEOUT));
+    let mut lines: Lines<BufReader<TcpStream>> = BufReader::new(reader).lines();
+    let joined: Option<(usize, u64)> = match lines.next().and_then(|line| line.ok()) {
+        Some(line) => match Message::parse(&line) {
+            Some(Message::Hello {
+                version,
+                token,
+                name,
+            }) => match stream.try_clone() {
+                Ok(writer) => lock(&state).join(writer, version, &token, &name),
+                Err(_) => None,
+            },
+            _ => None,
+        },
+        None => None,
+    };
+    let Some((index, connection)) = joined else {
+        let _ = stream.shutdown(Shutdown::Both);
+        return;
+    };
+    // Players answer the host's pings, so a quiet connection has been lost
+    let _ = stream.set_read_timeout(Some(lock(&state).timing.heartbeat_timeout));
+    for line in lines {
+        let Ok(line) = line else {
+            break;
+        };
+        match Message::parse(&line) {
+            Some(Message::Score {
+                round,
+                row,
+                pattern,
+            }) => lock(&state).score(index, round, row, pattern),
+            Some(Message::Bye) => {
+                lock(&state).disconnect(index, connection, true);
+                return;
+            }
+            _ => {}
+        }
+    }
+    lock(&state).disconnect(index, connection, false);
+}
+
+/// Lock shared state, carrying on if a thread panicked while holding it
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    return mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+}
+
+/// How a hosted race stands
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RaceStatus {
+    /// The number of players connected
+    pub players: usize,
+    /// The number of the latest race, or 0 before the first one
+    pub round: u32,
+    /// Whether the latest race is still being played
+    pub running: bool,
+    /// The name of the player who won the latest race and their number of guesses
+    pub winner: Option<(String, usize)>,
+}
+
+/// Hosts races for players on the local network. The host's own player joins like anyone else.
+pub struct RaceHost {
+    state: Arc<Mutex<HostState>>,
+    port: u16,
+}
+
+impl RaceHost {
+    /// Start listening for players on a port, or any free port if it's 0. Races are set up
+    /// from the board size, hard mode setting and answer list in the options.
+    pub fn start(port: u16, options: &GameOptions) -> Result<RaceHost, String> {
+        return RaceHost::start_with_timing(port, options, HOST_TIMING);
+    }
+
+    /// Start listening for players, waiting on them as long as the timing says
+    fn start_with_timing(
+        port: u16,
+        options: &GameOptions,
+        timing: HostTiming,
+    ) -> Result<RaceHost, String> {
+        let answers: String = WordLists::load(options)?.fingerprint();
+        let listener: TcpListener = TcpListener::bind(("0.0.0.0", port))
+            .map_err(|error| format!("Failed to host on port {}: {}", port, error))?;
+        let port: u16 = listener
+            .local_addr()
+            .map_err(|error| error.to_string())?
+            .port();
+        let state: Arc<Mutex<HostState>> = Arc::new(Mutex::new(HostState {
+            options: options.clone(),
+            answers,
+            timing,
+            players: Vec::new(),
+            settings: None,
+            running: false,
+            winner: None,
+            connections: 0,
+            closed: false,
+        }));
+        let state_2: Arc<Mutex<HostState>> = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if lock(&state_2).closed {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let state_3: Arc<Mutex<HostState>> = state_2.clone();
+                    thread::spawn(move || serve(stream, state_3));
+                }
+            }
+        });
+        let state_2: Arc<Mutex<HostState>> = state.clone();
+        thread::spawn(move || loop {
+            thread::sleep(timing.heartbeat_interval);
+            let mut state: MutexGuard<HostState> = lock(&state_2);
+            if state.closed {
+                break;
+            }
+            state.broadcast(&Message::Ping);
+        });
+        return Ok(RaceHost { state, port });
+    }
+
+    /// Get the port players connect to
+    pub fn port(&self) -> u16 {
+        return self.port;
+    }
+
+    /// Start a new race with a fresh answer, ending the one being played if there is one
+    pub fn start_race(&self) -> () {
+        let mut state: MutexGuard<HostState> = lock(&self.state);
+        let settings: RaceSettings = RaceSettings {
+            round: state.settings.as_ref().map_or(0, |settings| settings.round) + 1,
+            seed: new_seed(),
+            word_length: state.options.word_length,
+            max_guesses: state.options.max_guesses,
+            hard_mode: state.options.hard_mode,
+            answers: state.answers.clone(),
+        };
+        for player in state.players.iter_mut() {
+            player.rows.clear();
+        }
+        state.settings = Some(settings.clone());
+        state.running = true;
+        state.winner = None;
+        state.broadcast(&Message::Race(settings));
+    }
+
+    /// Get how the race stands
+    pub fn status(&self) -> RaceStatus {
+        let state: MutexGuard<HostState> = lock(&self.state);
+        return RaceStatus {
+            players: state
+                .players
+                .iter()
+                .filter(|player| player.stream.is_some())
+                .count(),
+            round: state.settings.as_ref().map_or(0, |settings| settings.round),
+            running: state.running,
+            winner: state
+                .winner
+                .map(|(id, guesses)| (state.players[id].name.clone(), guesses)),
+        };
+    }
+
+    /// Stop hosting, telling every player the race is over
+    pub fn stop(&self) -> () {
+        let mut state: MutexGuard<HostState> = lock(&self.state);
+        if state.closed {
+            return;
+        }
+        state.closed = true;
+        state.broadcast(&Message::Bye);
+        for player in state.players.iter_mut() {
+            if let Some(stream) = player.stream.take() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+        drop(state);
+        // Wake the listening thread up so it sees the host has stopped
+        let _ = TcpStream::connect_timeout(
+            &SocketAddr::from(([127, 0, 0, 1], self.port)),
+            CONNECT_TIMEOUT,
+        );
+    }
+}
+
+impl Drop for RaceHost {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Something that happened to a player's connection to a race
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RaceEvent {
+    /// The host sent a message
+    Message(Message),
+    /// The connection was lost, and is being got back
+    Disconnected,
+    /// The connection is back. The host sends everything again, so what was known of the race
+    /// should be forgotten.
+    Reconnected,
+    /// The connection is gone for good, for the reason given
+    Closed(String),
+}
+
+/// Why connecting to a host failed
+enum ConnectError {
+    /// The host couldn't be reached, which might not last
+    Unreachable(String),
+    /// The host turned the player away
+    Refused(String),
+}
+
+/// A player's connection to the host, shared with the thread that reads from it
+struct Connection {
+    stream: Option<TcpStream>,
+    /// The scores sent in the current race, sent again after reconnecting in case they were lost
+    scores: Vec<Message>,
+    /// Set once the player has left
+    closed: bool,
+}
+
+/// A player's connection to a race, which reconnects by itself if it drops
+pub struct RaceClient {
+    id: usize,
+    connection: Arc<Mutex<Connection>>,
+    events: Receiver<RaceEvent>,
+}
+
+/// Find this machine's address on the local network, for telling other players where to join.
+/// Nothing is sent: connecting a UDP socket only picks the interface a packet would leave from.
+pub fn local_address() -> Option<IpAddr> {
+    let socket: UdpSocket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("192.0.2.1", 9)).ok()?;
+    let address: IpAddr = socket.local_addr().ok()?.ip();
+    return (!address.is_unspecified()).then_some(address);
+}
+
+/// Add the default port to an address that doesn't have one
+pub fn address_with_port(address: &str) -> String {
+    let address: &str = address.trim();
+    let has_port: bool = address.parse::<SocketAddr>().is_ok()
+        || address
+            .rsplit_once(':')
+            .is_some_and(|(host, port)| !host.contains(':') && port.parse::<u16>().is_ok());
+    if has_port {
+        return address.to_string();
+    }
+    return format!("{}:{}", address, DEFAULT_PORT);
+}
+
+/// Connect to a host and introduce the player, returning the connection, the lines coming from
+/// it and the player's id
+fn handshake(
+    address: &str,
+    token: &str,
+    name: &str,
+) -> Result<(TcpStream, Lines<BufReader<TcpStream>>, usize), ConnectError> {
+    let unreachable = |error: std::io::Error| -> ConnectError {
+        return ConnectError::Unreachable(format!("Failed to connect to {}: {}", address, error));
+    };
+    let socket_address: SocketAddr = address
+        .to_socket_addrs()
+        .map_err(unreachable)?
+        .next()
+        .ok_or(ConnectError::Unreachable(format!(
+            "Failed to find {}",
+            address
+        )))?;
+    let mut stream: TcpStream =
+        TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT).map_err(unreachable)?;
+    stream
+        .set_read_timeout(Some(CONNECT_TIMEOUT))
+        .map_err(unreachable)?;
+    let hello: Message = Message::Hello {
+        version: PROTOCOL_VERSION,
+        token: token.to_string(),
+        name: name.to_string(),
+    };
+    send(&mut stream, &hello).map_err(unreachable)?;
+    let mut lines: Lines<BufReader<TcpStream>> =
+        BufReader::new(stream.try_clone().map_err(unreachable)?).lines();
+    let reply: Option<Message> = lines
+        .next()
+        .and_then(|line| line.ok())
+        .and_then(|line| Message::parse(&line));
+    return match reply {
+        Some(Message::Welcome { version, id }) if version == PROTOCOL_VERSION => {
+            // The host pings every few seconds, so a quiet connection has been lost
+            stream
+                .set_read_timeout(Some(HEARTBEAT_TIMEOUT))
+                .map_err(unreachable)?;
+            Ok((stream, lines, id))
+        }
+        Some(Message::Error(message)) => Err(ConnectError::Refused(message)),
+        _ => Err(ConnectError::Refused(format!(
+            "{} didn't answer like a race host",
+            address
+        ))),
+    };
+}
+
+/// Pass on the messages from the host until the player leaves, reconnecting whenever the
+/// connection drops
+fn read_messages(
+    address: String,
+    token: String,
+    name: String,
+    mut lines: Lines<BufReader<TcpStream>>,
+    connection: Arc<Mutex<Connection>>,
+    events: Sender<RaceEvent>,
+) -> () {
+    loop {
+        for line in lines.by_ref() {
+            let Ok(line) = line else {
+                break;
+            };
+            let Some(message) = Message::parse(&line) else {
+                continue;
+            };
+            if message == Message::Bye {
+                lock(&connection).closed = true;
+                let _ = events.send(RaceEvent::Closed(String::from("The host ended the race")));
+                return;
+            }
+            if message == Message::Ping {
+                if let Some(stream) = lock(&connection).stream.as_mut() {
+                    let _ = send(stream, &Message::Pong);
+                }
+                continue;
+            }
+            if events.send(RaceEvent::Message(message)).is_err() {
+                return;
+            }
+        }
+        if lock(&connection).closed {
+            return;
+        }
+        if let Some(stream) = lock(&connection).stream.take() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        let _ = events.send(RaceEvent::Disconnected);
+
+        let given_up: Instant = Instant::now() + RECONNECT_TIMEOUT;
+        loop {
+            thread::sleep(RECONNECT_INTERVAL);
+            if lock(&connection).closed {
+                return;
+            }
+            if Instant::now() >= given_up {
+                lock(&connection).closed = true;
+                let _ = events.send(RaceEvent::Closed(String::from(
+                    "Lost the connection to the host",
+                )));
+                return;
+            }
+            match handshake(&address, &token, &name) {
+                Ok((mut stream, new_lines, _)) => {
+                    let mut connection_val: MutexGuard<Connection> = lock(&connection);
+                    for score in &connection_val.scores {
+                        let _ = send(&mut stream, score);
+                    }
+                    connection_val.stream = Some(stream);
+                    drop(connection_val);
+                    lines = new_lines;
+                    let _ = events.send(RaceEvent::Reconnected);
+                    break;
+                }
+                Err(ConnectError::Refused(message)) => {
+                    lock(&connection).closed = true;
+                    let _ = events.send(RaceEvent::Closed(message));
+                    return;
+                }
+                Err(ConnectError::Unreachable(_)) => {}
+            }
+        }
+    }
+}
+
+impl RaceClient {
+    /// Join the race hosted at an address, given as `HOST` or `HOST:PORT`. This waits for the
+    /// host to answer.
+    pub fn connect(address: &str, name: &str) -> Result<RaceClient, String> {
+        let address: String = address_with_port(address);
+        let name: String = clean_name(name);
+        let token: String = format!("{:016x}", rng().random::<u64>());
+        let (stream, lines, id) = match handshake(&address, &token, &name) {
+            Ok(connected) => connected,
+            Err(ConnectError::Unreachable(message)) | Err(ConnectError::Refused(message)) => {
+                return Err(message);
+            }
+        };
+        let connection: Arc<Mutex<Connection>> = Arc::new(Mutex::new(Connection {
+            stream: Some(stream),
+            scores: Vec::new(),
+            closed: false,
+        }));
+        let (sender, events): (Sender<RaceEvent>, Receiver<RaceEvent>) = channel();
+        let connection_2: Arc<Mutex<Connection>> = connection.clone();
+        thread::spawn(move || read_messages(address, token, name, lines, connection_2, sender));
+        return Ok(RaceClient {
+            id,
+            connection,
+            events,
+        });
+    }
+
+    /// Get the player's id in the race
+    pub fn id(&self) -> usize {
+        return self.id;
+    }
+
+    /// Take the events that have happened since the last call, without waiting
+    pub fn poll(&self) -> Vec<RaceEvent> {
+        return self.events.try_iter().collect();
+    }
+
+    /// Wait for the next event, for up to a time
+    pub fn wait(&self, timeout: Duration) -> Option<RaceEvent> {
+        return self.events.recv_timeout(timeout).ok();
+    }
+
+    /// Tell the host how a row of the player's board was scored
+    pub fn send_score(&self, round: u32, row: usize, colors: &[usize]) -> () {
+        let score: Message = Message::Score {
+            round,
+            row,
+            pattern: pattern_string(colors),
+        };
+        let mut connection_val: MutexGuard<Connection> = lock(&self.connection);
+        connection_val.scores.retain(
+            |score| matches!(score, Message::Score { round: other, .. } if *other == round),
+        );
+        connection_val.scores.push(score.clone());
+        if let Some(stream) = connection_val.stream.as_mut() {
+            // If this is lost, it's sent again after reconnecting
+            let _ = send(stream, &score);
+        }
+    }
+
+    /// Leave the race for good
+    pub fn leave(&self) -> () {
+        let mut connection_val: MutexGuard<Connection> = lock(&self.connection);
+        if connection_val.closed {
+            return;
+        }
+        connection_val.closed = true;
+        if let Some(mut stream) = connection_val.stream.take() {
+            let _ = send(&mut stream, &Message::Bye);
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+impl Drop for RaceClient {
+    fn drop(&mut self) {
+        self.leave();
+    }
+}
+
+/// A player in a race, as the other players see them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RacePlayer {
+    pub id: usize,
+    pub name: String,
+    pub connected: bool,
+    /// The colors of each row the player has scored in the current race
+    pub rows: Vec<Vec<usize>>,
+}
+
+/// What a player knows about a race, built up from the host's messages
+#[derive(Clone, Debug, Default)]
+pub struct RaceState {
+    /// The players in the order they joined
+    pub players: Vec<RacePlayer>,
+    /// The settings of the current race, once one has started
+    pub settings: Option<RaceSettings>,
+    /// The id of the player who won the current race and their number of guesses
+    pub winner: Option<(usize, usize)>,
+    /// Set once the current race is over
+    pub over: bool,
+}
+
+impl RaceState {
+    /// Take in a message from the host
+    pub fn apply(&mut self, message: &Message) -> () {
+        match message {
+            Message::Player { id, name } => match self.player_mut(*id) {
+                Some(player) => {
+                    player.name = name.clone();
+                    player.connected = true;
+                }
+                None => self.players.push(RacePlayer {
+                    id: *id,
+                    name: name.clone(),
+                    connected: true,
+                    rows: Vec::new(),
+                }),
+            },
+            Message::Left { id } => {
+                if let Some(player) = self.player_mut(*id) {
+                    player.connected = false;
+                }
+            }
+            Message::Race(settings) => {
+                if self.settings.as_ref() != Some(settings) {
+                    for player in self.players.iter_mut() {
+                        player.rows.clear();
+                    }
+                    self.winner = None;
+                    self.over = false;
+                }
+                self.settings = Some(settings.clone());
+            }
+            Message::Progress { id, pattern } => {
+                if let Some(player) = self.player_mut(*id) {
+                    player.rows.push(parse_pattern(pattern));
+                }
+            }
+            Message::Winner { id, guesses } => {
+                self.winner = Some((*id, *guesses));
+                self.over = true;
+            }
+            Message::End => self.over = true,
+            _ => {}
+        }
+    }
+
+    /// Look up a player by id
+    pub fn player(&self, id: usize) -> Option<&RacePlayer> {
+        return self.players.iter().find(|player| player.id == id);
+    }
+
+    /// Look up a player by id, to change them
+    fn player_mut(&mut self, id: usize) -> Option<&mut RacePlayer> {
+        return self.players.iter_mut().find(|player| player.id == id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{COLOR_GRAY, COLOR_GREEN};
+
+    /// Timing short enough for tests to wait out, but long enough that players who don't
+    /// answer pings aren't dropped unless a test waits for it
+    const TEST_TIMING: HostTiming = HostTiming {
+        heartbeat_interval: Duration::from_millis(50),
+        heartbeat_timeout: Duration::from_secs(10),
+        reconnect_timeout: Duration::from_millis(300),
+    };
+    /// The longest a test waits for something to happen
+    const WAIT: Duration = Duration::from_secs(5);
+
+    /// Host races on a free port of the loopback address
+    fn host(timing: HostTiming) -> (RaceHost, String) {
+        let host: RaceHost =
+            RaceHost::start_with_timing(0, &GameOptions::default(), timing).unwrap();
+        let address: String = format!("127.0.0.1:{}", host.port());
+        return (host, address);
+    }
+
+    /// Wait for a player to be sent a message the check picks out
+    fn wait_for(client: &RaceClient, check: impl Fn(&Message) -> bool) -> Message {
+        let until: Instant = Instant::now() + WAIT;
+        while Instant::now() < until {
+            if let Some(RaceEvent::Message(message)) = client.wait(WAIT)
+                && check(&message)
+            {
+                return message;
+            }
+        }
+        panic!("the message never came");
+    }
+
+    /// Read lines from a raw connection until one the check picks out
+    fn read_until(
+        lines: &mut Lines<BufReader<TcpStream>>,
+        check: impl Fn(&Message) -> bool,
+    ) -> Message {
+        for line in lines {
+            if let Some(message) = Message::parse(&line.unwrap())
+                && check(&message)
+            {
+                return message;
+            }
+        }
+        panic!("the connection closed first");
+    }
+
+    /// Wait until the host has a number of players connected
+    fn wait_for_players(host: &RaceHost, players: usize) -> () {
+        let until: Instant = Instant::now() + WAIT;
+        while host.status().players != players {
+            assert!(Instant::now() < until, "the players never changed");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn messages_round_trip() -> () {
+        let messages: [Message; 6] = [
+            Message::Hello {
+                version: PROTOCOL_VERSION,
+                token: String::from("abc"),
+                name: String::from("Ann Lee"),
+            },
+            Message::Race(RaceSettings {
+                round: 2,
+                seed: 482913,
+                word_length: 5,
+                max_guesses: 6,
+                hard_mode: true,
+                answers: String::from("2309-6997292fdbae628b"),
+            }),
+            Message::Progress {
+                id: 1,
+                pattern: String::from("BYBBG"),
+            },
+            Message::Winner { id: 1, guesses: 3 },
+            Message::Ping,
+            Message::Pong,
+        ];
+        for message in messages {
+            assert_eq!(Message::parse(&message.to_string()), Some(message));
+        }
+        assert_eq!(Message::parse("progress 1 BXB"), None);
+        assert_eq!(Message::parse("score 1"), None);
+        assert_eq!(Message::parse("dance"), None);
+    }
+
+    #[test]
+    fn race_over_loopback() -> () {
+        let (host, address): (RaceHost, String) = host(TEST_TIMING);
+        let ann: RaceClient = RaceClient::connect(&address, "Ann").unwrap();
+        let bob: RaceClient = RaceClient::connect(&address, "Bob").unwrap();
+        wait_for_players(&host, 2);
+        host.start_race();
+
+        let settings: RaceSettings = match wait_for(&ann, |m| matches!(m, Message::Race(_))) {
+            Message::Race(settings) => settings,
+            _ => unreachable!(),
+        };
+        assert_eq!(settings.round, 1);
+        let lists: WordLists = WordLists::load(&GameOptions::default()).unwrap();
+        assert_eq!(settings.answers, lists.fingerprint());
+        wait_for(&bob, |m| *m == Message::Race(settings.clone()));
+
+        ann.send_score(settings.round, 1, &[COLOR_GRAY; 5]);
+        let progress: Message = Message::Progress {
+            id: ann.id(),
+            pattern: String::from("BBBBB"),
+        };
+        wait_for(&bob, |m| *m == progress);
+        bob.send_score(settings.round, 1, &[COLOR_GREEN; 5]);
+        let winner: Message = Message::Winner {
+            id: bob.id(),
+            guesses: 1,
+        };
+        wait_for(&ann, |m| *m == winner);
+        assert_eq!(host.status().winner, Some((String::from("Bob"), 1)));
+        assert!(!host.status().running);
+    }
+
+    #[test]
+    fn reconnecting_keeps_the_place() -> () {
+        let (host, address): (RaceHost, String) = host(TEST_TIMING);
+        let Ok((mut ann, _, ann_id)) = handshake(&address, "ann-token", "Ann") else {
+            panic!("Ann couldn't join");
+        };
+        let bob: RaceClient = RaceClient::connect(&address, "Bob").unwrap();
+        host.start_race();
+        let round: u32 = host.status().round;
+        send(
+            &mut ann,
+            &Message::Score {
+                round,
+                row: 1,
+                pattern: String::from("BYBBG"),
+            },
+        )
+        .unwrap();
+        let progress: Message = Message::Progress {
+            id: ann_id,
+            pattern: String::from("BYBBG"),
+        };
+        wait_for(&bob, |m| *m == progress);
+
+        ann.shutdown(Shutdown::Both).unwrap();
+        wait_for(&bob, |m| *m == Message::Left { id: ann_id });
+        let Ok((_ann, mut lines, id)) = handshake(&address, "ann-token", "Ann") else {
+            panic!("Ann couldn't get back in");
+        };
+        assert_eq!(id, ann_id);
+        read_until(&mut lines, |m| *m == progress);
+        wait_for(&bob, |m| {
+            *m == Message::Player {
+                id: ann_id,
+                name: String::from("Ann"),
+            }
+        });
+        wait_for_players(&host, 2);
+    }
+
+    #[test]
+    fn late_tokens_are_refused() -> () {
+        let (host, address): (RaceHost, String) = host(TEST_TIMING);
+        let Ok((ann, _, _)) = handshake(&address, "ann-token", "Ann") else {
+            panic!("Ann couldn't join");
+        };
+        wait_for_players(&host, 1);
+        ann.shutdown(Shutdown::Both).unwrap();
+        wait_for_players(&host, 0);
+        thread::sleep(TEST_TIMING.reconnect_timeout * 2);
+        assert!(matches!(
+            handshake(&address, "ann-token", "Ann"),
+            Err(ConnectError::Refused(_))
+        ));
+    }
+
+    #[test]
+    fn silent_players_are_dropped() -> () {
+        let timing: HostTiming = HostTiming {
+            heartbeat_timeout: Duration::from_millis(300),
+            ..TEST_TIMING
+        };
+        let (host, address): (RaceHost, String) = host(timing);
+        // This player never answers a ping
+        let Ok((_ann, _lines, _)) = handshake(&address, "ann-token", "Ann") else {
+            panic!("Ann couldn't join");
+        };
+        let bob: RaceClient = RaceClient::connect(&address, "Bob").unwrap();
+        wait_for_players(&host, 1);
+        // Bob answers every ping, so he's still there long after
+        thread::sleep(timing.heartbeat_timeout * 3);
+        assert_eq!(host.status().players, 1);
+        assert_eq!(
+            bob.poll()
+                .iter()
+                .filter(|e| **e == RaceEvent::Disconnected)
+                .count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use crate::i18n::fill;
+use gettextrs::{gettext, ngettext};
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4::accessible::Property;
+use gtk4::glib::WeakRef;
+use gtk4::Orientation::Vertical;
+use gtk4::{Align, Box, Button, Grid, Label, ScrolledWindow, Widget};
+use libadwaita::prelude::{
+    AdwDialogExt, EntryRowExt, PreferencesDialogExt, PreferencesGroupExt, PreferencesPageExt,
+    PreferencesRowExt,
+};
+use libadwaita::{EntryRow, PreferencesDialog, PreferencesGroup, PreferencesPage, SpinRow, Toast};
+use rustle::game::{COLOR_GRAY, COLOR_GREEN, COLOR_YELLOW};
+use rustle::profiles::Profiles;
+use rustle::race::{
+    local_address, RaceClient, RaceHost, RacePlayer, RaceSettings, RaceState, DEFAULT_PORT,
+};
+use std::rc::Rc;
+
+/// The width of the panel showing the other players, in pixels
+pub const RACE_PANEL_WIDTH: i32 = 160;
+/// The space between the tiles of another player's board
+const MINI_TILE_SPACING: i32 = 2;
+
+/// A race on the local network the player is in, and the one they're hosting if they are
+pub struct RaceSession {
+    /// Kept so the race stops when the session ends
+    pub host: Option<RaceHost>,
+    pub client: RaceClient,
+    pub state: RaceState,
+    /// The settings of the race on the board, once the first one has started
+    pub playing: Option<RaceSettings>,
+    /// Whether the end of the race on the board has been shown
+    pub finished: bool,
+    /// Whether the connection to the host is up
+    pub connected: bool,
+}
+
+impl RaceSession {
+    /// Get the name of a player, or the player's own name for their id
+    pub fn player_name(&self, id: usize) -> String {
+        if id == self.client.id() {
+            return gettext("You");
+        }
+        return self
+            .state
+            .player(id)
+            .map_or_else(|| gettext("Someone"), |player| player.name.clone());
+    }
+
+    /// Describe how the race stands
+    fn status(&self) -> String {
+        if !self.connected {
+            return gettext("Lost the connection to the host, reconnecting…");
+        }
+        let Some(settings) = &self.state.settings else {
+            return match &self.host {
+                Some(host) => fill(
+                    &gettext("Others can join at {}. Start the race once everyone is in."),
+                    &[&match local_address() {
+                        Some(address) => format!("{}:{}", address, host.port()),
+                        None => fill(&gettext("port {}"), &[&host.port().to_string()]),
+                    }],
+                ),
+                None => gettext("Waiting for the host to start the race"),
+            };
+        };
+        if !self.state.over {
+            return fill(
+                &gettext("Race {} · First to solve it wins"),
+                &[&settings.round.to_string()],
+            );
+        }
+        return match self.state.winner {
+            Some((id, _)) if id == self.client.id() => gettext("You won the race!"),
+            Some((id, guesses)) => fill(
+                &ngettext(
+                    "{} won the race in {} try",
+                    "{} won the race in {} tries",
+                    guesses as u32,
+                ),
+                &[&self.player_name(id), &guesses.to_string()],
+            ),
+            None => gettext("Nobody solved it"),
+        };
+    }
+}
+
+/// Build another player's board, showing the colors of their rows without the letters
+fn mini_board(player: &RacePlayer, settings: &RaceSettings) -> Grid {
+    let grid: Grid = Grid::builder()
+        .row_spacing(MINI_TILE_SPACING)
+        .column_spacing(MINI_TILE_SPACING)
+        .halign(Align::Center)
+        .build();
+    for y in 0..settings.max_guesses {
+        for x in 0..settings.word_length {
+            let tile: Label = Label::new(None);
+            tile.add_css_class("mini-tile");
+            match player.rows.get(y).and_then(|row| row.get(x)).copied() {
+                Some(COLOR_GREEN) => tile.add_css_class("green"),
+                Some(COLOR_YELLOW) => tile.add_css_class("yellow"),
+                Some(COLOR_GRAY) => tile.add_css_class("gray"),
+                _ => {}
+            }
+            grid.attach(&tile, x as i32, y as i32, 1, 1);
+        }
+    }
+    let solved: usize = player.rows.last().map_or(0, |row| {
+        row.iter().filter(|color| **color == COLOR_GREEN).count()
+    });
+    grid.update_property(&[Property::Label(&fill(
+        &ngettext(
+            "{} has made {} guess, the last with {} letters right",
+            "{} has made {} guesses, the last with {} letters right",
+            player.rows.len() as u32,
+        ),
+        &[
+            &player.name,
+            &player.rows.len().to_string(),
+            &solved.to_string(),
+        ],
+    ))]);
+    return grid;
+}
+
+/// Build the panel beside the board showing how the race stands and the other players' boards
+pub fn build_race_panel(session: &RaceSession) -> Widget {
+    let panel: Box = Box::new(Vertical, 12);
+    panel.set_width_request(RACE_PANEL_WIDTH);
+    panel.set_margin_top(10);
+    panel.set_margin_start(12);
+
+    let title: Label = Label::new(Some(&gettext("LAN Race")));
+    title.add_css_class("heading");
+    panel.append(&title);
+    let status: Label = Label::new(Some(&session.status()));
+    status.set_wrap(true);
+    status.set_max_width_chars(18);
+    status.set_justify(gtk::Justification::Center);
+    status.add_css_class("dim-label");
+    panel.append(&status);
+
+    if let Some(host) = &session.host {
+        let label: String = if host.status().round == 0 {
+            gettext("Start Race")
+        } else {
+            gettext("Next Race")
+        };
+        let start: Button = Button::with_label(&label);
+        start.add_css_class("suggested-action");
+        start.set_action_name(Some("win.race-start"));
+        start.set_sensitive(session.state.settings.is_none() || session.state.over);
+        panel.append(&start);
+    }
+    let leave: Button = Button::with_label(&gettext("Leave Race"));
+    leave.set_action_name(Some("win.race-leave"));
+    panel.append(&leave);
+
+    let boards: Box = Box::new(Vertical, 12);
+    let others: Vec<&RacePlayer> = session
+        .state
+        .players
+        .iter()
+        .filter(|player| player.id != session.client.id())
+        .collect();
+    if others.is_empty() {
+        let empty: Label = Label::new(Some(&gettext("Nobody else has joined yet")));
+        empty.set_wrap(true);
+        empty.add_css_class("dim-label");
+        boards.append(&empty);
+    }
+    for player in others {
+        let name: Label = Label::new(Some(&player.name));
+        name.set_ellipsize(gtk::pango::EllipsizeMode::End);
+        if !player.connected {
+            name.add_css_class("dim-label");
+            name.set_tooltip_text(Some(&gettext("Disconnected")));
+        }
+        boards.append(&name);
+        if let Some(settings) = &session.state.settings {
+            boards.append(&mini_board(player, settings));
+        }
+    }
+    let scrolled: ScrolledWindow = ScrolledWindow::builder()
+        .child(&boards)
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .propagate_natural_height(true)
+        .build();
+    panel.append(&scrolled);
+    return panel.upcast();
+}
+
+/// Show the dialog for hosting a race or joining one. `host` is given the player's name and
+/// the port to host on, and `join` the player's name and the address of the host.
+pub fn show_race_dialog(
+    parent: &impl IsA<Widget>,
+    host: impl Fn(String, u16) + 'static,
+    join: impl Fn(String, String) + 'static,
+) -> () {
+    let name_row: EntryRow = EntryRow::builder()
+        .title(gettext("Your Name"))
+        .text(&Profiles::load().active().name)
+        .build();
+    let name_group: PreferencesGroup = PreferencesGroup::builder()
+        .description(gettext(
+            "Everyone gets the same word, and sees the colors of each other's guesses but not \
+             the letters. Players need the same answer list.",
+        ))
+        .build();
+    name_group.add(&name_row);
+
+    let port_row: SpinRow = SpinRow::with_range(1024.0, 65535.0, 1.0);
+    port_row.set_title(&gettext("Port"));
+    port_row.set_value(DEFAULT_PORT as f64);
+    let host_button: Button = Button::with_label(&gettext("Host"));
+    host_button.add_css_class("suggested-action");
+    host_button.set_valign(Align::Center);
+    let host_group: PreferencesGroup = PreferencesGroup::builder()
+        .title(gettext("Host a Race"))
+        .description(gettext(
+            "Races use the board size and hard mode setting from your preferences",
+        ))
+        .header_suffix(&host_button)
+        .build();
+    host_group.add(&port_row);
+
+    let address_row: EntryRow = EntryRow::builder()
+        .title(gettext("Host Address"))
+        .input_purpose(gtk::InputPurpose::Url)
+        .build();
+    let join_button: Button = Button::with_label(&gettext("Join"));
+    join_button.set_valign(Align::Center);
+    let join_group: PreferencesGroup = PreferencesGroup::builder()
+        .title(gettext("Join a Race"))
+        .header_suffix(&join_button)
+        .build();
+    join_group.add(&address_row);
+
+    let page: PreferencesPage = PreferencesPage::new();
+    page.add(&name_group);
+    page.add(&host_group);
+    page.add(&join_group);
+    let dialog: PreferencesDialog = PreferencesDialog::builder()
+        .title(gettext("LAN Race"))
+        .search_enabled(false)
+        .build();
+    dialog.add(&page);
+
+    let name_row_2: EntryRow = name_row.clone();
+    let dialog_2: WeakRef<PreferencesDialog> = dialog.downgrade();
+    host_button.connect_clicked(move |_| {
+        let Some(dialog) = dialog_2.upgrade() else {
+            return;
+        };
+        let name: String = name_row_2.text().trim().to_string();
+        if name.is_empty() {
+            dialog.add_toast(Toast::new(&gettext("Players need a name")));
+            return;
+        }
+        dialog.close();
+        host(name, port_row.value() as u16);
+    });
+    let dialog_2: WeakRef<PreferencesDialog> = dialog.downgrade();
+    let address_row_2: EntryRow = address_row.clone();
+    let try_join: Rc<dyn Fn()> = Rc::new(move || {
+        let Some(dialog) = dialog_2.upgrade() else {
+            return;
+        };
+        let name: String = name_row.text().trim().to_string();
+        let address: String = address_row_2.text().trim().to_string();
+        if name.is_empty() {
+            dialog.add_toast(Toast::new(&gettext("Players need a name")));
+            return;
+        }
+        if address.is_empty() {
+            dialog.add_toast(Toast::new(&gettext("Enter the address of the host")));
+            return;
+        }
+        dialog.close();
+        join(name, address);
+    });
+    let try_join_2: Rc<dyn Fn()> = try_join.clone();
+    join_button.connect_clicked(move |_| try_join_2());
+    address_row.connect_entry_activated(move |_| try_join());
+    dialog.present(Some(parent));
+}
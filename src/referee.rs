@@ -0,0 +1,421 @@
+use crate::game::{pattern_string, Game, GameOptions, GuessResult, WordLists};
+use crate::scoreboard::Scoreboard;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+/// How long a bot gets to answer by default, in milliseconds
+pub const DEFAULT_TIMEOUT_MS: u64 = 5000;
+/// How many invalid guesses a bot can make in one game by default before it forfeits
+pub const DEFAULT_MAX_INVALID: usize = 3;
+
+/// Why a bot couldn't be heard from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BotError {
+    /// The bot didn't answer in time
+    Timeout,
+    /// The bot closed its end of the connection
+    Disconnected,
+}
+
+/// A line based connection to a bot
+pub trait BotConnection {
+    /// Send a line to the bot
+    fn send(&mut self, line: &str) -> Result<(), BotError>;
+    /// Wait for the next line from the bot
+    fn receive(&mut self, timeout: Duration) -> Result<String, BotError>;
+    /// Get the connection back into a usable state after a timeout or disconnect
+    fn restart(&mut self) -> Result<(), String>;
+}
+
+/// Read lines on a separate thread, so they can be waited for with a timeout
+fn spawn_line_reader<R: Read + Send + 'static>(reader: R) -> Receiver<String> {
+    let (sender, receiver): (Sender<String>, Receiver<String>) = channel();
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            match line {
+                Ok(line) => {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    });
+    return receiver;
+}
+
+/// Wait for a line from a reader thread
+fn receive_line(lines: &Receiver<String>, timeout: Duration) -> Result<String, BotError> {
+    return match lines.recv_timeout(timeout) {
+        Ok(line) => Ok(line),
+        Err(RecvTimeoutError::Timeout) => Err(BotError::Timeout),
+        Err(RecvTimeoutError::Disconnected) => Err(BotError::Disconnected),
+    };
+}
+
+/// A bot running as a child process, spoken to over its stdin and stdout
+pub struct ProcessBot {
+    command: String,
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl ProcessBot {
+    /// Start a bot with a shell command
+    pub fn spawn(command: &str) -> Result<ProcessBot, String> {
+        let mut child: Child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|error| format!("Failed to start bot \"{}\": {}", command, error))?;
+        let stdin: ChildStdin = child.stdin.take().unwrap();
+        let lines: Receiver<String> = spawn_line_reader(child.stdout.take().unwrap());
+        return Ok(ProcessBot {
+            command: command.to_string(),
+            child,
+            stdin,
+            lines,
+        });
+    }
+}
+
+impl BotConnection for ProcessBot {
+    fn send(&mut self, line: &str) -> Result<(), BotError> {
+        return writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|_| BotError::Disconnected);
+    }
+
+    fn receive(&mut self, timeout: Duration) -> Result<String, BotError> {
+        return receive_line(&self.lines, timeout);
+    }
+
+    fn restart(&mut self) -> Result<(), String> {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        *self = ProcessBot::spawn(&self.command)?;
+        return Ok(());
+    }
+}
+
+impl Drop for ProcessBot {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A bot on the other end of this process's own stdin and stdout
+pub struct StdioBot {
+    lines: Receiver<String>,
+}
+
+impl StdioBot {
+    /// Start listening on stdin
+    pub fn new() -> StdioBot {
+        return StdioBot {
+            lines: spawn_line_reader(std::io::stdin()),
+        };
+    }
+}
+
+impl Default for StdioBot {
+    fn default() -> StdioBot {
+        return StdioBot::new();
+    }
+}
+
+impl BotConnection for StdioBot {
+    fn send(&mut self, line: &str) -> Result<(), BotError> {
+        let mut stdout = std::io::stdout();
+        return writeln!(stdout, "{}", line)
+            .and_then(|_| stdout.flush())
+            .map_err(|_| BotError::Disconnected);
+    }
+
+    fn receive(&mut self, timeout: Duration) -> Result<String, BotError> {
+        return receive_line(&self.lines, timeout);
+    }
+
+    fn restart(&mut self) -> Result<(), String> {
+        // A late answer to the last game would be mistaken for a guess in the next one
+        loop {
+            match self.lines.try_recv() {
+                Ok(_) => continue,
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => return Err(String::from("stdin was closed")),
+            }
+        }
+    }
+}
+
+/// How a refereed game ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameOutcome {
+    /// The bot found the answer in some number of guesses
+    Won(usize),
+    /// The bot ran out of guesses
+    Lost,
+    /// The bot made too many invalid guesses
+    Forfeited,
+    /// The bot took too long to answer
+    TimedOut,
+    /// The bot went away
+    Disconnected,
+}
+
+impl GameOutcome {
+    /// Get the reason a game failed, as shown in reports
+    pub fn failure_reason(&self) -> &'static str {
+        return match self {
+            GameOutcome::Won(_) => "won",
+            GameOutcome::Lost => "lost",
+            GameOutcome::Forfeited => "forfeited",
+            GameOutcome::TimedOut => "timed out",
+            GameOutcome::Disconnected => "disconnected",
+        };
+    }
+}
+
+/// Rules the referee enforces
+#[derive(Clone, Debug)]
+pub struct RefereeConfig {
+    /// The board size and hard mode setting of every game
+    pub options: GameOptions,
+    /// How long the bot gets for each guess
+    pub timeout: Duration,
+    /// How many invalid guesses the bot can make in a game before it forfeits
+    pub max_invalid: usize,
+}
+
+/// Referee one game against a bot
+pub fn referee_game(
+    bot: &mut dyn BotConnection,
+    config: &RefereeConfig,
+    lists: &WordLists,
+    answer: &str,
+) -> GameOutcome {
+    let mut game: Game = Game::with_answer(&config.options, answer.to_string(), 0);
+    let mut invalid: usize = 0;
+    if bot
+        .send(&format!("new {} {}", game.word_length, game.max_guesses))
+        .is_err()
+    {
+        return GameOutcome::Disconnected;
+    }
+    loop {
+        let line: String = match bot.receive(config.timeout) {
+            Ok(line) => line,
+            Err(BotError::Timeout) => return GameOutcome::TimedOut,
+            Err(BotError::Disconnected) => return GameOutcome::Disconnected,
+        };
+        let word: String = line
+            .trim()
+            .trim_start_matches("guess ")
+            .trim()
+            .to_lowercase();
+        let result: GuessResult = if game.enter_word(&word) {
+            game.submit(&lists.words)
+        } else {
+            GuessResult::Incomplete
+        };
+        let reply: String = match &result {
+            GuessResult::Incomplete => format!("invalid not a {}-letter word", game.word_length),
+            GuessResult::InvalidWord => String::from("invalid not in word list"),
+            GuessResult::HardModeViolation(violation) => format!("invalid {}", violation),
+            GuessResult::Continue | GuessResult::Won | GuessResult::Lost => format!(
+                "result {}",
+                pattern_string(&game.board_colors[game.guesses_used() - 1])
+            ),
+        };
+        if bot.send(&reply).is_err() {
+            return GameOutcome::Disconnected;
+        }
+        let outcome: GameOutcome = match result {
+            GuessResult::Incomplete
+            | GuessResult::InvalidWord
+            | GuessResult::HardModeViolation(_) => {
+                invalid += 1;
+                if invalid <= config.max_invalid {
+                    continue;
+                }
+                GameOutcome::Forfeited
+            }
+            GuessResult::Continue => continue,
+            GuessResult::Won => GameOutcome::Won(game.guesses_used()),
+            GuessResult::Lost => GameOutcome::Lost,
+        };
+        let end: String = match outcome {
+            GameOutcome::Won(guesses) => format!("win {}", guesses),
+            _ => format!("lose {}", game.answer),
+        };
+        if bot.send(&end).is_err() {
+            return GameOutcome::Disconnected;
+        }
+        return outcome;
+    }
+}
+
+/// Referee a game against a bot for every answer, calling `progress` after each one
+pub fn run_tournament(
+    bot: &mut dyn BotConnection,
+    config: &RefereeConfig,
+    lists: &WordLists,
+    answers: &[String],
+    mut progress: impl FnMut(usize, &str, &GameOutcome),
+) -> Result<Scoreboard, String> {
+    let mut scoreboard: Scoreboard = Scoreboard::default();
+    for (i, answer) in answers.iter().enumerate() {
+        let outcome: GameOutcome = referee_game(bot, config, lists, answer);
+        match outcome {
+            GameOutcome::Won(guesses) => scoreboard.record_win(guesses),
+            _ => scoreboard.record_failure(outcome.failure_reason()),
+        }
+        progress(i, answer, &outcome);
+        if outcome == GameOutcome::TimedOut || outcome == GameOutcome::Disconnected {
+            bot.restart()?;
+        }
+    }
+    let _ = bot.send("quit");
+    return Ok(scoreboard);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// A bot that gives answers from a script and remembers what it was sent
+    #[derive(Default)]
+    struct ScriptedBot {
+        replies: VecDeque<Result<String, BotError>>,
+        sent: Vec<String>,
+        restarts: usize,
+    }
+
+    impl ScriptedBot {
+        /// Make a bot that sends some lines, then goes away
+        fn new(replies: &[&str]) -> ScriptedBot {
+            return ScriptedBot {
+                replies: replies.iter().map(|line| Ok(line.to_string())).collect(),
+                ..ScriptedBot::default()
+            };
+        }
+    }
+
+    impl BotConnection for ScriptedBot {
+        fn send(&mut self, line: &str) -> Result<(), BotError> {
+            self.sent.push(line.to_string());
+            return Ok(());
+        }
+
+        fn receive(&mut self, _timeout: Duration) -> Result<String, BotError> {
+            return self
+                .replies
+                .pop_front()
+                .unwrap_or(Err(BotError::Disconnected));
+        }
+
+        fn restart(&mut self) -> Result<(), String> {
+            self.restarts += 1;
+            return Ok(());
+        }
+    }
+
+    /// Get a small word list whose words are also the answers
+    fn lists() -> WordLists {
+        return WordLists::from_answers("crane\nslate\nfjord\n");
+    }
+
+    /// Get the rules for a game, allowing one invalid guess
+    fn config(max_guesses: usize, hard_mode: bool) -> RefereeConfig {
+        return RefereeConfig {
+            options: GameOptions {
+                max_guesses,
+                hard_mode,
+                ..GameOptions::default()
+            },
+            timeout: Duration::from_millis(DEFAULT_TIMEOUT_MS),
+            max_invalid: 1,
+        };
+    }
+
+    #[test]
+    fn guesses_are_scored() -> () {
+        let mut bot: ScriptedBot = ScriptedBot::new(&["guess slate", " CRANE \r"]);
+        let outcome: GameOutcome = referee_game(&mut bot, &config(6, false), &lists(), "crane");
+        assert_eq!(outcome, GameOutcome::Won(2));
+        assert_eq!(
+            bot.sent,
+            vec!["new 5 6", "result BBGBG", "result GGGGG", "win 2"]
+        );
+    }
+
+    #[test]
+    fn invalid_guesses_forfeit_the_game() -> () {
+        let mut bot: ScriptedBot = ScriptedBot::new(&["guess cran", "zzzzz"]);
+        let outcome: GameOutcome = referee_game(&mut bot, &config(6, false), &lists(), "crane");
+        assert_eq!(outcome, GameOutcome::Forfeited);
+        assert_eq!(
+            bot.sent,
+            vec![
+                "new 5 6",
+                "invalid not a 5-letter word",
+                "invalid not in word list",
+                "lose crane"
+            ]
+        );
+    }
+
+    #[test]
+    fn hard_mode_is_enforced() -> () {
+        let mut bot: ScriptedBot = ScriptedBot::new(&["slate", "fjord", "crane"]);
+        let outcome: GameOutcome = referee_game(&mut bot, &config(6, true), &lists(), "crane");
+        assert_eq!(outcome, GameOutcome::Won(2));
+        assert!(bot.sent[2].starts_with("invalid "));
+        assert_eq!(bot.sent[3..], ["result GGGGG", "win 2"]);
+    }
+
+    #[test]
+    fn games_can_be_lost() -> () {
+        let mut bot: ScriptedBot = ScriptedBot::new(&["slate", "fjord"]);
+        let outcome: GameOutcome = referee_game(&mut bot, &config(2, false), &lists(), "crane");
+        assert_eq!(outcome, GameOutcome::Lost);
+        assert_eq!(bot.sent.last().map(String::as_str), Some("lose crane"));
+
+        let mut bot: ScriptedBot = ScriptedBot::default();
+        bot.replies.push_back(Err(BotError::Timeout));
+        let outcome: GameOutcome = referee_game(&mut bot, &config(2, false), &lists(), "crane");
+        assert_eq!(outcome, GameOutcome::TimedOut);
+        let outcome: GameOutcome = referee_game(&mut bot, &config(2, false), &lists(), "crane");
+        assert_eq!(outcome, GameOutcome::Disconnected);
+    }
+
+    #[test]
+    fn tournaments_restart_stuck_bots() -> () {
+        let mut bot: ScriptedBot = ScriptedBot::new(&["slate"]);
+        bot.replies.push_back(Err(BotError::Timeout));
+        let answers: Vec<String> = vec![String::from("slate"), String::from("crane")];
+        let mut played: Vec<String> = Vec::new();
+        let scoreboard: Scoreboard = run_tournament(
+            &mut bot,
+            &config(6, false),
+            &lists(),
+            &answers,
+            |_, answer, outcome| played.push(format!("{} {}", answer, outcome.failure_reason())),
+        )
+        .unwrap();
+        assert_eq!(played, vec!["slate won", "crane timed out"]);
+        assert_eq!(scoreboard.wins, 1);
+        assert_eq!(scoreboard.failures, vec![(String::from("timed out"), 1)]);
+        assert_eq!(bot.restarts, 1);
+        assert_eq!(bot.sent.last().map(String::as_str), Some("quit"));
+    }
+}
@@ -0,0 +1,47 @@
+use crate::date::Date;
+use crate::game::{Game, GameMode};
+use crate::history::GameRecord;
+use crate::storage::data_dir;
+use std::fs::{create_dir_all, read_to_string, remove_file, write};
+use std::io;
+use std::path::PathBuf;
+
+/// Get the file an unfinished game is saved in
+pub fn path() -> PathBuf {
+    return data_dir().join("game.txt");
+}
+
+/// Check if a game can be put aside and picked up later. Games against the clock and runs of
+/// several words can't.
+pub fn resumable(game: &Game) -> bool {
+    return matches!(game.mode, GameMode::Practice | GameMode::Daily)
+        && !game.locked
+        && game.guesses_used() > 0;
+}
+
+/// Save a game to pick up later, or remove the saved game if this one can't be
+pub fn save_game(game: &Game, today: Date) -> io::Result<()> {
+    let path: PathBuf = path();
+    if !resumable(game) {
+        return match remove_file(&path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        };
+    }
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    return write(path, GameRecord::from_game(game, today).to_line() + "\n");
+}
+
+/// Take the saved game, so it's only picked up once. A daily game saved on an earlier day is
+/// thrown away.
+pub fn take_saved_game(today: Date) -> Option<Game> {
+    let text: String = read_to_string(path()).ok()?;
+    let _ = remove_file(path());
+    let record: GameRecord = GameRecord::from_line(text.lines().next()?)?;
+    if record.mode == GameMode::Daily && record.date != today {
+        return None;
+    }
+    return Some(record.replay(record.guesses.len()));
+}
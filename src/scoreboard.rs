@@ -0,0 +1,97 @@
+/// Totals from playing many games, used to compare bots and strategies
+#[derive(Clone, Debug, Default)]
+pub struct Scoreboard {
+    pub games: usize,
+    pub wins: usize,
+    pub total_guesses: usize,
+    /// The most guesses any win took
+    pub worst: usize,
+    /// The number of wins for each number of guesses, starting at 1 guess
+    pub distribution: Vec<usize>,
+    /// How many games failed for each reason, such as "lost" or "timed out"
+    pub failures: Vec<(String, usize)>,
+}
+
+impl Scoreboard {
+    /// Count a game that was won
+    pub fn record_win(&mut self, guesses: usize) -> () {
+        self.games += 1;
+        self.wins += 1;
+        self.total_guesses += guesses;
+        self.worst = self.worst.max(guesses);
+        if self.distribution.len() < guesses {
+            self.distribution.resize(guesses, 0);
+        }
+        self.distribution[guesses - 1] += 1;
+    }
+
+    /// Count a game that wasn't won
+    pub fn record_failure(&mut self, reason: &str) -> () {
+        self.games += 1;
+        self.add_failures(reason, 1);
+    }
+
+    /// Add to the count of failures for a reason
+    fn add_failures(&mut self, reason: &str, count: usize) -> () {
+        match self.failures.iter_mut().find(|(r, _)| r == reason) {
+            Some((_, total)) => *total += count,
+            None => self.failures.push((reason.to_string(), count)),
+        }
+    }
+
+    /// Add the totals from another scoreboard to this one
+    pub fn merge(&mut self, other: &Scoreboard) -> () {
+        self.games += other.games;
+        self.wins += other.wins;
+        self.total_guesses += other.total_guesses;
+        self.worst = self.worst.max(other.worst);
+        if self.distribution.len() < other.distribution.len() {
+            self.distribution.resize(other.distribution.len(), 0);
+        }
+        for (i, count) in other.distribution.iter().enumerate() {
+            self.distribution[i] += count;
+        }
+        for (reason, count) in &other.failures {
+            self.add_failures(reason, *count);
+        }
+    }
+
+    /// Get the number of games that weren't won
+    pub fn failure_count(&self) -> usize {
+        return self.games - self.wins;
+    }
+
+    /// Get the average number of guesses a win took
+    pub fn mean_guesses(&self) -> f64 {
+        if self.wins == 0 {
+            return 0.0;
+        }
+        return self.total_guesses as f64 / self.wins as f64;
+    }
+
+    /// Format the totals as text
+    pub fn summary(&self) -> String {
+        let mut text: String = format!(
+            "Games: {}\nWins: {}\nMean guesses: {:.3}\nWorst case: {}\nFailures: {}\n",
+            self.games,
+            self.wins,
+            self.mean_guesses(),
+            self.worst,
+            self.failure_count()
+        );
+        for (reason, count) in &self.failures {
+            text.push_str(&format!("  {}: {}\n", reason, count));
+        }
+        text.push_str("Guess distribution:\n");
+        let most: usize = self.distribution.iter().copied().max().unwrap_or(0).max(1);
+        for (i, count) in self.distribution.iter().enumerate() {
+            text.push_str(&format!(
+                "{:>2} | {} {}\n",
+                i + 1,
+                "#".repeat(count * 40 / most),
+                count
+            ));
+        }
+        return text;
+    }
+}
@@ -0,0 +1,284 @@
+use gtk4::gio;
+use gtk4::gio::prelude::*;
+use gtk4::glib::{KeyFile, KeyFileFlags};
+use rustle::game::{
+    Difficulty, GameOptions, DEFAULT_AVOID_RECENT, DEFAULT_MAX_GUESSES, DEFAULT_SPEED_RUN_WORDS,
+    DEFAULT_TIME_LIMIT, DEFAULT_WORD_LENGTH,
+};
+use rustle::profiles::{Profiles, DEFAULT_PROFILE};
+use rustle::storage::data_dir;
+use std::fs::create_dir_all;
+use std::path::PathBuf;
+
+/// The id of the GSettings schema in data/dev.droc101.rustle.gschema.xml
+const SCHEMA_ID: &str = "dev.droc101.rustle";
+/// The group settings are kept under when saved to a keyfile
+const KEYFILE_GROUP: &str = "rustle";
+
+/// Saved preferences, kept in GSettings if the schema is installed or a keyfile if it isn't.
+/// Profiles other than the default one always use a keyfile in their own directory.
+pub enum AppSettings {
+    Schema(gio::Settings),
+    /// The keyfile is read again for every access, so separate handles never overwrite each
+    /// other's changes
+    KeyFile(PathBuf),
+}
+
+impl AppSettings {
+    /// Open the settings of the active profile
+    pub fn load() -> AppSettings {
+        let schema_installed: bool = gio::SettingsSchemaSource::default()
+            .and_then(|source| source.lookup(SCHEMA_ID, true))
+            .is_some();
+        if schema_installed && Profiles::active_id() == DEFAULT_PROFILE {
+            return AppSettings::Schema(gio::Settings::new(SCHEMA_ID));
+        }
+        return AppSettings::KeyFile(data_dir().join("settings.ini"));
+    }
+
+    /// Read the keyfile
+    fn read_key_file(path: &PathBuf) -> KeyFile {
+        let key_file: KeyFile = KeyFile::new();
+        // A missing file just means nothing has been saved yet
+        let _ = key_file.load_from_file(path, KeyFileFlags::KEEP_COMMENTS);
+        return key_file;
+    }
+
+    /// Write the keyfile after a change
+    fn save_key_file(key_file: &KeyFile, path: &PathBuf) -> () {
+        if let Some(parent) = path.parent() {
+            let _ = create_dir_all(parent);
+        }
+        if let Err(error) = key_file.save_to_file(path) {
+            eprintln!("Failed to save settings: {}", error);
+        }
+    }
+
+    /// Get an integer setting
+    fn int(&self, key: &str, default: i32) -> i32 {
+        return match self {
+            AppSettings::Schema(settings) => settings.int(key),
+            AppSettings::KeyFile(path) => AppSettings::read_key_file(path)
+                .integer(KEYFILE_GROUP, key)
+                .unwrap_or(default),
+        };
+    }
+
+    /// Save an integer setting
+    fn set_int(&self, key: &str, value: i32) -> () {
+        match self {
+            AppSettings::Schema(settings) => {
+                let _ = settings.set_int(key, value);
+            }
+            AppSettings::KeyFile(path) => {
+                let key_file: KeyFile = AppSettings::read_key_file(path);
+                key_file.set_integer(KEYFILE_GROUP, key, value);
+                AppSettings::save_key_file(&key_file, path);
+            }
+        }
+    }
+
+    /// Get a boolean setting
+    fn boolean(&self, key: &str, default: bool) -> bool {
+        return match self {
+            AppSettings::Schema(settings) => settings.boolean(key),
+            AppSettings::KeyFile(path) => AppSettings::read_key_file(path)
+                .boolean(KEYFILE_GROUP, key)
+                .unwrap_or(default),
+        };
+    }
+
+    /// Save a boolean setting
+    fn set_boolean(&self, key: &str, value: bool) -> () {
+        match self {
+            AppSettings::Schema(settings) => {
+                let _ = settings.set_boolean(key, value);
+            }
+            AppSettings::KeyFile(path) => {
+                let key_file: KeyFile = AppSettings::read_key_file(path);
+                key_file.set_boolean(KEYFILE_GROUP, key, value);
+                AppSettings::save_key_file(&key_file, path);
+            }
+        }
+    }
+
+    /// Get a string setting
+    fn string(&self, key: &str, default: &str) -> String {
+        return match self {
+            AppSettings::Schema(settings) => settings.string(key).to_string(),
+            AppSettings::KeyFile(path) => AppSettings::read_key_file(path)
+                .string(KEYFILE_GROUP, key)
+                .map(|value| value.to_string())
+                .unwrap_or(default.to_string()),
+        };
+    }
+
+    /// Save a string setting
+    fn set_string(&self, key: &str, value: &str) -> () {
+        match self {
+            AppSettings::Schema(settings) => {
+                let _ = settings.set_string(key, value);
+            }
+            AppSettings::KeyFile(path) => {
+                let key_file: KeyFile = AppSettings::read_key_file(path);
+                key_file.set_string(KEYFILE_GROUP, key, value);
+                AppSettings::save_key_file(&key_file, path);
+            }
+        }
+    }
+
+    /// Get the game options to start from before the command line is applied
+    pub fn game_options(&self) -> GameOptions {
+        let defaults: GameOptions = GameOptions::default();
+        return GameOptions {
+            word_length: self.int("word-length", DEFAULT_WORD_LENGTH as i32).max(0) as usize,
+            max_guesses: self.int("max-guesses", DEFAULT_MAX_GUESSES as i32).max(0) as usize,
+            hard_mode: self.boolean("hard-mode", false),
+            time_limit: self.int("time-limit", DEFAULT_TIME_LIMIT as i32).max(0) as u64,
+            speed_run_words: self
+                .int("speed-run-words", DEFAULT_SPEED_RUN_WORDS as i32)
+                .max(0) as usize,
+            word_list: self.string("word-list", &defaults.word_list),
+            answer_list: self.string("answer-list", &defaults.answer_list),
+            difficulty: Difficulty::from_name(&self.string("difficulty", "any"))
+                .unwrap_or(Difficulty::Any),
+            avoid_recent: self.int("avoid-recent", DEFAULT_AVOID_RECENT as i32).max(0) as usize,
+            ..defaults
+        };
+    }
+
+    /// Set the number of letters in the word of new games
+    pub fn set_word_length(&self, word_length: usize) -> () {
+        self.set_int("word-length", word_length as i32);
+    }
+
+    /// Set the number of guesses allowed in new games
+    pub fn set_max_guesses(&self, max_guesses: usize) -> () {
+        self.set_int("max-guesses", max_guesses as i32);
+    }
+
+    /// Set whether new games are in hard mode
+    pub fn set_hard_mode(&self, hard_mode: bool) -> () {
+        self.set_boolean("hard-mode", hard_mode);
+    }
+
+    /// Set the number of seconds allowed in timed games
+    pub fn set_time_limit(&self, time_limit: u64) -> () {
+        self.set_int("time-limit", time_limit as i32);
+    }
+
+    /// Set the number of words in new speed runs
+    pub fn set_speed_run_words(&self, words: usize) -> () {
+        self.set_int("speed-run-words", words as i32);
+    }
+
+    /// Set how common the answers of random games are
+    pub fn set_difficulty(&self, difficulty: Difficulty) -> () {
+        self.set_string("difficulty", difficulty.name());
+    }
+
+    /// Set the number of recent games whose answers aren't picked again
+    pub fn set_avoid_recent(&self, games: usize) -> () {
+        self.set_int("avoid-recent", games as i32);
+    }
+
+    /// Get whether the clock stops while the window isn't focused
+    pub fn pause_unfocused(&self) -> bool {
+        return self.boolean("pause-unfocused", true);
+    }
+
+    /// Set whether the clock stops while the window isn't focused
+    pub fn set_pause_unfocused(&self, pause_unfocused: bool) -> () {
+        self.set_boolean("pause-unfocused", pause_unfocused);
+    }
+
+    /// Set the file of valid guesses
+    pub fn set_word_list(&self, word_list: &str) -> () {
+        self.set_string("word-list", word_list);
+    }
+
+    /// Set the file of possible answers
+    pub fn set_answer_list(&self, answer_list: &str) -> () {
+        self.set_string("answer-list", answer_list);
+    }
+
+    /// Get the file of definitions shown after a game, or an empty string for none
+    pub fn dictionary(&self) -> String {
+        return self.string("dictionary", "");
+    }
+
+    /// Set the file of definitions shown after a game
+    pub fn set_dictionary(&self, dictionary: &str) -> () {
+        self.set_string("dictionary", dictionary);
+    }
+
+    /// Get the name of the on-screen keyboard layout
+    pub fn keyboard_layout(&self) -> String {
+        return self.string("keyboard-layout", "qwerty");
+    }
+
+    /// Set the name of the on-screen keyboard layout
+    pub fn set_keyboard_layout(&self, layout: &str) -> () {
+        self.set_string("keyboard-layout", layout);
+    }
+
+    /// Get the name of the theme
+    pub fn theme(&self) -> String {
+        return self.string("theme", "system");
+    }
+
+    /// Set the name of the theme
+    pub fn set_theme(&self, theme: &str) -> () {
+        self.set_string("theme", theme);
+    }
+
+    /// Get the name of the tile palette
+    pub fn palette(&self) -> String {
+        return self.string("palette", "classic");
+    }
+
+    /// Set the name of the tile palette
+    pub fn set_palette(&self, palette: &str) -> () {
+        self.set_string("palette", palette);
+    }
+
+    /// Get whether tiles show a pattern as well as a color
+    pub fn patterns(&self) -> bool {
+        return self.boolean("patterns", false);
+    }
+
+    /// Set whether tiles show a pattern as well as a color
+    pub fn set_patterns(&self, patterns: bool) -> () {
+        self.set_boolean("patterns", patterns);
+    }
+
+    /// Get whether tiles animate, as long as the system allows it
+    pub fn animations(&self) -> bool {
+        return self.boolean("animations", true);
+    }
+
+    /// Set whether tiles animate
+    pub fn set_animations(&self, animations: bool) -> () {
+        self.set_boolean("animations", animations);
+    }
+
+    /// Get the code of the interface language, or an empty string to follow the system
+    pub fn language(&self) -> String {
+        return self.string("language", "");
+    }
+
+    /// Set the code of the interface language
+    pub fn set_language(&self, language: &str) -> () {
+        self.set_string("language", language);
+    }
+
+    /// Get whether the tutorial has been played or skipped
+    pub fn tutorial_done(&self) -> bool {
+        return self.boolean("tutorial-done", false);
+    }
+
+    /// Set whether the tutorial has been played or skipped
+    pub fn set_tutorial_done(&self, tutorial_done: bool) -> () {
+        self.set_boolean("tutorial-done", tutorial_done);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::date::Date;
+use crate::game::{Game, GameMode, COLOR_GREEN, COLOR_YELLOW};
+
+/// A set of tile colors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Palette {
+    /// Green and yellow
+    Classic,
+    /// Orange and blue
+    HighContrast,
+    /// Blue and orange, which stay apart for people who can't see green well
+    Deuteranopia,
+    /// Sky blue and yellow, which stay apart for people who can't see red well
+    Protanopia,
+}
+
+impl Palette {
+    /// Every palette
+    pub const ALL: [Palette; 4] = [
+        Palette::Classic,
+        Palette::HighContrast,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+    ];
+
+    /// Get the name used to save the palette
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Palette::Classic => "classic",
+            Palette::HighContrast => "high-contrast",
+            Palette::Deuteranopia => "deuteranopia",
+            Palette::Protanopia => "protanopia",
+        };
+    }
+
+    /// Get the label shown for the palette in menus
+    pub fn label(&self) -> &'static str {
+        return match self {
+            Palette::Classic => "Classic",
+            Palette::HighContrast => "High Contrast",
+            Palette::Deuteranopia => "Deuteranopia",
+            Palette::Protanopia => "Protanopia",
+        };
+    }
+
+    /// Find a palette by its name
+    pub fn from_name(name: &str) -> Option<Palette> {
+        return Palette::ALL
+            .into_iter()
+            .find(|palette| palette.name() == name);
+    }
+
+    /// Get the emoji square for a tile color
+    pub fn emoji(&self, color: usize) -> char {
+        return match (self, color) {
+            (Palette::Classic, COLOR_GREEN) => '🟩',
+            (Palette::Classic, COLOR_YELLOW) => '🟨',
+            (Palette::HighContrast, COLOR_GREEN) => '🟧',
+            (Palette::HighContrast, COLOR_YELLOW) => '🟦',
+            (Palette::Deuteranopia, COLOR_GREEN) => '🟦',
+            (Palette::Deuteranopia, COLOR_YELLOW) => '🟧',
+            (Palette::Protanopia, COLOR_GREEN) => '🟦',
+            (Palette::Protanopia, COLOR_YELLOW) => '🟨',
+            _ => '⬛',
+        };
+    }
+}
+
+/// Get the text shared for a finished game: a title line and an emoji square for each tile.
+/// The letters are left out so the answer isn't spoiled.
+pub fn share_text(game: &Game, palette: Palette, today: Date) -> String {
+    let name: String = match game.mode {
+        GameMode::Practice => format!("Seed {}", game.seed),
+        GameMode::Daily => format!("Daily {}", today),
+        GameMode::Timed => format!("Timed {}", game.seed),
+        GameMode::SpeedRun => format!("Speed Run {}", game.seed),
+        GameMode::Survival => format!("Survival {}", game.seed),
+    };
+    let score: String = match game.won {
+        true => game.guesses_used().to_string(),
+        false => String::from("X"),
+    };
+    let mut text: String = format!(
+        "Rustle {} {}/{}{}\n",
+        name,
+        score,
+        game.max_guesses,
+        if game.hard_mode { "*" } else { "" }
+    );
+    for row in &game.board_colors[..game.guesses_used()] {
+        text.push('\n');
+        text.extend(row.iter().map(|&color| palette.emoji(color)));
+    }
+    return text;
+}
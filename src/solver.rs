@@ -0,0 +1,229 @@
+use crate::game::{pattern_code, pattern_string, score_pattern, Game, GameOptions, GuessResult};
+use std::collections::HashMap;
+
+/// The most possible answers `next_guess` scores as guesses. Scoring one takes a pass over every
+/// possible answer, so without a limit the first guesses would cost the square of the list size.
+const MAX_SCORED_GUESSES: usize = 500;
+
+/// How the solver picks its next guess
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// The first answer that fits everything seen so far
+    First,
+    /// The answer that leaves the fewest possible answers on average
+    Expected,
+    /// The answer whose result tells the most, measured in bits
+    Entropy,
+}
+
+impl Strategy {
+    /// Every strategy
+    pub const ALL: [Strategy; 3] = [Strategy::First, Strategy::Expected, Strategy::Entropy];
+
+    /// Get the name used for the strategy on the command line
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Strategy::First => "first",
+            Strategy::Expected => "expected",
+            Strategy::Entropy => "entropy",
+        };
+    }
+
+    /// Find a strategy by its name
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        return Strategy::ALL
+            .into_iter()
+            .find(|strategy| strategy.name() == name);
+    }
+}
+
+/// Narrows down the possible answers as guesses are scored.
+/// It only ever guesses possible answers, so its guesses are always allowed in hard mode.
+pub struct Solver<'a> {
+    answers: &'a [Vec<u8>],
+    candidates: Vec<usize>,
+    strategy: Strategy,
+}
+
+impl<'a> Solver<'a> {
+    /// Start with every answer possible
+    pub fn new(answers: &'a [Vec<u8>], strategy: Strategy) -> Solver<'a> {
+        return Solver {
+            answers,
+            candidates: (0..answers.len()).collect(),
+            strategy,
+        };
+    }
+
+    /// Get the number of answers that are still possible
+    pub fn remaining(&self) -> usize {
+        return self.candidates.len();
+    }
+
+    /// Remove the answers that wouldn't have given a guess its pattern
+    pub fn observe(&mut self, guess: &[u8], pattern: u32) -> () {
+        let answers: &[Vec<u8>] = self.answers;
+        self.candidates
+            .retain(|&i| score_pattern(guess, &answers[i]) == pattern);
+    }
+
+    /// Pick the next guess, or None if no answer fits.
+    /// Only the first `MAX_SCORED_GUESSES` possible answers are scored as guesses, each against
+    /// every possible answer.
+    pub fn next_guess(&self) -> Option<&'a [u8]> {
+        if self.candidates.len() <= 2 || self.strategy == Strategy::First {
+            return self.candidates.first().map(|&i| self.answers[i].as_slice());
+        }
+        let mut best: usize = self.candidates[0];
+        let mut best_score: f64 = f64::MIN;
+        let mut buckets: HashMap<u32, usize> = HashMap::new();
+        for &guess in self.candidates.iter().take(MAX_SCORED_GUESSES) {
+            buckets.clear();
+            for &answer in &self.candidates {
+                *buckets
+                    .entry(score_pattern(&self.answers[guess], &self.answers[answer]))
+                    .or_insert(0) += 1;
+            }
+            let total: f64 = self.candidates.len() as f64;
+            // Higher is better for both
+            let score: f64 = match self.strategy {
+                Strategy::Expected => -buckets
+                    .values()
+                    .map(|&size| (size * size) as f64)
+                    .sum::<f64>(),
+                _ => -buckets
+                    .values()
+                    .map(|&size| {
+                        let p: f64 = size as f64 / total;
+                        p * p.log2()
+                    })
+                    .sum::<f64>(),
+            };
+            if score > best_score {
+                best_score = score;
+                best = guess;
+            }
+        }
+        return Some(self.answers[best].as_slice());
+    }
+}
+
+/// One guess the solver made and the pattern it got back
+#[derive(Clone, Debug)]
+pub struct TraceStep {
+    pub guess: String,
+    pub pattern: String,
+}
+
+/// Every guess the solver made in a game
+#[derive(Clone, Debug)]
+pub struct Trace {
+    pub answer: String,
+    pub solved: bool,
+    pub steps: Vec<TraceStep>,
+}
+
+/// Play one game with the solver, scoring its guesses with the real game rules
+pub fn solve(
+    options: &GameOptions,
+    words: &[String],
+    answers: &[Vec<u8>],
+    answer: &str,
+    opener: &str,
+    strategy: Strategy,
+) -> Trace {
+    let mut game: Game = Game::with_answer(options, answer.to_string(), 0);
+    let mut solver: Solver = Solver::new(answers, strategy);
+    let mut steps: Vec<TraceStep> = Vec::new();
+    let mut guess: String = opener.to_string();
+    loop {
+        game.enter_word(&guess);
+        let result: GuessResult = game.submit(words);
+        let row: usize = game.guesses_used().saturating_sub(1);
+        if result != GuessResult::Continue
+            && result != GuessResult::Won
+            && result != GuessResult::Lost
+        {
+            break;
+        }
+        steps.push(TraceStep {
+            guess: guess.clone(),
+            pattern: pattern_string(&game.board_colors[row]),
+        });
+        if game.locked {
+            break;
+        }
+        solver.observe(guess.as_bytes(), pattern_code(&game.board_colors[row]));
+        match solver.next_guess() {
+            Some(next) => guess = String::from_utf8_lossy(next).into_owned(),
+            None => break,
+        }
+    }
+    return Trace {
+        answer: answer.to_string(),
+        solved: game.won,
+        steps,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers that share a lot of letters, so only a good guess tells them apart quickly
+    const ANSWERS: [&str; 10] = [
+        "crane", "crate", "grate", "trace", "react", "slate", "plate", "brick", "ghost", "fjord",
+    ];
+
+    /// Get the test answers as words
+    fn words() -> Vec<String> {
+        return ANSWERS.iter().map(|word| word.to_string()).collect();
+    }
+
+    /// Get the test answers as bytes, the way the solver takes them
+    fn answers() -> Vec<Vec<u8>> {
+        return ANSWERS
+            .iter()
+            .map(|word| word.as_bytes().to_vec())
+            .collect();
+    }
+
+    #[test]
+    fn every_answer_is_solved() -> () {
+        let options: GameOptions = GameOptions::default();
+        let words: Vec<String> = words();
+        let answers: Vec<Vec<u8>> = answers();
+        for strategy in Strategy::ALL {
+            for answer in ANSWERS {
+                let trace: Trace = solve(&options, &words, &answers, answer, "slate", strategy);
+                assert!(trace.solved, "{} with {}", answer, strategy.name());
+                assert!(trace.steps.len() <= options.max_guesses);
+                assert_eq!(trace.steps[0].guess, "slate");
+                assert_eq!(trace.steps.last().unwrap().guess, answer);
+            }
+        }
+    }
+
+    #[test]
+    fn guesses_are_deterministic() -> () {
+        let answers: Vec<Vec<u8>> = answers();
+        let solver: Solver = Solver::new(&answers, Strategy::Expected);
+        let first: &[u8] = solver.next_guess().unwrap();
+        assert_eq!(solver.next_guess(), Some(first));
+        assert_eq!(
+            Solver::new(&answers, Strategy::First).next_guess(),
+            Some("crane".as_bytes())
+        );
+    }
+
+    #[test]
+    fn observing_narrows_the_answers() -> () {
+        let answers: Vec<Vec<u8>> = answers();
+        let mut solver: Solver = Solver::new(&answers, Strategy::Entropy);
+        solver.observe(b"crane", score_pattern(b"crane", b"fjord"));
+        assert_eq!(solver.remaining(), 1);
+        assert_eq!(solver.next_guess(), Some("fjord".as_bytes()));
+        solver.observe(b"ghost", score_pattern(b"ghost", b"crane"));
+        assert_eq!(solver.next_guess(), None);
+    }
+}
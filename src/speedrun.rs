@@ -0,0 +1,195 @@
+use crate::game::GameOptions;
+use crate::storage::{data_dir, load_key_values, save_key_values};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A run of several words played against one clock
+#[derive(Clone, Debug)]
+pub struct SpeedRun {
+    /// The number of words to solve
+    pub words: usize,
+    /// The runs this one can be compared with, as described by `category`
+    pub category: String,
+    /// The time each solved word took
+    pub splits: Vec<Duration>,
+}
+
+impl SpeedRun {
+    /// Start a run with the speed run options
+    pub fn new(options: &GameOptions) -> SpeedRun {
+        return SpeedRun {
+            words: options.speed_run_words,
+            category: category(options),
+            splits: Vec::new(),
+        };
+    }
+
+    /// Get the total time of the solved words
+    pub fn total(&self) -> Duration {
+        return self.splits.iter().sum();
+    }
+
+    /// Record that a word was solved, given the time on the clock
+    pub fn solve(&mut self, elapsed: Duration) -> () {
+        let split: Duration = elapsed.saturating_sub(self.total());
+        self.splits.push(split);
+    }
+
+    /// Get the number of the word being played, starting at 1
+    pub fn current_word(&self) -> usize {
+        return (self.splits.len() + 1).min(self.words);
+    }
+
+    /// Check if every word has been solved
+    pub fn complete(&self) -> bool {
+        return self.splits.len() >= self.words;
+    }
+}
+
+/// Describe the options that make runs comparable, such as `5w-5l-6g-hard`
+pub fn category(options: &GameOptions) -> String {
+    return format!(
+        "{}w-{}l-{}g{}",
+        options.speed_run_words,
+        options.word_length,
+        options.max_guesses,
+        if options.hard_mode { "-hard" } else { "" }
+    );
+}
+
+/// The splits of the fastest complete run in each category
+#[derive(Clone, Debug, Default)]
+pub struct PersonalBests {
+    pub runs: HashMap<String, Vec<Duration>>,
+}
+
+impl PersonalBests {
+    /// Get the file the personal bests are saved in
+    pub fn path() -> PathBuf {
+        return data_dir().join("speedruns.txt");
+    }
+
+    /// Load the saved personal bests
+    pub fn load() -> PersonalBests {
+        let runs: HashMap<String, Vec<Duration>> = load_key_values(&PersonalBests::path())
+            .into_iter()
+            .map(|(category, splits)| {
+                let splits: Vec<Duration> = splits
+                    .split(',')
+                    .filter_map(|ms| ms.parse().ok())
+                    .map(Duration::from_millis)
+                    .collect();
+                (category, splits)
+            })
+            .collect();
+        return PersonalBests { runs };
+    }
+
+    /// Save the personal bests
+    pub fn save(&self) -> io::Result<()> {
+        let mut values: Vec<(&str, String)> = self
+            .runs
+            .iter()
+            .map(|(category, splits)| {
+                let splits: Vec<String> =
+                    splits.iter().map(|s| s.as_millis().to_string()).collect();
+                (category.as_str(), splits.join(","))
+            })
+            .collect();
+        values.sort();
+        return save_key_values(&PersonalBests::path(), &values);
+    }
+
+    /// Get the splits of the best run in a category
+    pub fn best(&self, category: &str) -> Option<&[Duration]> {
+        return self.runs.get(category).map(|splits| splits.as_slice());
+    }
+
+    /// Keep a complete run if it's the fastest in its category, returning whether it was
+    pub fn submit(&mut self, run: &SpeedRun) -> bool {
+        if !run.complete() {
+            return false;
+        }
+        let faster: bool = self
+            .best(&run.category)
+            .is_none_or(|best| run.total() < best.iter().sum());
+        if faster {
+            self.runs.insert(run.category.clone(), run.splits.clone());
+        }
+        return faster;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Make a run of three words solved at the given times on the clock, in seconds
+    fn run(solved: &[u64]) -> SpeedRun {
+        let options: GameOptions = GameOptions {
+            speed_run_words: 3,
+            ..GameOptions::default()
+        };
+        let mut run: SpeedRun = SpeedRun::new(&options);
+        for seconds in solved {
+            run.solve(Duration::from_secs(*seconds));
+        }
+        return run;
+    }
+
+    #[test]
+    fn runs_are_split() -> () {
+        let mut run: SpeedRun = run(&[]);
+        assert_eq!(run.current_word(), 1);
+        run.solve(Duration::from_secs(20));
+        run.solve(Duration::from_secs(50));
+        assert_eq!(
+            run.splits,
+            vec![Duration::from_secs(20), Duration::from_secs(30)]
+        );
+        assert_eq!(run.current_word(), 3);
+        assert!(!run.complete());
+        run.solve(Duration::from_secs(65));
+        assert_eq!(run.total(), Duration::from_secs(65));
+        assert_eq!(run.current_word(), 3);
+        assert!(run.complete());
+    }
+
+    #[test]
+    fn categories_name_the_options() -> () {
+        let mut options: GameOptions = GameOptions {
+            speed_run_words: 5,
+            word_length: 6,
+            max_guesses: 7,
+            ..GameOptions::default()
+        };
+        assert_eq!(category(&options), "5w-6l-7g");
+        options.hard_mode = true;
+        assert_eq!(category(&options), "5w-6l-7g-hard");
+    }
+
+    #[test]
+    fn only_faster_complete_runs_are_kept() -> () {
+        let mut bests: PersonalBests = PersonalBests::default();
+        assert!(!bests.submit(&run(&[10, 20])));
+        assert_eq!(bests.best("3w-5l-6g"), None);
+
+        assert!(bests.submit(&run(&[10, 20, 60])));
+        assert!(!bests.submit(&run(&[5, 10, 60])));
+        assert!(!bests.submit(&run(&[30, 40, 70])));
+        assert!(bests.submit(&run(&[30, 40, 50])));
+        assert_eq!(
+            bests.best("3w-5l-6g"),
+            Some(
+                [
+                    Duration::from_secs(30),
+                    Duration::from_secs(10),
+                    Duration::from_secs(10)
+                ]
+                .as_slice()
+            )
+        );
+    }
+}
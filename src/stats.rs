@@ -0,0 +1,354 @@
+use crate::date::Date;
+use crate::game::{Game, GameMode};
+use crate::history::History;
+use crate::storage::{data_dir, load_key_values, save_key_values};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+/// The most survival runs kept on the leaderboard
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// Statistics of every finished game
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    pub played: usize,
+    pub wins: usize,
+    pub current_streak: usize,
+    pub max_streak: usize,
+    /// The number of wins for each number of guesses, starting at 1 guess
+    pub distribution: Vec<usize>,
+    pub daily_played: usize,
+    pub daily_wins: usize,
+    pub daily_streak: usize,
+    pub daily_max_streak: usize,
+    /// The last date the daily game was played
+    pub last_daily: Option<Date>,
+    /// The best survival runs as (words solved, date), best first
+    pub survival_scores: Vec<(usize, Date)>,
+}
+
+impl Stats {
+    /// Get the file the stats are saved in
+    pub fn path() -> PathBuf {
+        return data_dir().join("stats.txt");
+    }
+
+    /// Load the saved stats, or empty stats if there are none
+    pub fn load() -> Stats {
+        return Stats::from_values(&load_key_values(&Stats::path()));
+    }
+
+    /// Save the stats
+    pub fn save(&self) -> io::Result<()> {
+        return save_key_values(&Stats::path(), &self.to_values());
+    }
+
+    /// Read stats from named values, as written by `to_values`. Missing values are zero.
+    pub fn from_values(values: &HashMap<String, String>) -> Stats {
+        let number = |key: &str| -> usize {
+            return values.get(key).and_then(|v| v.parse().ok()).unwrap_or(0);
+        };
+        return Stats {
+            played: number("played"),
+            wins: number("wins"),
+            current_streak: number("current_streak"),
+            max_streak: number("max_streak"),
+            distribution: values
+                .get("distribution")
+                .map(|v| v.split(',').filter_map(|n| n.parse().ok()).collect())
+                .unwrap_or_default(),
+            daily_played: number("daily_played"),
+            daily_wins: number("daily_wins"),
+            daily_streak: number("daily_streak"),
+            daily_max_streak: number("daily_max_streak"),
+            last_daily: values.get("last_daily").and_then(|v| Date::parse(v)),
+            survival_scores: values
+                .get("survival_scores")
+                .map(|v| {
+                    v.split(',')
+                        .filter_map(|run| run.split_once('@'))
+                        .filter_map(|(score, date)| Some((score.parse().ok()?, Date::parse(date)?)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+    }
+
+    /// Get the stats as named values
+    pub fn to_values(&self) -> Vec<(&'static str, String)> {
+        let distribution: Vec<String> = self.distribution.iter().map(|n| n.to_string()).collect();
+        let survival_scores: Vec<String> = self
+            .survival_scores
+            .iter()
+            .map(|(score, date)| format!("{}@{}", score, date))
+            .collect();
+        return vec![
+            ("played", self.played.to_string()),
+            ("wins", self.wins.to_string()),
+            ("current_streak", self.current_streak.to_string()),
+            ("max_streak", self.max_streak.to_string()),
+            ("distribution", distribution.join(",")),
+            ("daily_played", self.daily_played.to_string()),
+            ("daily_wins", self.daily_wins.to_string()),
+            ("daily_streak", self.daily_streak.to_string()),
+            ("daily_max_streak", self.daily_max_streak.to_string()),
+            (
+                "last_daily",
+                self.last_daily.map(|d| d.to_string()).unwrap_or_default(),
+            ),
+            ("survival_scores", survival_scores.join(",")),
+        ];
+    }
+
+    /// Work out the stats by playing back every game in a history
+    pub fn from_history(history: &History) -> Stats {
+        let mut stats: Stats = Stats::default();
+        for record in &history.games {
+            stats.record(&record.replay(record.guesses.len()), record.date);
+        }
+        return stats;
+    }
+
+    /// Combine with other stats of the same player, keeping the larger of each count so that
+    /// nothing is lost
+    pub fn merge(&mut self, other: &Stats) -> () {
+        self.played = self.played.max(other.played);
+        self.wins = self.wins.max(other.wins);
+        self.current_streak = self.current_streak.max(other.current_streak);
+        self.max_streak = self.max_streak.max(other.max_streak);
+        if self.distribution.len() < other.distribution.len() {
+            self.distribution.resize(other.distribution.len(), 0);
+        }
+        for (count, other_count) in self.distribution.iter_mut().zip(&other.distribution) {
+            *count = (*count).max(*other_count);
+        }
+        self.daily_played = self.daily_played.max(other.daily_played);
+        self.daily_wins = self.daily_wins.max(other.daily_wins);
+        self.daily_streak = self.daily_streak.max(other.daily_streak);
+        self.daily_max_streak = self.daily_max_streak.max(other.daily_max_streak);
+        self.last_daily = self.last_daily.max(other.last_daily);
+        for (score, date) in &other.survival_scores {
+            if !self.survival_scores.contains(&(*score, *date)) {
+                self.record_survival(*score, *date);
+            }
+        }
+    }
+
+    /// Add a finished survival run to the leaderboard, returning its place from 1 if it made it
+    pub fn record_survival(&mut self, score: usize, date: Date) -> Option<usize> {
+        if score == 0 {
+            return None;
+        }
+        // Earlier runs stay ahead of later ones with the same score
+        let place: usize = self
+            .survival_scores
+            .iter()
+            .position(|(other, _)| *other < score)
+            .unwrap_or(self.survival_scores.len());
+        if place >= LEADERBOARD_SIZE {
+            return None;
+        }
+        self.survival_scores.insert(place, (score, date));
+        self.survival_scores.truncate(LEADERBOARD_SIZE);
+        return Some(place + 1);
+    }
+
+    /// Get the most words solved in a survival run
+    pub fn survival_best(&self) -> usize {
+        return self.survival_scores.first().map_or(0, |(score, _)| *score);
+    }
+
+    /// Check if the daily game has already been played on a date
+    pub fn played_daily(&self, date: Date) -> bool {
+        return self.last_daily == Some(date);
+    }
+
+    /// Add a finished game to the stats. A daily game only counts the first time it is played each day.
+    pub fn record(&mut self, game: &Game, today: Date) -> () {
+        if game.mode == GameMode::Daily {
+            if self.played_daily(today) {
+                return;
+            }
+            let continues_streak: bool = self.last_daily == Some(today.add_days(-1));
+            self.daily_played += 1;
+            if game.won {
+                self.daily_wins += 1;
+                self.daily_streak = if continues_streak {
+                    self.daily_streak + 1
+                } else {
+                    1
+                };
+                self.daily_max_streak = self.daily_max_streak.max(self.daily_streak);
+            } else {
+                self.daily_streak = 0;
+            }
+            self.last_daily = Some(today);
+        }
+        self.played += 1;
+        if game.won {
+            self.wins += 1;
+            self.current_streak += 1;
+            self.max_streak = self.max_streak.max(self.current_streak);
+            let guesses: usize = game.guesses_used();
+            if self.distribution.len() < guesses {
+                self.distribution.resize(guesses, 0);
+            }
+            self.distribution[guesses - 1] += 1;
+        } else {
+            self.current_streak = 0;
+        }
+    }
+
+    /// Get the percentage of games won
+    pub fn win_percentage(&self) -> usize {
+        if self.played == 0 {
+            return 0;
+        }
+        return self.wins * 100 / self.played;
+    }
+
+    /// Format the stats as text
+    pub fn summary(&self) -> String {
+        let mut text: String = format!(
+            "Played: {}\nWin %: {}\nCurrent streak: {}\nMax streak: {}\n",
+            self.played,
+            self.win_percentage(),
+            self.current_streak,
+            self.max_streak
+        );
+        text.push_str(&format!(
+            "Daily played: {}\nDaily wins: {}\nDaily streak: {}\nMax daily streak: {}\n",
+            self.daily_played, self.daily_wins, self.daily_streak, self.daily_max_streak
+        ));
+        text.push_str(&format!("Survival best: {}\n", self.survival_best()));
+        text.push_str("Guess distribution:\n");
+        let most: usize = self.distribution.iter().copied().max().unwrap_or(0).max(1);
+        for (i, count) in self.distribution.iter().enumerate() {
+            text.push_str(&format!(
+                "{:>2} | {} {}\n",
+                i + 1,
+                "#".repeat(count * 20 / most),
+                count
+            ));
+        }
+        if !self.survival_scores.is_empty() {
+            text.push_str("Survival leaderboard:\n");
+            for (i, (score, date)) in self.survival_scores.iter().enumerate() {
+                text.push_str(&format!("{:>2}. {:>3} words  {}\n", i + 1, score, date));
+            }
+        }
+        return text;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameOptions;
+
+    /// Play a game of "crane", winning with the second guess or losing every one
+    fn game(mode: GameMode, won: bool) -> Game {
+        let options: GameOptions = GameOptions {
+            mode,
+            ..GameOptions::default()
+        };
+        let words: Vec<String> = vec![String::from("crane"), String::from("slate")];
+        let mut game: Game = Game::with_answer(&options, String::from("crane"), 0);
+        game.enter_word("slate");
+        game.submit(&words);
+        while !game.locked {
+            game.enter_word(if won { "crane" } else { "slate" });
+            game.submit(&words);
+        }
+        return game;
+    }
+
+    /// Read a date that is known to be valid
+    fn date(text: &str) -> Date {
+        return Date::parse(text).unwrap();
+    }
+
+    #[test]
+    fn daily_streaks_need_every_day() -> () {
+        let mut stats: Stats = Stats::default();
+        stats.record(&game(GameMode::Daily, true), date("2026-12-30"));
+        stats.record(&game(GameMode::Daily, true), date("2026-12-31"));
+        stats.record(&game(GameMode::Daily, true), date("2027-01-01"));
+        assert_eq!(stats.daily_streak, 3);
+        // Practice games don't break the daily streak
+        stats.record(&game(GameMode::Practice, false), date("2027-01-01"));
+        assert_eq!(stats.daily_streak, 3);
+        assert_eq!(stats.current_streak, 0);
+
+        // Skipping a day starts over
+        stats.record(&game(GameMode::Daily, true), date("2027-01-03"));
+        assert_eq!(stats.daily_streak, 1);
+        assert_eq!(stats.daily_max_streak, 3);
+        stats.record(&game(GameMode::Daily, false), date("2027-01-04"));
+        assert_eq!(stats.daily_streak, 0);
+        assert_eq!((stats.daily_played, stats.daily_wins), (5, 4));
+        assert_eq!((stats.played, stats.wins), (6, 4));
+        assert_eq!(stats.distribution, vec![0, 4]);
+    }
+
+    #[test]
+    fn daily_games_count_once_a_day() -> () {
+        let mut stats: Stats = Stats::default();
+        let today: Date = date("2026-10-19");
+        stats.record(&game(GameMode::Daily, false), today);
+        assert!(stats.played_daily(today));
+        stats.record(&game(GameMode::Daily, true), today);
+        assert_eq!((stats.played, stats.wins), (1, 0));
+        assert_eq!((stats.daily_played, stats.daily_streak), (1, 0));
+        assert!(!stats.played_daily(today.add_days(1)));
+    }
+
+    #[test]
+    fn merging_keeps_the_larger_counts() -> () {
+        let mut stats: Stats = Stats {
+            played: 10,
+            wins: 8,
+            max_streak: 5,
+            distribution: vec![1, 2],
+            last_daily: Some(date("2026-10-18")),
+            ..Stats::default()
+        };
+        stats.record_survival(4, date("2026-10-01"));
+        let mut other: Stats = Stats {
+            played: 7,
+            wins: 7,
+            current_streak: 7,
+            max_streak: 7,
+            distribution: vec![0, 3, 4],
+            last_daily: Some(date("2026-10-19")),
+            ..Stats::default()
+        };
+        other.record_survival(4, date("2026-10-01"));
+        other.record_survival(9, date("2026-10-02"));
+        stats.merge(&other);
+        assert_eq!((stats.played, stats.wins), (10, 8));
+        assert_eq!((stats.current_streak, stats.max_streak), (7, 7));
+        assert_eq!(stats.distribution, vec![1, 3, 4]);
+        assert_eq!(stats.last_daily, Some(date("2026-10-19")));
+        assert_eq!(
+            stats.survival_scores,
+            vec![(9, date("2026-10-02")), (4, date("2026-10-01"))]
+        );
+    }
+
+    #[test]
+    fn stats_round_trip() -> () {
+        let mut stats: Stats = Stats::default();
+        stats.record(&game(GameMode::Daily, true), date("2026-10-19"));
+        stats.record_survival(3, date("2026-10-19"));
+        let values: HashMap<String, String> = stats
+            .to_values()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+        let read: Stats = Stats::from_values(&values);
+        assert_eq!(read.to_values(), stats.to_values());
+        assert_eq!(Stats::from_values(&HashMap::new()).played, 0);
+    }
+}
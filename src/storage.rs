@@ -0,0 +1,47 @@
+use crate::profiles::{profile_dir, Profiles};
+use std::collections::HashMap;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::io;
+use std::path::PathBuf;
+
+/// Get the directory the app keeps its data in, following the XDG base directory spec
+pub fn app_dir() -> PathBuf {
+    let base: PathBuf = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => match std::env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(".local").join("share"),
+            None => PathBuf::from("."),
+        },
+    };
+    return base.join("rustle");
+}
+
+/// Get the directory the active profile's saved data is kept in
+pub fn data_dir() -> PathBuf {
+    return profile_dir(&Profiles::active_id());
+}
+
+/// Read a file of `key=value` lines. A missing file reads as empty.
+pub fn load_key_values(path: &PathBuf) -> HashMap<String, String> {
+    let mut values: HashMap<String, String> = HashMap::new();
+    if let Ok(text) = read_to_string(path) {
+        for line in text.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                values.insert(key.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+    return values;
+}
+
+/// Write a file of `key=value` lines, creating its directory if needed
+pub fn save_key_values(path: &PathBuf, values: &[(&str, String)]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    let mut text: String = String::new();
+    for (key, value) in values {
+        text.push_str(&format!("{}={}\n", key, value));
+    }
+    return write(path, text);
+}
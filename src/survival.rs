@@ -0,0 +1,87 @@
+use crate::game::{GameOptions, MAX_GUESS_LIMIT};
+
+/// An endless run of words, where the guesses left over after solving a word are added to the
+/// next one. It ends at the first word that isn't solved.
+#[derive(Clone, Copy, Debug)]
+pub struct SurvivalRun {
+    /// The guesses every word gets before the bonus
+    pub base_guesses: usize,
+    /// The guesses allowed for the word being played, including the bonus
+    pub guesses: usize,
+    /// The number of words solved so far, which is the run's score
+    pub solved: usize,
+    /// Where the run placed on the leaderboard once it ended, if it made it
+    pub place: Option<usize>,
+}
+
+impl SurvivalRun {
+    /// Start a run with the guess limit in the options
+    pub fn new(options: &GameOptions) -> SurvivalRun {
+        return SurvivalRun {
+            base_guesses: options.max_guesses,
+            guesses: options.max_guesses,
+            solved: 0,
+            place: None,
+        };
+    }
+
+    /// Get the extra guesses carried over to the word being played
+    pub fn bonus(&self) -> usize {
+        return self.guesses - self.base_guesses;
+    }
+
+    /// Record that the word was solved in a number of guesses, carrying the unused ones over
+    /// to the next word
+    pub fn solve(&mut self, guesses_used: usize) -> () {
+        let unused: usize = self.guesses.saturating_sub(guesses_used);
+        self.solved += 1;
+        self.guesses = (self.base_guesses + unused).min(MAX_GUESS_LIMIT);
+    }
+
+    /// Get the options for the next word, with the bonus guesses added
+    pub fn word_options(&self, options: &GameOptions) -> GameOptions {
+        return GameOptions {
+            max_guesses: self.guesses,
+            ..options.clone()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unused_guesses_carry_over() -> () {
+        let options: GameOptions = GameOptions::default();
+        let mut run: SurvivalRun = SurvivalRun::new(&options);
+        assert_eq!(run.guesses, 6);
+        assert_eq!(run.bonus(), 0);
+
+        run.solve(4);
+        assert_eq!(run.guesses, 8);
+        assert_eq!(run.bonus(), 2);
+        assert_eq!(run.word_options(&options).max_guesses, 8);
+        // Only what's left of this word's guesses carries over, not the last bonus too
+        run.solve(7);
+        assert_eq!(run.guesses, 7);
+        run.solve(7);
+        assert_eq!(run.bonus(), 0);
+        assert_eq!(run.solved, 3);
+    }
+
+    #[test]
+    fn the_bonus_is_capped() -> () {
+        let options: GameOptions = GameOptions {
+            max_guesses: 10,
+            ..GameOptions::default()
+        };
+        let mut run: SurvivalRun = SurvivalRun::new(&options);
+        run.solve(1);
+        assert_eq!(run.guesses, MAX_GUESS_LIMIT);
+        run.solve(1);
+        assert_eq!(run.guesses, MAX_GUESS_LIMIT);
+        assert_eq!(run.bonus(), MAX_GUESS_LIMIT - 10);
+        assert_eq!(run.word_options(&options).max_guesses, MAX_GUESS_LIMIT);
+    }
+}
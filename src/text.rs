@@ -0,0 +1,5 @@
+/// Mark a message for translation without translating it yet, for constants that the window
+/// version translates where they're shown
+pub const fn n_(message: &'static str) -> &'static str {
+    return message;
+}
@@ -0,0 +1,134 @@
+use crate::settings::AppSettings;
+use gettextrs::gettext;
+use gtk4 as gtk;
+use gtk4::prelude::*;
+use gtk4::{gdk, CssProvider, Widget};
+use libadwaita::{ColorScheme, StyleManager};
+use rustle::share::Palette;
+
+/// Whether the app is light, dark or follows the system
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Theme {
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// Every theme
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+    /// Get the name used to save the theme
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Theme::System => "system",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        };
+    }
+
+    /// Get the label shown for the theme in menus
+    pub fn label(&self) -> String {
+        return match self {
+            Theme::System => gettext("Follow System"),
+            Theme::Light => gettext("Light"),
+            Theme::Dark => gettext("Dark"),
+        };
+    }
+
+    /// Find a theme by its name
+    pub fn from_name(name: &str) -> Option<Theme> {
+        return Theme::ALL.into_iter().find(|theme| theme.name() == name);
+    }
+
+    /// Get the libadwaita color scheme for the theme
+    fn color_scheme(&self) -> ColorScheme {
+        return match self {
+            Theme::System => ColorScheme::PreferLight,
+            Theme::Light => ColorScheme::ForceLight,
+            Theme::Dark => ColorScheme::ForceDark,
+        };
+    }
+}
+
+/// Load the saved theme, following the system if none was saved
+pub fn load_theme() -> Theme {
+    return Theme::from_name(&AppSettings::load().theme()).unwrap_or(Theme::System);
+}
+
+/// Switch to a theme and save it
+pub fn set_theme(theme: Theme) -> () {
+    StyleManager::default().set_color_scheme(theme.color_scheme());
+    AppSettings::load().set_theme(theme.name());
+}
+
+/// Get the label shown for a tile palette in menus, translated
+pub fn palette_label(palette: Palette) -> String {
+    return match palette {
+        Palette::Classic => gettext("Classic"),
+        Palette::HighContrast => gettext("High Contrast"),
+        Palette::Deuteranopia => gettext("Deuteranopia"),
+        Palette::Protanopia => gettext("Protanopia"),
+    };
+}
+
+/// Load the saved tile palette
+pub fn load_palette() -> Palette {
+    return Palette::from_name(&AppSettings::load().palette()).unwrap_or(Palette::Classic);
+}
+
+/// Load whether tiles show a pattern as well as a color
+pub fn load_patterns() -> bool {
+    return AppSettings::load().patterns();
+}
+
+/// Save the tile palette and patterns setting
+pub fn save_palette(palette: Palette, patterns: bool) -> () {
+    let settings: AppSettings = AppSettings::load();
+    settings.set_palette(palette.name());
+    settings.set_patterns(patterns);
+}
+
+/// Show a window's tiles and keys in a palette, with or without patterns
+pub fn apply_palette(window: &impl IsA<Widget>, palette: Palette, patterns: bool) -> () {
+    for other in Palette::ALL {
+        window.remove_css_class(&format!("palette-{}", other.name()));
+    }
+    window.add_css_class(&format!("palette-{}", palette.name()));
+    if patterns {
+        window.add_css_class("patterns");
+    } else {
+        window.remove_css_class("patterns");
+    }
+}
+
+/// Load the app's styles, swapping the dark styles in and out whenever the app changes between
+/// light and dark
+pub fn load_styles() -> () {
+    let display: gdk::Display = gdk::Display::default().expect("Could not connect to a display.");
+    let provider: CssProvider = CssProvider::new();
+    provider.load_from_string(include_str!("../assets/style/style.css"));
+    gtk::style_context_add_provider_for_display(
+        &display,
+        &provider,
+        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+    );
+
+    let dark_provider: CssProvider = CssProvider::new();
+    dark_provider.load_from_string(include_str!("../assets/style/style.dark.css"));
+    let style_manager: StyleManager = StyleManager::default();
+    style_manager.set_color_scheme(load_theme().color_scheme());
+    let update = move |style_manager: &StyleManager| {
+        if style_manager.is_dark() {
+            gtk::style_context_add_provider_for_display(
+                &display,
+                &dark_provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        } else {
+            gtk::style_context_remove_provider_for_display(&display, &dark_provider);
+        }
+    };
+    update(&style_manager);
+    style_manager.connect_dark_notify(update);
+}
@@ -0,0 +1,157 @@
+use crate::i18n::fill;
+use gettextrs::gettext;
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4::Orientation::{Horizontal, Vertical};
+use gtk4::{Align, Box, Button, Label, Popover, PositionType, Widget};
+use rustle::text::n_;
+
+/// The answer of the tutorial game
+pub const ANSWER: &str = "steep";
+
+/// What a callout points at
+pub enum Target {
+    /// The whole board
+    Board,
+    /// A tile, by row and column
+    Tile(usize, usize),
+    /// A key on the on-screen keyboard
+    Key(char),
+}
+
+impl Target {
+    /// Get which side of the target the callout goes on, keeping scored rows in view
+    fn position(&self) -> PositionType {
+        return match self {
+            Target::Board => PositionType::Bottom,
+            Target::Tile(_, col) if *col < 3 => PositionType::Left,
+            Target::Tile(_, _) => PositionType::Right,
+            Target::Key(_) => PositionType::Top,
+        };
+    }
+}
+
+/// A step of the tutorial: an optional guess to play, then a callout explaining the result
+pub struct Step {
+    pub guess: Option<&'static str>,
+    pub target: Target,
+    pub text: &'static str,
+}
+
+/// The scripted game. The guesses are picked to show every color, and how a letter guessed more
+/// times than it's in the answer is scored.
+pub const STEPS: [Step; 9] = [
+    Step {
+        guess: None,
+        target: Target::Board,
+        text: n_(
+            "Welcome to Rustle! You have six guesses to find a hidden five-letter word. \
+             Let's play through a game together.",
+        ),
+    },
+    Step {
+        guess: Some("crane"),
+        target: Target::Tile(0, 4),
+        text: n_("E turned yellow: it's in the word, but not in this spot."),
+    },
+    Step {
+        guess: None,
+        target: Target::Tile(0, 0),
+        text: n_("C turned gray: it isn't in the word at all. Neither are R, A or N."),
+    },
+    Step {
+        guess: None,
+        target: Target::Key('a'),
+        text: n_(
+            "The keyboard keeps track of every letter too, so you can see which ones are \
+             ruled out.",
+        ),
+    },
+    Step {
+        guess: Some("geese"),
+        target: Target::Tile(1, 2),
+        text: n_("This E is green: it's in the word and in the right spot."),
+    },
+    Step {
+        guess: None,
+        target: Target::Tile(1, 1),
+        text: n_(
+            "GEESE has three E's, but a letter only lights up as many times as it's in the \
+             answer. Green matches count first, then the rest turn yellow from left to right…",
+        ),
+    },
+    Step {
+        guess: None,
+        target: Target::Tile(1, 4),
+        text: n_("…until the answer runs out. This E is gray because the answer only has two."),
+    },
+    Step {
+        guess: None,
+        target: Target::Tile(1, 3),
+        text: n_("S is yellow, so it goes somewhere else. Time to put it all together."),
+    },
+    Step {
+        guess: Some("steep"),
+        target: Target::Board,
+        text: n_("All green, the word was STEEP! Now it's your turn."),
+    },
+];
+
+/// Create a callout for a step of the tutorial, attached to the widget it points at.
+/// `next` is called by its button, and `skip` if the player leaves the tutorial early.
+pub fn build_callout(
+    target: &impl IsA<Widget>,
+    index: usize,
+    next: impl Fn() + 'static,
+    skip: impl Fn() + 'static,
+) -> Popover {
+    let step: &Step = &STEPS[index];
+    let last: bool = index == STEPS.len() - 1;
+
+    let content: Box = Box::new(Vertical, 12);
+    content.set_margin_top(6);
+    content.set_margin_bottom(6);
+    content.set_margin_start(6);
+    content.set_margin_end(6);
+
+    let text: Label = Label::new(Some(&gettext(step.text)));
+    text.set_wrap(true);
+    text.set_xalign(0.0);
+    text.set_max_width_chars(32);
+    content.append(&text);
+
+    let buttons: Box = Box::new(Horizontal, 6);
+    let progress: Label = Label::new(Some(&fill(
+        &gettext("{} of {}"),
+        &[&(index + 1).to_string(), &STEPS.len().to_string()],
+    )));
+    progress.add_css_class("dim-label");
+    progress.set_hexpand(true);
+    progress.set_xalign(0.0);
+    buttons.append(&progress);
+    if !last {
+        let skip_button: Button = Button::with_label(&gettext("Skip"));
+        skip_button.add_css_class("flat");
+        skip_button.connect_clicked(move |_| skip());
+        buttons.append(&skip_button);
+    }
+    let next_button: Button = Button::with_label(&if last {
+        gettext("Start Playing")
+    } else {
+        gettext("Next")
+    });
+    next_button.add_css_class("suggested-action");
+    next_button.set_halign(Align::End);
+    next_button.connect_clicked(move |_| next());
+    buttons.append(&next_button);
+    content.append(&buttons);
+
+    let popover: Popover = Popover::builder()
+        .child(&content)
+        .position(step.target.position())
+        .autohide(false)
+        .default_widget(&next_button)
+        .build();
+    popover.set_parent(target);
+    return popover;
+}
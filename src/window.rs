@@ -0,0 +1,2404 @@
+use crate::achievements_view::{announce_achievements, show_achievements};
+use crate::animation;
+use crate::archive_view::show_archive;
+use crate::help::{build_shortcuts_window, show_how_to_play};
+use crate::history_view::{mode_label, show_history};
+use crate::i18n::fill;
+use crate::preferences::show_preferences;
+use crate::profiles_view::show_profiles;
+use crate::race_view::{build_race_panel, show_race_dialog, RaceSession, RACE_PANEL_WIDTH};
+use crate::settings::AppSettings;
+use crate::theme;
+use crate::tutorial::{self, Step, Target};
+use gettextrs::{gettext, ngettext};
+use gtk::prelude::*;
+use gtk4 as gtk;
+use gtk4::accessible::Property;
+use gtk4::gdk::{Key, ModifierType};
+use gtk4::gio::{self, Cancellable, File, ListStore, Menu, SimpleAction};
+use gtk4::glib::{self, ControlFlow, Propagation, Variant, VariantTy};
+use gtk4::pango::{self, AttrList, AttrSize};
+use gtk4::AccessibleAnnouncementPriority;
+use gtk4::Orientation::{Horizontal, Vertical};
+use gtk4::{
+    AlertDialog, Align, Box, Button, DrawingArea, Entry, EventControllerKey, FileDialog,
+    FileFilter, Fixed, GestureClick, Grid, InputPurpose, Label, MenuButton, Overlay, PickFlags,
+    Popover, Widget,
+};
+use libadwaita::prelude::{
+    AdwApplicationWindowExt, AdwDialogExt, AlertDialogExt, AlertDialogExtManual, BinExt,
+};
+use libadwaita::{
+    Application, ApplicationWindow, Bin, Breakpoint, BreakpointCondition, ButtonContent, HeaderBar,
+    ResponseAppearance, Toast, ToastOverlay, ToastPriority, WindowTitle,
+};
+use rustle::achievements::{Achievement, Unlocks};
+use rustle::archive::Archive;
+use rustle::clock::{format_clock, Clock};
+use rustle::date::Date;
+use rustle::dictionary::{Definition, Dictionary};
+use rustle::export::{export_file, import_file};
+use rustle::game::{
+    daily_seed, Game, GameMode, GameOptions, GuessResult, HardModeViolation, OptionsError,
+    WordLists, COLOR_GRAY, COLOR_GREEN, COLOR_UNSET, COLOR_YELLOW, MAX_AVOID_RECENT,
+    MAX_GUESS_LIMIT, MAX_SPEED_RUN_WORDS, MAX_TIME_LIMIT, MAX_WORD_LENGTH, MIN_TIME_LIMIT,
+    MIN_WORD_LENGTH,
+};
+use rustle::history::{GameRecord, History};
+use rustle::profiles::Profiles;
+use rustle::race::{Message, RaceClient, RaceEvent, RaceHost, RaceSettings};
+use rustle::saved_game::{save_game, take_saved_game};
+use rustle::share::share_text;
+use rustle::speedrun::{PersonalBests, SpeedRun};
+use rustle::stats::Stats;
+use rustle::survival::SurvivalRun;
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+
+/// Layouts of the on-screen keyboard
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyboardLayout {
+    Qwerty,
+    Qwertz,
+    Azerty,
+    Dvorak,
+    Alphabetical,
+}
+
+impl KeyboardLayout {
+    /// Every layout
+    pub const ALL: [KeyboardLayout; 5] = [
+        KeyboardLayout::Qwerty,
+        KeyboardLayout::Qwertz,
+        KeyboardLayout::Azerty,
+        KeyboardLayout::Dvorak,
+        KeyboardLayout::Alphabetical,
+    ];
+
+    /// Get the name used to save the layout
+    pub fn name(&self) -> &'static str {
+        return match self {
+            KeyboardLayout::Qwerty => "qwerty",
+            KeyboardLayout::Qwertz => "qwertz",
+            KeyboardLayout::Azerty => "azerty",
+            KeyboardLayout::Dvorak => "dvorak",
+            KeyboardLayout::Alphabetical => "alphabetical",
+        };
+    }
+
+    /// Get the label shown for the layout in preferences
+    pub fn label(&self) -> String {
+        return match self {
+            KeyboardLayout::Qwerty => String::from("QWERTY"),
+            KeyboardLayout::Qwertz => String::from("QWERTZ"),
+            KeyboardLayout::Azerty => String::from("AZERTY"),
+            KeyboardLayout::Dvorak => String::from("Dvorak"),
+            KeyboardLayout::Alphabetical => gettext("Alphabetical"),
+        };
+    }
+
+    /// Find a layout by its name
+    pub fn from_name(name: &str) -> Option<KeyboardLayout> {
+        return KeyboardLayout::ALL
+            .into_iter()
+            .find(|layout| layout.name() == name);
+    }
+
+    /// Get the letters on each row of keys, from top to bottom
+    pub fn rows(&self) -> [&'static str; 3] {
+        return match self {
+            KeyboardLayout::Qwerty => ["qwertyuiop", "asdfghjkl", "zxcvbnm"],
+            KeyboardLayout::Qwertz => ["qwertzuiop", "asdfghjkl", "yxcvbnm"],
+            KeyboardLayout::Azerty => ["azertyuiop", "qsdfghjklm", "wxcvbn"],
+            KeyboardLayout::Dvorak => ["pyfgcrl", "aoeuidhtns", "qjkxbmwvz"],
+            KeyboardLayout::Alphabetical => ["abcdefghi", "jklmnopqr", "stuvwxyz"],
+        };
+    }
+}
+
+/// Gap between tiles, in pixels
+const TILE_SPACING: i32 = 4;
+/// Gap between keys, in pixels
+const KEY_SPACING: i32 = 4;
+/// Smallest and largest tile sizes, in pixels
+const TILE_SIZE_RANGE: (i32, i32) = (20, 96);
+/// Smallest and largest key widths, in pixels
+const KEY_WIDTH_RANGE: (i32, i32) = (20, 64);
+/// Windows narrower than this get the compact, phone-like layout
+const NARROW_WIDTH: i32 = 550;
+/// Windows at least this wide put the keyboard beside the board
+const WIDE_WIDTH: i32 = 1100;
+/// The time left in a timed game when the clock turns red
+const LOW_TIME: Duration = Duration::from_secs(10);
+/// The most definitions of a word shown after a game
+const MAX_DEFINITIONS_SHOWN: usize = 3;
+
+/// Describe a tile or key color for screen readers
+fn color_description(color: usize) -> String {
+    return match color {
+        COLOR_GREEN => gettext("correct position"),
+        COLOR_YELLOW => gettext("in the word, wrong position"),
+        COLOR_GRAY => gettext("not in the word"),
+        _ => String::new(),
+    };
+}
+
+/// Describe a tile for screen readers, such as "Row 2, letter 3, R, correct position"
+fn tile_description(row: usize, col: usize, c: char, color: usize, cursor: bool) -> String {
+    let letter: String = if c == ' ' {
+        gettext("empty")
+    } else {
+        c.to_ascii_uppercase().to_string()
+    };
+    let mut description: String = fill(
+        &gettext("Row {}, letter {}, {}"),
+        &[&(row + 1).to_string(), &(col + 1).to_string(), &letter],
+    );
+    if color != COLOR_UNSET {
+        description.push_str(", ");
+        description.push_str(&color_description(color));
+    }
+    if cursor {
+        description.push_str(", ");
+        description.push_str(&gettext("cursor"));
+    }
+    return description;
+}
+
+/// Summarise a scored row for screen readers, such as "Guess 2, CRANE: C not in the word, …"
+pub fn guess_announcement(board_chars: &[char], board_colors: &[usize], row: usize) -> String {
+    let word: String = board_chars.iter().collect::<String>().to_uppercase();
+    let letters: Vec<String> = board_chars
+        .iter()
+        .zip(board_colors)
+        .map(|(c, &color)| format!("{} {}", c.to_ascii_uppercase(), color_description(color)))
+        .collect();
+    return fill(
+        &gettext("Guess {}, {}: {}"),
+        &[&(row + 1).to_string(), &word, &letters.join(", ")],
+    );
+}
+
+/// Get the tile at a position on the board
+pub fn tile_at(grid: &Grid, x: usize, y: usize) -> Label {
+    let w: Widget = grid.child_at(x as i32, y as i32).unwrap();
+    return w.first_child().unwrap().downcast::<Label>().ok().unwrap();
+}
+
+/// Get the tiles of a row of the board
+pub fn row_tiles(grid: &Grid, row: usize, word_length: usize) -> Vec<Label> {
+    return (0..word_length).map(|x| tile_at(grid, x, row)).collect();
+}
+
+/// Update the colors & letters of the board
+pub fn update_board(
+    board_chars: &[Vec<char>],
+    board_colors: &[Vec<usize>],
+    cur_row: usize,
+    cur_col: usize,
+    grid: &Grid,
+) -> () {
+    for row in 0..board_chars.len() {
+        for chr in 0..board_chars[row].len() {
+            let mut c: char = board_chars[row][chr];
+            let color: usize = board_colors[row][chr];
+            let l: Label = tile_at(grid, chr, row);
+            let cursor: bool = color == COLOR_UNSET && row == cur_row && chr == cur_col;
+            l.update_property(&[Property::Label(&tile_description(
+                row, chr, c, color, cursor,
+            ))]);
+            l.remove_css_class("green");
+            l.remove_css_class("yellow");
+            l.remove_css_class("gray");
+            l.remove_css_class("cursor");
+            if color == COLOR_GREEN {
+                l.add_css_class("green");
+            } else if color == COLOR_YELLOW {
+                l.add_css_class("yellow");
+            } else if color == COLOR_GRAY {
+                l.add_css_class("gray");
+            } else if cursor {
+                l.add_css_class("cursor");
+                if c == ' ' {
+                    c = '_';
+                }
+            }
+            l.set_text(&c.to_string().to_uppercase());
+        }
+    }
+}
+
+/// Update the colors on a row of the keyboard
+fn update_keyboard_row(
+    letter_states: &HashMap<char, usize>,
+    keyboard_row: &Box,
+    chars: &str,
+) -> () {
+    let mut child: Widget = keyboard_row.first_child().expect("Keyboard key missing!");
+
+    for c in chars.chars() {
+        if let Ok(l) = child.clone().downcast::<Label>() {
+            if let Some(color) = letter_states.get(&c) {
+                l.remove_css_class("green");
+                l.remove_css_class("yellow");
+                l.remove_css_class("gray");
+                l.remove_css_class("cursor");
+                if *color == COLOR_GREEN {
+                    l.add_css_class("green");
+                } else if *color == COLOR_YELLOW {
+                    l.add_css_class("yellow");
+                } else if *color == COLOR_GRAY {
+                    l.add_css_class("gray");
+                }
+                let description: String = match *color {
+                    COLOR_UNSET => c.to_ascii_uppercase().to_string(),
+                    _ => format!("{}, {}", c.to_ascii_uppercase(), color_description(*color)),
+                };
+                l.update_property(&[Property::Label(&description)]);
+            }
+        }
+        match child.next_sibling() {
+            Some(next_child) => child = next_child,
+            None => break,
+        }
+    }
+}
+
+/// Update the colors on the keyboard
+pub fn update_keyboard(
+    letter_states: &HashMap<char, usize>,
+    keyboard_rows: &[Box],
+    layout: KeyboardLayout,
+) -> () {
+    for (keyboard_row, chars) in keyboard_rows.iter().zip(layout.rows()) {
+        update_keyboard_row(letter_states, keyboard_row, chars);
+    }
+}
+
+/// Find the key for a letter on the keyboard
+fn key_label(keyboard_rows: &[Box], layout: KeyboardLayout, c: char) -> Option<Label> {
+    for (keyboard_row, chars) in keyboard_rows.iter().zip(layout.rows()) {
+        if let Some(index) = chars.chars().position(|key| key == c) {
+            let mut child: Option<Widget> = keyboard_row.first_child();
+            for _ in 0..index {
+                child = child.and_then(|child| child.next_sibling());
+            }
+            return child.and_then(|child| child.downcast::<Label>().ok());
+        }
+    }
+    return None;
+}
+
+/// Create a row of keys
+fn build_keyboard_row(keys: &str) -> Box {
+    let keyboard_row: Box = Box::new(Horizontal, KEY_SPACING);
+    keyboard_row.set_halign(Align::Center);
+    for c in keys.chars() {
+        let key: Label = Label::builder().build();
+        key.set_text(&c.to_uppercase().to_string());
+        key.add_css_class("key");
+        keyboard_row.append(&key);
+    }
+    return keyboard_row;
+}
+
+/// Create the rows of keys
+pub fn build_keyboard(layout: KeyboardLayout) -> Vec<Box> {
+    return layout.rows().into_iter().map(build_keyboard_row).collect();
+}
+
+/// Create an empty board
+pub fn build_grid(word_length: usize, max_guesses: usize) -> Grid {
+    let grid: Grid = Grid::builder().build();
+    grid.set_column_homogeneous(true);
+    grid.set_row_homogeneous(true);
+    grid.set_column_spacing(TILE_SPACING as u32);
+    grid.set_row_spacing(TILE_SPACING as u32);
+    grid.set_hexpand(false);
+    for y in 0i32..max_guesses as i32 {
+        for x in 0i32..word_length as i32 {
+            let label: Label = Label::builder().build();
+            label.add_css_class("tile");
+            label.set_text("");
+            // The tile sits in a Fixed so it can be moved and scaled by animations
+            let fixed: Fixed = Fixed::new();
+            fixed.put(&label, 0.0, 0.0);
+            grid.attach(&fixed, x, y, 1, 1);
+        }
+    }
+    return grid;
+}
+
+/// Set the size of a tile or key, scaling its letter with it
+fn scale_label(label: &Label, width: i32, height: i32, font_scale: f64) -> () {
+    label.set_size_request(width, height);
+    let font_size: f64 = width.min(height) as f64 * font_scale;
+    let attributes: AttrList = AttrList::new();
+    attributes.insert(AttrSize::new_size_absolute(
+        (font_size * pango::SCALE as f64) as i32,
+    ));
+    label.set_attributes(Some(&attributes));
+}
+
+/// Resize every tile of the board
+pub fn resize_grid(grid: &Grid, word_length: usize, max_guesses: usize, tile_size: i32) -> () {
+    for row in 0..max_guesses {
+        for tile in row_tiles(grid, row, word_length) {
+            scale_label(&tile, tile_size, tile_size, 0.5);
+        }
+    }
+}
+
+/// Resize every key of the keyboard
+pub fn resize_keyboard(keyboard_rows: &[Box], key_width: i32, key_height: i32) -> () {
+    for keyboard_row in keyboard_rows {
+        let mut child: Option<Widget> = keyboard_row.first_child();
+        while let Some(key) = child {
+            if let Ok(key) = key.clone().downcast::<Label>() {
+                scale_label(&key, key_width, key_height, 0.45);
+            }
+            child = key.next_sibling();
+        }
+    }
+}
+
+/// Describe a finished speed run: the split of each word, compared with the personal best's if
+/// there was one, then the total
+fn speed_run_summary(run: &SpeedRun, best: Option<&[Duration]>, new_best: bool) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for (i, split) in run.splits.iter().enumerate() {
+        let mut line: String = fill(
+            &gettext("Word {}: {}"),
+            &[&(i + 1).to_string(), &format_clock(*split, true)],
+        );
+        if let Some(best_split) = best.and_then(|best| best.get(i)) {
+            line.push_str(&format!(" ({})", format_difference(*split, *best_split)));
+        }
+        lines.push(line);
+    }
+    lines.push(fill(
+        &gettext("Total {}"),
+        &[&format_clock(run.total(), true)],
+    ));
+    if new_best {
+        lines.push(gettext("New personal best!"));
+    } else if let Some(best) = best {
+        lines.push(fill(
+            &gettext("Personal best {}"),
+            &[&format_clock(best.iter().sum(), true)],
+        ));
+    }
+    return lines.join("\n");
+}
+
+/// Format how much slower or faster a time was than another, like `+0:01.2`
+fn format_difference(time: Duration, compared_to: Duration) -> String {
+    if time >= compared_to {
+        return format!("+{}", format_clock(time - compared_to, true));
+    }
+    return format!("−{}", format_clock(compared_to - time, true));
+}
+
+/// Describe the hint a guess ignored in hard mode
+fn hard_mode_message(violation: HardModeViolation) -> String {
+    return match violation {
+        HardModeViolation::Misplaced(position, letter) => fill(
+            // Translators: the first {} is the position of a letter in the word, such as 1
+            &gettext("Letter {} must be {}"),
+            &[
+                &(position + 1).to_string(),
+                &letter.to_uppercase().to_string(),
+            ],
+        ),
+        HardModeViolation::Missing(letter) => fill(
+            &gettext("Guess must contain {}"),
+            &[&letter.to_uppercase().to_string()],
+        ),
+    };
+}
+
+/// Describe why a game can't be played with some options
+pub fn options_error_message(error: &OptionsError) -> String {
+    return match error {
+        OptionsError::WordLength => fill(
+            &gettext("Word length must be between {} and {}"),
+            &[&MIN_WORD_LENGTH.to_string(), &MAX_WORD_LENGTH.to_string()],
+        ),
+        OptionsError::Guesses => fill(
+            &gettext("Guesses must be between 1 and {}"),
+            &[&MAX_GUESS_LIMIT.to_string()],
+        ),
+        OptionsError::TimeLimit => fill(
+            &gettext("Time limit must be between {} and {} seconds"),
+            &[&MIN_TIME_LIMIT.to_string(), &MAX_TIME_LIMIT.to_string()],
+        ),
+        OptionsError::SpeedRunWords => fill(
+            &gettext("Speed runs must have between 1 and {} words"),
+            &[&MAX_SPEED_RUN_WORDS.to_string()],
+        ),
+        OptionsError::AvoidRecent => fill(
+            &gettext("At most the last {} games can be avoided"),
+            &[&MAX_AVOID_RECENT.to_string()],
+        ),
+        OptionsError::Seed(mode) => fill(
+            &gettext("A seed can't be used in {} mode"),
+            &[&mode_label(*mode)],
+        ),
+        OptionsError::ListNotFound(path) => fill(&gettext("Word list \"{}\" not found"), &[path]),
+        OptionsError::NoAnswers(path, word_length) => fill(
+            &gettext("Answer list \"{}\" has no {}-letter words"),
+            &[path, &word_length.to_string()],
+        ),
+    };
+}
+
+/// Get the file types stats and history can be exported to
+fn data_file_filters() -> ListStore {
+    let json: FileFilter = FileFilter::new();
+    json.set_name(Some(&gettext("JSON")));
+    json.add_suffix("json");
+    let csv: FileFilter = FileFilter::new();
+    csv.set_name(Some(&gettext("CSV")));
+    csv.add_suffix("csv");
+    let filters: ListStore = ListStore::new::<FileFilter>();
+    filters.append(&json);
+    filters.append(&csv);
+    return filters;
+}
+
+/// Show a toast with an error message
+pub fn show_error_toast(toast_overlay: &ToastOverlay, message: &str) -> () {
+    toast_overlay.dismiss_all();
+    let toast: Toast = Toast::new(message);
+    toast.set_timeout(2);
+    toast.set_priority(ToastPriority::High);
+    toast_overlay.add_toast(toast);
+}
+
+/// Format the stats for the statistics dialog, like `Stats::summary` but translated
+fn statistics_summary(stats: &Stats) -> String {
+    let lines: [(String, usize); 9] = [
+        (gettext("Played"), stats.played),
+        (gettext("Win %"), stats.win_percentage()),
+        (gettext("Current streak"), stats.current_streak),
+        (gettext("Max streak"), stats.max_streak),
+        (gettext("Daily played"), stats.daily_played),
+        (gettext("Daily wins"), stats.daily_wins),
+        (gettext("Daily streak"), stats.daily_streak),
+        (gettext("Max daily streak"), stats.daily_max_streak),
+        (gettext("Survival best"), stats.survival_best()),
+    ];
+    let mut text: String = String::new();
+    for (label, value) in lines {
+        text.push_str(&format!("{}: {}\n", label, value));
+    }
+    text.push_str(&format!("{}:\n", gettext("Guess distribution")));
+    let most: usize = stats.distribution.iter().copied().max().unwrap_or(0).max(1);
+    for (i, count) in stats.distribution.iter().enumerate() {
+        text.push_str(&format!(
+            "{:>2} | {} {}\n",
+            i + 1,
+            "#".repeat(count * 20 / most),
+            count
+        ));
+    }
+    if !stats.survival_scores.is_empty() {
+        text.push_str(&format!("{}:\n", gettext("Survival leaderboard")));
+        for (i, (score, date)) in stats.survival_scores.iter().enumerate() {
+            let words: String = fill(
+                &ngettext("{} word", "{} words", *score as u32),
+                &[&score.to_string()],
+            );
+            text.push_str(&format!("{:>2}. {:<10} {}\n", i + 1, words, date));
+        }
+    }
+    return text;
+}
+
+/// Write out the first few definitions of a word, one per line, or None if there are none
+pub fn definitions_text(definitions: &[Definition]) -> Option<String> {
+    if definitions.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = definitions
+        .iter()
+        .take(MAX_DEFINITIONS_SHOWN)
+        .map(|definition| {
+            if definition.part_of_speech.is_empty() {
+                return definition.text.clone();
+            }
+            return format!("{}: {}", definition.part_of_speech, definition.text);
+        })
+        .collect();
+    return Some(lines.join("\n"));
+}
+
+/// Get the text shown under the title for a game, given the date of its puzzle if it's a past
+/// daily puzzle from the archive
+fn game_subtitle(game: &Game, archive: Option<Date>) -> String {
+    let mut subtitle: String = match game.mode {
+        GameMode::Practice => fill(&gettext("Seed {}"), &[&game.seed.to_string()]),
+        GameMode::Daily => match archive {
+            Some(date) => fill(&gettext("Archive {}"), &[&date.to_string()]),
+            None => fill(&gettext("Daily {}"), &[&Date::today().to_string()]),
+        },
+        GameMode::Timed => gettext("Timed"),
+        GameMode::SpeedRun => gettext("Speed Run"),
+        GameMode::Survival => gettext("Survival"),
+    };
+    if game.hard_mode {
+        subtitle.push_str(" · ");
+        subtitle.push_str(&gettext("Hard"));
+    }
+    return subtitle;
+}
+
+/// Build the menu for switching between profiles
+fn profile_menu(profiles: &Profiles) -> Menu {
+    let switch_menu: Menu = Menu::new();
+    for profile in &profiles.profiles {
+        switch_menu.append(
+            Some(&profile.name),
+            Some(&format!("win.profile::{}", profile.id)),
+        );
+    }
+    let manage_menu: Menu = Menu::new();
+    manage_menu.append(Some(&gettext("Manage Profiles…")), Some("win.profiles"));
+    let menu: Menu = Menu::new();
+    menu.append_section(None, &switch_menu);
+    menu.append_section(None, &manage_menu);
+    return menu;
+}
+
+/// The game window and everything needed to play in it
+pub struct GameWindow {
+    pub window: ApplicationWindow,
+    window_title: WindowTitle,
+    toast_overlay: ToastOverlay,
+    grid_box: Box,
+    grid: RefCell<Grid>,
+    keyboard_box: Box,
+    keyboard_rows: RefCell<Vec<Box>>,
+    keyboard_layout: Cell<KeyboardLayout>,
+    new_game: Button,
+    options: RefCell<GameOptions>,
+    lists: RefCell<WordLists>,
+    game: RefCell<Game>,
+    stats: RefCell<Stats>,
+    /// Definitions of answers, shared with the history view
+    dictionary: RefCell<Rc<Dictionary>>,
+    /// The achievements unlocked so far
+    unlocks: RefCell<Unlocks>,
+    /// Achievements unlocked by the game that just finished, to announce once it's shown
+    new_achievements: RefCell<Vec<&'static Achievement>>,
+    /// Whether a scored row is still being revealed
+    revealing: Cell<bool>,
+    /// Whether preferences that apply from the next game have changed
+    preferences_changed: Cell<bool>,
+    /// The size of the space below the header, in pixels
+    size: Cell<(i32, i32)>,
+    /// The step of the tutorial being shown, if it's running
+    tutorial_step: Cell<Option<usize>>,
+    /// The callout of the tutorial step being shown
+    callout: RefCell<Option<Popover>>,
+    /// Shows the time in timed games and speed runs
+    clock_label: Label,
+    /// Times the current timed game, or the whole speed run
+    clock: Cell<Clock>,
+    /// The speed run being played, if there is one
+    speed_run: RefCell<Option<SpeedRun>>,
+    /// The survival run being played, if there is one
+    survival: Cell<Option<SurvivalRun>>,
+    /// Picks the mode of the next game from the menu
+    mode_action: SimpleAction,
+    /// Shows the active profile, with a menu to switch to another
+    profile_button: MenuButton,
+    profile_content: ButtonContent,
+    /// Switches to the profile with the id it's given
+    profile_action: SimpleAction,
+    /// The game that was being played when the tutorial started
+    interrupted_game: RefCell<Option<Game>>,
+    /// The race on the local network the player is in, if they're in one
+    race: RefCell<Option<RaceSession>>,
+    /// Holds the panel showing how the race stands
+    race_bin: Bin,
+    /// The date of the past daily puzzle on the board, if it was picked from the archive
+    archive: Cell<Option<Date>>,
+}
+
+impl GameWindow {
+    /// Build the window and start a game with the given options
+    pub fn new(app: &Application, options: GameOptions, lists: WordLists) -> Rc<GameWindow> {
+        let window: ApplicationWindow = ApplicationWindow::builder()
+            .application(app)
+            .default_width(800)
+            .default_height(810)
+            .width_request(360)
+            .height_request(480)
+            .title("Rustle!")
+            .build();
+        theme::apply_palette(&window, theme::load_palette(), theme::load_patterns());
+
+        let outermost_box: Box = Box::new(Vertical, 0);
+
+        let header: HeaderBar = HeaderBar::builder().show_start_title_buttons(true).build();
+        let window_title: WindowTitle = WindowTitle::new("Rustle!", "");
+        header.set_title_widget(Some(&window_title));
+        let seed_button: Button = Button::from_icon_name("document-edit-symbolic");
+        seed_button.set_tooltip_text(Some(&gettext("Play a Seed")));
+        header.pack_start(&seed_button);
+        let clock_label: Label = Label::new(None);
+        clock_label.add_css_class("heading");
+        clock_label.add_css_class("numeric");
+        clock_label.set_visible(false);
+        header.pack_start(&clock_label);
+
+        let game_menu: Menu = Menu::new();
+        game_menu.append(Some(&gettext("New Game")), Some("win.new-game"));
+        game_menu.append(Some(&gettext("Statistics")), Some("win.statistics"));
+        game_menu.append(Some(&gettext("History")), Some("win.history"));
+        game_menu.append(Some(&gettext("Achievements")), Some("win.achievements"));
+        game_menu.append(Some(&gettext("Daily Archive…")), Some("win.archive"));
+        game_menu.append(Some(&gettext("LAN Race…")), Some("win.race"));
+        game_menu.append(Some(&gettext("Export Data…")), Some("win.export"));
+        game_menu.append(Some(&gettext("Import Data…")), Some("win.import"));
+        let mode_menu: Menu = Menu::new();
+        mode_menu.append(Some(&gettext("Practice")), Some("win.mode::practice"));
+        mode_menu.append(Some(&gettext("Daily")), Some("win.mode::daily"));
+        mode_menu.append(Some(&gettext("Timed")), Some("win.mode::timed"));
+        mode_menu.append(Some(&gettext("Speed Run")), Some("win.mode::speedrun"));
+        mode_menu.append(Some(&gettext("Survival")), Some("win.mode::survival"));
+        let app_menu: Menu = Menu::new();
+        app_menu.append(Some(&gettext("Preferences")), Some("win.preferences"));
+        app_menu.append(
+            Some(&gettext("Keyboard Shortcuts")),
+            Some("win.show-help-overlay"),
+        );
+        app_menu.append(Some(&gettext("How to Play")), Some("win.how-to-play"));
+        app_menu.append(Some(&gettext("Tutorial")), Some("win.tutorial"));
+        app_menu.append(Some(&gettext("About Rustle!")), Some("app.about"));
+        let menu: Menu = Menu::new();
+        menu.append_section(None, &game_menu);
+        menu.append_section(None, &mode_menu);
+        menu.append_section(None, &app_menu);
+        let menu_button: MenuButton = MenuButton::builder()
+            .icon_name("open-menu-symbolic")
+            .menu_model(&menu)
+            .tooltip_text(gettext("Main Menu"))
+            .primary(true)
+            .build();
+        header.pack_end(&menu_button);
+        let profiles: Profiles = Profiles::load();
+        let profile_content: ButtonContent = ButtonContent::builder()
+            .icon_name("avatar-default-symbolic")
+            .label(&profiles.active().name)
+            .build();
+        let profile_button: MenuButton = MenuButton::builder()
+            .child(&profile_content)
+            .menu_model(&profile_menu(&profiles))
+            .tooltip_text(gettext("Switch Profile"))
+            .build();
+        header.pack_end(&profile_button);
+        outermost_box.append(&header);
+
+        let outer_box: Box = Box::new(Horizontal, 6);
+        outer_box.set_hexpand(false);
+        outer_box.set_halign(Align::Center);
+
+        let main_box: Box = Box::new(Vertical, 6);
+        main_box.set_vexpand(true);
+        main_box.set_hexpand(false);
+        main_box.set_margin_top(10);
+
+        let title: Label = Label::builder().build();
+        title.set_text("Rustle!");
+        title.add_css_class("title_text");
+        title.set_margin_bottom(10);
+        main_box.append(&title);
+
+        // The board and keyboard are stacked, or side by side in wide windows
+        let play_box: Box = Box::new(Vertical, 0);
+        play_box.set_halign(Align::Center);
+        main_box.append(&play_box);
+
+        let grid_box: Box = Box::new(Horizontal, 6);
+        grid_box.set_halign(Align::Center);
+        grid_box.set_valign(Align::Center);
+
+        let grid: Grid = build_grid(options.word_length, options.max_guesses);
+        grid_box.append(&grid);
+        grid_box.update_property(&[Property::Label(&gettext("Board"))]);
+        play_box.append(&grid_box);
+
+        let keyboard_bin: Bin = Bin::builder().build();
+        keyboard_bin.set_margin_top(40);
+        keyboard_bin.set_valign(Align::Center);
+        keyboard_bin.set_widget_name("keyboard");
+        play_box.append(&keyboard_bin);
+
+        let kb_box: Box = Box::new(Vertical, 8);
+        let keyboard_layout: KeyboardLayout =
+            KeyboardLayout::from_name(&AppSettings::load().keyboard_layout())
+                .unwrap_or(KeyboardLayout::Qwerty);
+        let keyboard_rows: Vec<Box> = build_keyboard(keyboard_layout);
+        for keyboard_row in &keyboard_rows {
+            kb_box.append(keyboard_row);
+        }
+        keyboard_bin.set_child(Some(&kb_box));
+        keyboard_bin.update_property(&[Property::Label(&gettext("Keyboard"))]);
+
+        let new_game: Button = Button::builder().label(gettext("Play Again")).build();
+        new_game.add_css_class("new_game");
+        new_game.set_visible(false);
+        new_game.set_size_request(200, -1);
+        new_game.set_hexpand(false);
+        new_game.set_halign(Align::Center);
+        main_box.set_homogeneous(false);
+        main_box.append(&new_game);
+
+        outer_box.append(&main_box);
+
+        // Shows the other players' boards during a race
+        let race_bin: Bin = Bin::builder().visible(false).build();
+        outer_box.append(&race_bin);
+
+        // The drawing area fills the space below the header and reports its size, so the tiles
+        // and keys can be scaled to fit. The game sits over it without adding to the window's
+        // minimum size.
+        let size_watcher: DrawingArea = DrawingArea::new();
+        size_watcher.set_vexpand(true);
+        let content_overlay: Overlay = Overlay::new();
+        content_overlay.set_child(Some(&size_watcher));
+        content_overlay.add_overlay(&outer_box);
+        outermost_box.append(&content_overlay);
+
+        let toast_overlay: ToastOverlay = ToastOverlay::new();
+        toast_overlay.set_child(Some(&outermost_box));
+        window.set_content(Some(&toast_overlay));
+
+        let narrow: Breakpoint = Breakpoint::new(
+            BreakpointCondition::parse(&format!("max-width: {}px", NARROW_WIDTH - 1)).unwrap(),
+        );
+        narrow.add_setter(&title, "visible", Some(&false.to_value()));
+        narrow.add_setter(&main_box, "margin-top", Some(&4.to_value()));
+        narrow.add_setter(&keyboard_bin, "margin-top", Some(&12.to_value()));
+        window.add_breakpoint(narrow);
+
+        let wide: Breakpoint = Breakpoint::new(
+            BreakpointCondition::parse(&format!("min-width: {}px", WIDE_WIDTH)).unwrap(),
+        );
+        wide.add_setter(&play_box, "orientation", Some(&Horizontal.to_value()));
+        wide.add_setter(&keyboard_bin, "margin-top", Some(&0.to_value()));
+        wide.add_setter(&keyboard_bin, "margin-start", Some(&40.to_value()));
+        window.add_breakpoint(wide);
+
+        let mut options: GameOptions = options;
+        let recent: Vec<String> = History::load().recent_answers(options.avoid_recent);
+        let seed: u64 = options.take_seed(Date::today(), &lists, &recent);
+        let game: Game = Game::new(&options, &lists, seed);
+        let speed_run: Option<SpeedRun> =
+            (options.mode == GameMode::SpeedRun).then(|| SpeedRun::new(&options));
+        let survival: Option<SurvivalRun> =
+            (options.mode == GameMode::Survival).then(|| SurvivalRun::new(&options));
+        let mode_action: SimpleAction = SimpleAction::new_stateful(
+            "mode",
+            Some(VariantTy::STRING),
+            &options.mode.name().to_variant(),
+        );
+        window.add_action(&mode_action);
+        let profile_action: SimpleAction = SimpleAction::new_stateful(
+            "profile",
+            Some(VariantTy::STRING),
+            &profiles.active.to_variant(),
+        );
+        window.add_action(&profile_action);
+
+        let game_window: Rc<GameWindow> = Rc::new(GameWindow {
+            window: window.clone(),
+            window_title,
+            toast_overlay,
+            grid_box,
+            grid: RefCell::new(grid),
+            keyboard_box: kb_box,
+            keyboard_rows: RefCell::new(keyboard_rows),
+            keyboard_layout: Cell::new(keyboard_layout),
+            new_game: new_game.clone(),
+            options: RefCell::new(options),
+            lists: RefCell::new(lists),
+            game: RefCell::new(game),
+            stats: RefCell::new(Stats::load()),
+            dictionary: RefCell::new(Rc::new(Dictionary::default())),
+            unlocks: RefCell::new(Unlocks::load()),
+            new_achievements: RefCell::new(Vec::new()),
+            revealing: Cell::new(false),
+            preferences_changed: Cell::new(false),
+            size: Cell::new((0, 0)),
+            tutorial_step: Cell::new(None),
+            callout: RefCell::new(None),
+            interrupted_game: RefCell::new(None),
+            clock_label,
+            clock: Cell::new(Clock::default()),
+            speed_run: RefCell::new(speed_run),
+            survival: Cell::new(survival),
+            mode_action,
+            profile_button,
+            profile_content,
+            profile_action,
+            race: RefCell::new(None),
+            race_bin,
+            archive: Cell::new(None),
+        });
+
+        let this: Weak<GameWindow> = Rc::downgrade(&game_window);
+        size_watcher.connect_resize(move |_, width: i32, height: i32| {
+            if let Some(this) = this.upgrade() {
+                this.size.set((width, height));
+                // Size requests can't change in the middle of laying out the window
+                let this: Weak<GameWindow> = Rc::downgrade(&this);
+                glib::idle_add_local_once(move || {
+                    if let Some(this) = this.upgrade() {
+                        this.scale_to_fit();
+                    }
+                });
+            }
+        });
+
+        new_game.set_action_name(Some("win.new-game"));
+        game_window.add_action("new-game", |this: &Rc<GameWindow>| {
+            let mut options_val: RefMut<GameOptions> = this.options.borrow_mut();
+            if options_val.mode == GameMode::Daily {
+                options_val.mode = GameMode::Practice;
+            }
+            drop(options_val);
+            this.start_game();
+        });
+        let this: Weak<GameWindow> = Rc::downgrade(&game_window);
+        game_window
+            .mode_action
+            .connect_activate(move |_, target: Option<&Variant>| {
+                let Some(this) = this.upgrade() else {
+                    return;
+                };
+                let Some(mode) = target
+                    .and_then(|target| target.str())
+                    .and_then(GameMode::from_name)
+                else {
+                    return;
+                };
+                let mut options_val: RefMut<GameOptions> = this.options.borrow_mut();
+                options_val.mode = mode;
+                options_val.seed = None;
+                drop(options_val);
+                this.start_game();
+            });
+        let this: Weak<GameWindow> = Rc::downgrade(&game_window);
+        game_window
+            .profile_action
+            .connect_activate(move |_, target: Option<&Variant>| {
+                if let Some(this) = this.upgrade()
+                    && let Some(id) = target.and_then(|target| target.str())
+                {
+                    this.switch_profile(id);
+                }
+            });
+        game_window.add_action("profiles", |this: &Rc<GameWindow>| {
+            let this_2: Weak<GameWindow> = Rc::downgrade(this);
+            show_profiles(&this.window, move || {
+                if let Some(this) = this_2.upgrade() {
+                    this.update_profiles();
+                }
+            });
+        });
+        game_window.add_action("statistics", |this: &Rc<GameWindow>| this.show_statistics());
+        game_window.add_action("history", |this: &Rc<GameWindow>| {
+            show_history(&this.window, this.dictionary.borrow().clone())
+        });
+        game_window.add_action("achievements", |this: &Rc<GameWindow>| {
+            show_achievements(&this.window, &this.unlocks.borrow())
+        });
+        game_window.add_action("archive", |this: &Rc<GameWindow>| this.show_archive());
+        game_window.add_action("race", |this: &Rc<GameWindow>| this.show_race_dialog());
+        game_window.add_action("race-start", |this: &Rc<GameWindow>| {
+            if let Some(host) = this
+                .race
+                .borrow()
+                .as_ref()
+                .and_then(|race| race.host.as_ref())
+            {
+                host.start_race();
+            }
+        });
+        game_window.add_action("race-leave", |this: &Rc<GameWindow>| {
+            if this.leave_race() {
+                this.start_game();
+            }
+        });
+        game_window.add_action("export", |this: &Rc<GameWindow>| this.export_data());
+        game_window.add_action("import", |this: &Rc<GameWindow>| this.import_data());
+        game_window.add_action("preferences", |this: &Rc<GameWindow>| {
+            let this_2: Weak<GameWindow> = Rc::downgrade(this);
+            show_preferences(&this.window, move || {
+                if let Some(this) = this_2.upgrade() {
+                    this.preferences_changed.set(true);
+                }
+            });
+        });
+        game_window.add_action("show-help-overlay", |this: &Rc<GameWindow>| {
+            build_shortcuts_window(&this.window).present();
+        });
+        game_window.add_action("how-to-play", |this: &Rc<GameWindow>| {
+            show_how_to_play(&this.window);
+        });
+        game_window.add_action("tutorial", |this: &Rc<GameWindow>| this.start_tutorial());
+
+        let this: Weak<GameWindow> = Rc::downgrade(&game_window);
+        let click: GestureClick = GestureClick::new();
+        click.connect_pressed(move |_, _, x: f64, y: f64| {
+            if let Some(this) = this.upgrade() {
+                this.tile_clicked(x, y);
+            }
+        });
+        game_window.grid_box.add_controller(click);
+
+        let this: Weak<GameWindow> = Rc::downgrade(&game_window);
+        seed_button.connect_clicked(move |_| {
+            if let Some(this) = this.upgrade() {
+                this.show_seed_dialog();
+            }
+        });
+
+        let this: Weak<GameWindow> = Rc::downgrade(&game_window);
+        let k: EventControllerKey = EventControllerKey::builder().build();
+        k.connect_key_pressed(move |_, k: Key, _, state: ModifierType| {
+            // Leave shortcuts like Ctrl+N to the application
+            if state.intersects(ModifierType::CONTROL_MASK | ModifierType::ALT_MASK) {
+                return Propagation::Proceed;
+            }
+            return match this.upgrade() {
+                Some(this) => this.key_pressed(k),
+                None => Propagation::Proceed,
+            };
+        });
+        window.add_controller(k);
+
+        // The clock is checked often enough to show tenths of a second, and the race for news
+        let this: Weak<GameWindow> = Rc::downgrade(&game_window);
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            return match this.upgrade() {
+                Some(this) => {
+                    this.poll_race();
+                    this.tick_clock();
+                    ControlFlow::Continue
+                }
+                None => ControlFlow::Break,
+            };
+        });
+        let this: Weak<GameWindow> = Rc::downgrade(&game_window);
+        window.connect_is_active_notify(move |_| {
+            if let Some(this) = this.upgrade() {
+                this.update_clock();
+            }
+        });
+
+        game_window.load_dictionary();
+        game_window.game_started();
+        window.present();
+        if !AppSettings::load().tutorial_done() {
+            // Wait for the window to be laid out, so the first callout has somewhere to point
+            let this: Weak<GameWindow> = Rc::downgrade(&game_window);
+            glib::idle_add_local_once(move || {
+                if let Some(this) = this.upgrade() {
+                    this.start_tutorial();
+                }
+            });
+        }
+        return game_window;
+    }
+
+    /// Add an action to the window that calls a method on the game window
+    fn add_action(self: &Rc<Self>, name: &str, f: impl Fn(&Rc<GameWindow>) + 'static) -> () {
+        let this: Weak<GameWindow> = Rc::downgrade(self);
+        let action: SimpleAction = SimpleAction::new(name, None);
+        action.connect_activate(move |_, _| {
+            if let Some(this) = this.upgrade() {
+                f(&this);
+            }
+        });
+        self.window.add_action(&action);
+    }
+
+    /// Replace the options and word lists, then start a new game with them
+    pub fn set_options(&self, options: GameOptions, lists: WordLists) -> () {
+        *self.options.borrow_mut() = options;
+        *self.lists.borrow_mut() = lists;
+        self.start_game();
+    }
+
+    /// Start a new game with the current options, leaving the race if the player is in one
+    pub fn start_game(&self) -> () {
+        self.leave_race();
+        // Starting a game from the menu during the tutorial leaves it
+        if self.tutorial_step.take().is_some() {
+            self.close_callout();
+            self.interrupted_game.take();
+            AppSettings::load().set_tutorial_done(true);
+        }
+        if self.preferences_changed.replace(false) {
+            self.apply_preferences();
+        }
+        let options_val: Ref<GameOptions> = self.options.borrow();
+        self.mode_action
+            .set_state(&options_val.mode.name().to_variant());
+        *self.speed_run.borrow_mut() =
+            (options_val.mode == GameMode::SpeedRun).then(|| SpeedRun::new(&options_val));
+        self.survival
+            .set((options_val.mode == GameMode::Survival).then(|| SurvivalRun::new(&options_val)));
+        drop(options_val);
+        self.clock.set(Clock::default());
+        self.next_word();
+    }
+
+    /// Put the next word on the board. A speed run or survival run carries on with it, keeping
+    /// the clock and bonus guesses.
+    fn next_word(&self) -> () {
+        let mut options_val: RefMut<GameOptions> = self.options.borrow_mut();
+        let recent: Vec<String> = History::load().recent_answers(options_val.avoid_recent);
+        let seed: u64 = options_val.take_seed(Date::today(), &self.lists.borrow(), &recent);
+        let options: GameOptions = match self.survival.get() {
+            Some(run) => run.word_options(&options_val),
+            None => options_val.clone(),
+        };
+        drop(options_val);
+        let game: Game = Game::new(&options, &self.lists.borrow(), seed);
+        self.archive.set(None);
+        self.show_game(game);
+    }
+
+    /// Run the clock only while a timed game is being played, and the window is focused if the
+    /// player wants it paused otherwise
+    fn update_clock(&self) -> () {
+        let game_val: Ref<Game> = self.game.borrow();
+        let running: bool = game_val.mode.timed()
+            && !game_val.locked
+            && self.tutorial_step.get().is_none()
+            && (self.window.is_active() || !AppSettings::load().pause_unfocused());
+        drop(game_val);
+        let mut clock: Clock = self.clock.get();
+        if running {
+            clock.resume();
+        } else {
+            clock.pause();
+        }
+        self.clock.set(clock);
+        self.show_clock();
+    }
+
+    /// Get the time left to find the word in a timed game
+    fn time_left(&self) -> Duration {
+        let time_limit: Duration = Duration::from_secs(self.options.borrow().time_limit);
+        return time_limit.saturating_sub(self.clock.get().elapsed());
+    }
+
+    /// Show the clock in the header: the time left in a timed game, or the time so far in a
+    /// speed run. It's hidden in other games.
+    fn show_clock(&self) -> () {
+        let game_val: Ref<Game> = self.game.borrow();
+        let visible: bool = game_val.mode.timed() && self.tutorial_step.get().is_none();
+        self.clock_label.set_visible(visible);
+        if !visible {
+            return;
+        }
+        let speed_run: Ref<Option<SpeedRun>> = self.speed_run.borrow();
+        match speed_run.as_ref() {
+            Some(run) if game_val.mode == GameMode::SpeedRun => {
+                self.clock_label.set_text(&fill(
+                    &gettext("{} · Word {} of {}"),
+                    &[
+                        &format_clock(self.clock.get().elapsed(), true),
+                        &run.current_word().to_string(),
+                        &run.words.to_string(),
+                    ],
+                ));
+                self.clock_label.remove_css_class("error");
+            }
+            _ => {
+                // Count down in whole seconds, reaching zero just as time runs out
+                let time_left: Duration = self.time_left();
+                let shown: Duration = Duration::from_secs(time_left.as_secs_f64().ceil() as u64);
+                self.clock_label.set_text(&format_clock(shown, false));
+                if time_left < LOW_TIME {
+                    self.clock_label.add_css_class("error");
+                } else {
+                    self.clock_label.remove_css_class("error");
+                }
+            }
+        }
+    }
+
+    /// Update the clock shown, and end a timed game once its time has run out
+    fn tick_clock(self: &Rc<Self>) -> () {
+        if !self.clock.get().running() {
+            return;
+        }
+        self.show_clock();
+        if self.game.borrow().mode == GameMode::Timed && self.time_left().is_zero() {
+            self.time_up();
+        }
+    }
+
+    /// End a timed game that ran out of time
+    fn time_up(self: &Rc<Self>) -> () {
+        self.game.borrow_mut().time_out();
+        // A row still turning over would otherwise end the game a second time
+        self.revealing.set(false);
+        self.record_game();
+        self.check_achievements();
+        self.update_clock();
+        self.refresh();
+        self.window
+            .announce(&gettext("Time's up!"), AccessibleAnnouncementPriority::High);
+        self.finish_game();
+    }
+
+    /// Stop the clock of a game that just finished, timing the word if it's part of a speed run
+    fn stop_clock(&self) -> () {
+        self.update_clock();
+        let game_val: Ref<Game> = self.game.borrow();
+        if game_val.mode == GameMode::SpeedRun
+            && game_val.won
+            && let Some(run) = self.speed_run.borrow_mut().as_mut()
+        {
+            run.solve(self.clock.get().elapsed());
+        }
+    }
+
+    /// Put a game on the board, rebuilding the board if it's a different size
+    fn show_game(&self, game: Game) -> () {
+        let old_game: Ref<Game> = self.game.borrow();
+        let resized: bool =
+            old_game.word_length != game.word_length || old_game.max_guesses != game.max_guesses;
+        drop(old_game);
+        if resized {
+            let grid: Grid = build_grid(game.word_length, game.max_guesses);
+            self.grid_box.remove(&*self.grid.borrow());
+            self.grid_box.append(&grid);
+            *self.grid.borrow_mut() = grid;
+        }
+
+        *self.game.borrow_mut() = game;
+        if resized {
+            self.scale_to_fit();
+        }
+        self.revealing.set(false);
+        self.new_game.set_visible(false);
+        self.game_started();
+        self.window.grab_focus();
+    }
+
+    /// Scale the tiles and keys to fill the window, following the layout the breakpoints pick
+    fn scale_to_fit(&self) -> () {
+        let (mut width, height): (i32, i32) = self.size.get();
+        if self.race_bin.is_visible() {
+            width -= RACE_PANEL_WIDTH + 12;
+        }
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let narrow: bool = width < NARROW_WIDTH;
+        let wide: bool = width >= WIDE_WIDTH;
+        let margin: i32 = if narrow { 8 } else { 24 };
+        let title_height: i32 = if narrow { 0 } else { 60 };
+        let new_game_height: i32 = 48;
+
+        let keyboard_rows: Ref<Vec<Box>> = self.keyboard_rows.borrow();
+        let longest_row: i32 = self
+            .keyboard_layout
+            .get()
+            .rows()
+            .iter()
+            .map(|row| row.len() as i32)
+            .max()
+            .unwrap_or(10);
+        let keyboard_width: i32 = if wide { width / 2 } else { width } - 2 * margin - 16;
+        let key_width: i32 = ((keyboard_width - (longest_row - 1) * KEY_SPACING) / longest_row)
+            .clamp(KEY_WIDTH_RANGE.0, KEY_WIDTH_RANGE.1);
+        let key_height: i32 = key_width * 3 / 2;
+        resize_keyboard(&keyboard_rows, key_width, key_height);
+
+        let keyboard_margin: i32 = if narrow { 12 } else { 40 };
+        let keyboard_height: i32 = 3 * key_height + 2 * 8 + 16 + keyboard_margin;
+        let (board_width, board_height): (i32, i32) = if wide {
+            (
+                width / 2 - 2 * margin,
+                height - title_height - new_game_height - 2 * margin,
+            )
+        } else {
+            (
+                width - 2 * margin,
+                height - title_height - keyboard_height - new_game_height - 2 * margin,
+            )
+        };
+        let game_val: Ref<Game> = self.game.borrow();
+        let columns: i32 = game_val.word_length as i32;
+        let rows: i32 = game_val.max_guesses as i32;
+        let tile_size: i32 = ((board_width - (columns - 1) * TILE_SPACING) / columns)
+            .min((board_height - (rows - 1) * TILE_SPACING) / rows)
+            .clamp(TILE_SIZE_RANGE.0, TILE_SIZE_RANGE.1);
+        resize_grid(
+            &self.grid.borrow(),
+            game_val.word_length,
+            game_val.max_guesses,
+            tile_size,
+        );
+    }
+
+    /// Use the game settings and keyboard layout from the preferences
+    fn apply_preferences(&self) -> () {
+        let settings: AppSettings = AppSettings::load();
+        let mut options: GameOptions = settings.game_options();
+        let options_val: Ref<GameOptions> = self.options.borrow();
+        options.mode = options_val.mode;
+        options.seed = options_val.seed;
+        drop(options_val);
+        match options.validate().and_then(|_| WordLists::load(&options)) {
+            Ok(lists) => {
+                *self.options.borrow_mut() = options;
+                *self.lists.borrow_mut() = lists;
+            }
+            Err(error) => show_error_toast(&self.toast_overlay, &options_error_message(&error)),
+        }
+        self.load_dictionary();
+
+        let layout: KeyboardLayout = KeyboardLayout::from_name(&settings.keyboard_layout())
+            .unwrap_or(KeyboardLayout::Qwerty);
+        if layout != self.keyboard_layout.replace(layout) {
+            for keyboard_row in self.keyboard_rows.borrow().iter() {
+                self.keyboard_box.remove(keyboard_row);
+            }
+            let keyboard_rows: Vec<Box> = build_keyboard(layout);
+            for keyboard_row in &keyboard_rows {
+                self.keyboard_box.append(keyboard_row);
+            }
+            *self.keyboard_rows.borrow_mut() = keyboard_rows;
+            self.scale_to_fit();
+        }
+    }
+
+    /// Load the dictionary from the preferences, telling the player if it can't be read
+    fn load_dictionary(&self) -> () {
+        match Dictionary::load(&AppSettings::load().dictionary()) {
+            Ok(dictionary) => *self.dictionary.borrow_mut() = Rc::new(dictionary),
+            Err(message) => {
+                *self.dictionary.borrow_mut() = Rc::new(Dictionary::default());
+                show_error_toast(&self.toast_overlay, &message);
+            }
+        }
+    }
+
+    /// Show the state of a game that just started
+    fn game_started(&self) -> () {
+        let game_val: Ref<Game> = self.game.borrow();
+        self.window_title
+            .set_subtitle(&game_subtitle(&game_val, self.archive.get()));
+        if game_val.mode == GameMode::Daily
+            && self.archive.get().is_none()
+            && self.stats.borrow().played_daily(Date::today())
+        {
+            let toast: Toast = Toast::new(&gettext(
+                "You already played today's puzzle, this game won't count",
+            ));
+            toast.set_timeout(4);
+            self.toast_overlay.add_toast(toast);
+        }
+        drop(game_val);
+        self.refresh();
+        self.update_clock();
+    }
+
+    /// Redraw the board and keyboard
+    fn refresh(&self) -> () {
+        let game_val: Ref<Game> = self.game.borrow();
+        update_board(
+            &game_val.board_chars,
+            &game_val.board_colors,
+            game_val.guess,
+            game_val.cur_x,
+            &self.grid.borrow(),
+        );
+        update_keyboard(
+            &game_val.letter_states,
+            &self.keyboard_rows.borrow(),
+            self.keyboard_layout.get(),
+        );
+    }
+
+    /// Handle a key press on the window
+    fn key_pressed(self: &Rc<Self>, k: Key) -> Propagation {
+        if self.game.borrow().locked
+            || self.tutorial_step.get().is_some()
+            || self.revealing.get()
+            || self.window.visible_dialog().is_some()
+        {
+            return Propagation::Proceed;
+        }
+
+        let moved: Option<bool> = {
+            let mut game_val: RefMut<Game> = self.game.borrow_mut();
+            let cur_x: usize = game_val.cur_x;
+            match k {
+                Key::BackSpace => Some(game_val.backspace()),
+                Key::Delete | Key::KP_Delete => Some(game_val.delete()),
+                Key::Left | Key::KP_Left => Some(game_val.move_cursor(cur_x.saturating_sub(1))),
+                Key::Right | Key::KP_Right => Some(game_val.move_cursor(cur_x + 1)),
+                Key::Home | Key::KP_Home => Some(game_val.move_cursor(0)),
+                Key::End | Key::KP_End => {
+                    let row_end: usize = game_val.row_end();
+                    Some(game_val.move_cursor(row_end))
+                }
+                _ => None,
+            }
+        };
+        if let Some(moved) = moved {
+            if moved {
+                self.refresh();
+            }
+            return Propagation::Stop;
+        } else if k == Key::Return || k == Key::KP_Enter {
+            let result: GuessResult = self.game.borrow_mut().submit(&self.lists.borrow().words);
+            match result {
+                GuessResult::Incomplete => self.reject_guess(&gettext("Not enough letters!")),
+                GuessResult::InvalidWord => self.reject_guess(&gettext("Invalid Word!")),
+                GuessResult::HardModeViolation(violation) => {
+                    self.reject_guess(&hard_mode_message(violation))
+                }
+                GuessResult::Continue | GuessResult::Won | GuessResult::Lost => self.reveal_guess(),
+            }
+            return Propagation::Stop;
+        } else if let Some(unicode) = k.to_unicode() {
+            let chr: String = unicode.to_lowercase().collect();
+            if chr.chars().count() == 1
+                && self
+                    .game
+                    .borrow_mut()
+                    .type_letter(chr.chars().next().unwrap())
+            {
+                self.refresh();
+                let game_val: Ref<Game> = self.game.borrow();
+                animation::pop(&tile_at(
+                    &self.grid.borrow(),
+                    game_val.cur_x - 1,
+                    game_val.guess,
+                ));
+                return Propagation::Stop;
+            }
+        }
+        return Propagation::Proceed;
+    }
+
+    /// Select the tile under a click on the board, if it's in the current row
+    fn tile_clicked(&self, x: f64, y: f64) -> () {
+        if self.revealing.get() || self.tutorial_step.get().is_some() {
+            return;
+        }
+        let grid: Ref<Grid> = self.grid.borrow();
+        let mut widget: Option<Widget> = self.grid_box.pick(x, y, PickFlags::DEFAULT);
+        while let Some(child) = widget {
+            if child.parent().as_ref() == Some(grid.upcast_ref::<Widget>()) {
+                let (column, row, _, _) = grid.query_child(&child);
+                let mut game_val: RefMut<Game> = self.game.borrow_mut();
+                if row as usize == game_val.guess && game_val.move_cursor(column as usize) {
+                    drop(game_val);
+                    drop(grid);
+                    self.refresh();
+                }
+                return;
+            }
+            widget = child.parent();
+        }
+    }
+
+    /// Tell the player why a guess wasn't accepted and shake the row
+    fn reject_guess(&self, message: &str) -> () {
+        show_error_toast(&self.toast_overlay, message);
+        let game_val: Ref<Game> = self.game.borrow();
+        animation::shake(&row_tiles(
+            &self.grid.borrow(),
+            game_val.guess,
+            game_val.word_length,
+        ));
+    }
+
+    /// Flip over the row that was just scored, then update the keyboard and end the game if it's over
+    fn reveal_guess(self: &Rc<Self>) -> () {
+        let game_val: Ref<Game> = self.game.borrow();
+        let grid: Ref<Grid> = self.grid.borrow();
+        update_board(
+            &game_val.board_chars,
+            &game_val.board_colors,
+            game_val.guess,
+            game_val.cur_x,
+            &grid,
+        );
+        let tiles: Vec<Label> = row_tiles(&grid, game_val.guesses_used() - 1, game_val.word_length);
+        drop(grid);
+        let finished: bool = game_val.locked;
+        let row: usize = game_val.guesses_used();
+        let colors: Vec<usize> = game_val.board_colors[row - 1].clone();
+        drop(game_val);
+        let race_round: Option<u32> = self.race_round();
+        if let Some(round) = race_round
+            && let Some(race) = self.race.borrow().as_ref()
+        {
+            race.client.send_score(round, row, &colors);
+        }
+        // Record the game straight away, so closing the window mid-animation doesn't lose it.
+        // Races aren't recorded.
+        if finished && self.tutorial_step.get().is_none() && race_round.is_none() {
+            self.stop_clock();
+            self.record_game();
+            self.record_survival();
+            self.check_achievements();
+        }
+
+        self.revealing.set(true);
+        let this: Weak<GameWindow> = Rc::downgrade(self);
+        let bounce_tiles: Vec<Label> = tiles.clone();
+        animation::flip_row(&tiles, move || {
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            // A new game may have been started while the row was turning over
+            if !this.revealing.replace(false) {
+                return;
+            }
+            this.refresh();
+            let game_val: Ref<Game> = this.game.borrow();
+            let row: usize = game_val.guesses_used() - 1;
+            this.window.announce(
+                &guess_announcement(&game_val.board_chars[row], &game_val.board_colors[row], row),
+                AccessibleAnnouncementPriority::Medium,
+            );
+            let won: bool = game_val.won;
+            drop(game_val);
+            if won {
+                this.revealing.set(true);
+                let this_2: Weak<GameWindow> = Rc::downgrade(&this);
+                animation::bounce(&bounce_tiles, move || {
+                    if let Some(this) = this_2.upgrade()
+                        && this.revealing.replace(false)
+                    {
+                        this.guess_revealed();
+                    }
+                });
+            } else {
+                this.guess_revealed();
+            }
+        });
+    }
+
+    /// Carry on once a scored row has been revealed
+    fn guess_revealed(self: &Rc<Self>) -> () {
+        if self.tutorial_step.get().is_some() {
+            self.show_callout();
+        } else if self.game.borrow().locked {
+            self.finish_game();
+        }
+    }
+
+    /// Record a finished game in the stats and history, or with the other archive games if it
+    /// was picked from the archive
+    fn record_game(&self) -> () {
+        let game_val: Ref<Game> = self.game.borrow();
+        if let Some(date) = self.archive.get() {
+            if let Err(error) = Archive::append(&GameRecord::from_game(&game_val, date)) {
+                eprintln!("Failed to save the archive game: {}", error);
+            }
+            return;
+        }
+        let mut stats_val: RefMut<Stats> = self.stats.borrow_mut();
+        stats_val.record(&game_val, Date::today());
+        if let Err(error) = stats_val.save() {
+            eprintln!("Failed to save stats: {}", error);
+        }
+        if let Err(error) = History::append(&GameRecord::from_game(&game_val, Date::today())) {
+            eprintln!("Failed to save history: {}", error);
+        }
+    }
+
+    /// Carry a survival run on past a solved word, or put it on the leaderboard if the word
+    /// wasn't solved
+    fn record_survival(&self) -> () {
+        let Some(mut run) = self.survival.get() else {
+            return;
+        };
+        let game_val: Ref<Game> = self.game.borrow();
+        if game_val.mode != GameMode::Survival {
+            return;
+        }
+        if game_val.won {
+            run.solve(game_val.guesses_used());
+        } else {
+            let mut stats_val: RefMut<Stats> = self.stats.borrow_mut();
+            run.place = stats_val.record_survival(run.solved, Date::today());
+            if let Err(error) = stats_val.save() {
+                eprintln!("Failed to save stats: {}", error);
+            }
+        }
+        self.survival.set(Some(run));
+    }
+
+    /// Unlock any achievements the saved history and stats have earned, to be announced once
+    /// the game is over
+    fn check_achievements(&self) -> () {
+        let mut unlocks_val: RefMut<Unlocks> = self.unlocks.borrow_mut();
+        let unlocked: Vec<&'static Achievement> =
+            unlocks_val.check(&History::load(), &self.stats.borrow(), Date::today());
+        if unlocked.is_empty() {
+            return;
+        }
+        if let Err(error) = unlocks_val.save() {
+            eprintln!("Failed to save achievements: {}", error);
+        }
+        self.new_achievements.borrow_mut().extend(unlocked);
+    }
+
+    /// Tell the player how a finished game went
+    fn finish_game(self: &Rc<Self>) -> () {
+        if self.race_round().is_some() {
+            self.finish_race_game();
+            return;
+        }
+        announce_achievements(&self.toast_overlay, &self.new_achievements.take());
+        match self.game.borrow().mode {
+            GameMode::SpeedRun => {
+                self.finish_speed_run_word();
+                return;
+            }
+            GameMode::Survival => {
+                self.finish_survival_word();
+                return;
+            }
+            _ => {}
+        }
+        let game_val: Ref<Game> = self.game.borrow();
+        self.new_game.set_visible(true);
+        // Focus returns here once the dialog is closed, so Enter plays again
+        self.new_game.grab_focus();
+        let mut seed: String = fill(&gettext("Seed {}"), &[&game_val.seed.to_string()]);
+        if game_val.mode == GameMode::Timed && game_val.won {
+            seed = format!(
+                "{}\n{}",
+                fill(
+                    &gettext("Time {}"),
+                    &[&format_clock(self.clock.get().elapsed(), true)]
+                ),
+                seed
+            );
+        }
+        let (message, detail): (String, String) = if game_val.won {
+            let guesses: usize = game_val.guesses_used();
+            (
+                gettext("You Win!"),
+                format!(
+                    "{}\n{}",
+                    fill(
+                        &ngettext("Guessed in {} try", "Guessed in {} tries", guesses as u32),
+                        &[&guesses.to_string()],
+                    ),
+                    seed
+                ),
+            )
+        } else {
+            (
+                if game_val.timed_out {
+                    gettext("Time's Up!")
+                } else {
+                    gettext("You Lose!")
+                },
+                format!(
+                    "{}\n{}",
+                    fill(&gettext("The word was \"{}\""), &[&game_val.answer]),
+                    seed
+                ),
+            )
+        };
+        let detail: String =
+            match definitions_text(self.dictionary.borrow().lookup(&game_val.answer)) {
+                Some(definitions) => format!(
+                    "{}\n\n{}",
+                    detail,
+                    fill(
+                        &gettext("{}:\n{}"),
+                        &[&game_val.answer.to_uppercase(), &definitions]
+                    )
+                ),
+                None => detail,
+            };
+        let share: String = share_text(
+            &game_val,
+            theme::load_palette(),
+            self.archive.get().unwrap_or(Date::today()),
+        );
+        let window: ApplicationWindow = self.window.clone();
+        let toast_overlay: ToastOverlay = self.toast_overlay.clone();
+        AlertDialog::builder()
+            .message(message)
+            .detail(detail)
+            .buttons([gettext("Close"), gettext("Copy Result")])
+            .cancel_button(0)
+            .default_button(0)
+            .build()
+            .choose(Some(&self.window), None::<&Cancellable>, move |response| {
+                if response == Ok(1) {
+                    window.clipboard().set_text(&share);
+                    toast_overlay.add_toast(Toast::new(&gettext("Result copied to the clipboard")));
+                }
+            });
+    }
+
+    /// Go straight on to the next word of a survival run after a solved one, or tell the player
+    /// how the run went once it's over
+    fn finish_survival_word(&self) -> () {
+        let Some(run) = self.survival.get() else {
+            return;
+        };
+        let game_val: Ref<Game> = self.game.borrow();
+        if game_val.won {
+            drop(game_val);
+            let toast: Toast = Toast::new(&fill(
+                &ngettext(
+                    "Solved! {} bonus guess for the next word",
+                    "Solved! {} bonus guesses for the next word",
+                    run.bonus() as u32,
+                ),
+                &[&run.bonus().to_string()],
+            ));
+            toast.set_timeout(2);
+            self.toast_overlay.add_toast(toast);
+            self.next_word();
+            return;
+        }
+
+        self.new_game.set_visible(true);
+        self.new_game.grab_focus();
+        let mut detail: String = format!(
+            "{}\n{}",
+            fill(&gettext("The word was \"{}\""), &[&game_val.answer]),
+            fill(
+                &ngettext(
+                    "You solved {} word",
+                    "You solved {} words",
+                    run.solved as u32
+                ),
+                &[&run.solved.to_string()],
+            )
+        );
+        match run.place {
+            Some(1) => detail.push_str(&format!("\n{}", gettext("New high score!"))),
+            Some(place) => detail.push_str(&format!(
+                "\n{}",
+                fill(
+                    &gettext("Number {} on the leaderboard"),
+                    &[&place.to_string()]
+                )
+            )),
+            None => {}
+        }
+        AlertDialog::builder()
+            .message(gettext("Run Over"))
+            .detail(detail)
+            .buttons([gettext("Close")])
+            .cancel_button(0)
+            .default_button(0)
+            .build()
+            .show(Some(&self.window));
+    }
+
+    /// Tell the player how a word of a speed run went, then go on to the next word or end the
+    /// run. The clock stays stopped until the next word is on the board.
+    fn finish_speed_run_word(self: &Rc<Self>) -> () {
+        let Some(run) = self.speed_run.borrow().clone() else {
+            return;
+        };
+        let game_val: Ref<Game> = self.game.borrow();
+        if game_val.won && !run.complete() {
+            let split: Duration = run.splits.last().copied().unwrap_or_default();
+            let this: Weak<GameWindow> = Rc::downgrade(self);
+            AlertDialog::builder()
+                .message(fill(
+                    &gettext("Word {} of {} Solved"),
+                    &[&run.splits.len().to_string(), &run.words.to_string()],
+                ))
+                .detail(fill(
+                    &gettext("Split {} · Total {}"),
+                    &[&format_clock(split, true), &format_clock(run.total(), true)],
+                ))
+                .buttons([gettext("Next Word")])
+                .cancel_button(0)
+                .default_button(0)
+                .build()
+                .choose(Some(&self.window), None::<&Cancellable>, move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.next_word();
+                    }
+                });
+            return;
+        }
+
+        self.new_game.set_visible(true);
+        self.new_game.grab_focus();
+        let (message, detail): (String, String) = if run.complete() {
+            let mut bests: PersonalBests = PersonalBests::load();
+            let previous: Option<Vec<Duration>> = bests.best(&run.category).map(<[_]>::to_vec);
+            let new_best: bool = bests.submit(&run);
+            if new_best && let Err(error) = bests.save() {
+                eprintln!("Failed to save personal bests: {}", error);
+            }
+            (
+                gettext("Speed Run Complete!"),
+                speed_run_summary(&run, previous.as_deref(), new_best),
+            )
+        } else {
+            (
+                gettext("Speed Run Over"),
+                format!(
+                    "{}\n{}",
+                    fill(&gettext("The word was \"{}\""), &[&game_val.answer]),
+                    fill(
+                        &gettext("Solved {} of {} words"),
+                        &[&run.splits.len().to_string(), &run.words.to_string()],
+                    )
+                ),
+            )
+        };
+        AlertDialog::builder()
+            .message(message)
+            .detail(detail)
+            .buttons([gettext("Close")])
+            .cancel_button(0)
+            .default_button(0)
+            .build()
+            .show(Some(&self.window));
+    }
+
+    /// Show the calendar of past daily puzzles
+    fn show_archive(self: &Rc<Self>) -> () {
+        let this: Weak<GameWindow> = Rc::downgrade(self);
+        show_archive(&self.window, move |date: Date| {
+            if let Some(this) = this.upgrade() {
+                this.play_archive(date);
+            }
+        });
+    }
+
+    /// Play a past daily puzzle from the archive. It's played like the daily puzzle, but kept
+    /// out of the stats and history.
+    fn play_archive(&self, date: Date) -> () {
+        self.leave_race();
+        // Picking a puzzle during the tutorial ends it, without going back to the game before
+        if self.tutorial_step.take().is_some() {
+            self.close_callout();
+            self.interrupted_game.take();
+            AppSettings::load().set_tutorial_done(true);
+        }
+        if self.preferences_changed.replace(false) {
+            self.apply_preferences();
+        }
+        let options: GameOptions = GameOptions {
+            mode: GameMode::Daily,
+            seed: None,
+            ..self.options.borrow().clone()
+        };
+        let game: Game = Game::new(&options, &self.lists.borrow(), daily_seed(date));
+        *self.speed_run.borrow_mut() = None;
+        self.survival.set(None);
+        self.clock.set(Clock::default());
+        self.archive.set(Some(date));
+        self.show_game(game);
+    }
+
+    /// Show the dialog for hosting or joining a race on the local network
+    fn show_race_dialog(self: &Rc<Self>) -> () {
+        let this: Weak<GameWindow> = Rc::downgrade(self);
+        let this_2: Weak<GameWindow> = Rc::downgrade(self);
+        show_race_dialog(
+            &self.window,
+            move |name: String, port: u16| {
+                if let Some(this) = this.upgrade() {
+                    this.host_race(&name, port);
+                }
+            },
+            move |name: String, address: String| {
+                if let Some(this) = this_2.upgrade() {
+                    this.join_race(name, address);
+                }
+            },
+        );
+    }
+
+    /// Host races on the local network, joining them as a player too
+    fn host_race(&self, name: &str, port: u16) -> () {
+        let host: RaceHost = match RaceHost::start(port, &self.options.borrow()) {
+            Ok(host) => host,
+            Err(message) => {
+                show_error_toast(&self.toast_overlay, &message);
+                return;
+            }
+        };
+        match RaceClient::connect(&format!("127.0.0.1:{}", host.port()), name) {
+            Ok(client) => self.begin_race(Some(host), client),
+            Err(message) => show_error_toast(&self.toast_overlay, &message),
+        }
+    }
+
+    /// Join a race hosted on another machine, connecting in the background
+    fn join_race(self: &Rc<Self>, name: String, address: String) -> () {
+        self.toast_overlay.add_toast(Toast::new(&fill(
+            &gettext("Connecting to {}…"),
+            &[&address],
+        )));
+        let this: Weak<GameWindow> = Rc::downgrade(self);
+        glib::spawn_future_local(async move {
+            let result: Result<RaceClient, String> =
+                gio::spawn_blocking(move || RaceClient::connect(&address, &name))
+                    .await
+                    .unwrap_or_else(|_| Err(gettext("Failed to connect")));
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            match result {
+                Ok(client) => this.begin_race(None, client),
+                Err(message) => show_error_toast(&this.toast_overlay, &message),
+            }
+        });
+    }
+
+    /// Start following a race the player has joined. The game on the board carries on until
+    /// the first race starts.
+    fn begin_race(&self, host: Option<RaceHost>, client: RaceClient) -> () {
+        if self.leave_race() {
+            self.start_game();
+        }
+        *self.race.borrow_mut() = Some(RaceSession {
+            host,
+            client,
+            state: Default::default(),
+            playing: None,
+            finished: false,
+            connected: true,
+        });
+        self.race_bin.set_visible(true);
+        self.update_race_panel();
+        self.scale_to_fit();
+    }
+
+    /// Leave the race the player is in, if they're in one, returning whether a race's game is
+    /// on the board
+    fn leave_race(&self) -> bool {
+        let Some(race) = self.race.take() else {
+            return false;
+        };
+        // Dropping the session tells the host, and stops hosting if this is the host
+        drop(race.client);
+        drop(race.host);
+        self.race_bin.set_child(None::<&Widget>);
+        self.race_bin.set_visible(false);
+        self.scale_to_fit();
+        return race.playing.is_some();
+    }
+
+    /// Get the round of the race whose game is on the board, if there is one
+    fn race_round(&self) -> Option<u32> {
+        return self
+            .race
+            .borrow()
+            .as_ref()
+            .and_then(|race| race.playing.as_ref())
+            .map(|settings| settings.round);
+    }
+
+    /// Show how the race stands beside the board
+    fn update_race_panel(&self) -> () {
+        if let Some(race) = self.race.borrow().as_ref() {
+            self.race_bin.set_child(Some(&build_race_panel(race)));
+        }
+    }
+
+    /// Take in whatever has happened in the race since the last check
+    fn poll_race(self: &Rc<Self>) -> () {
+        let events: Vec<RaceEvent> = match self.race.borrow().as_ref() {
+            Some(race) => race.client.poll(),
+            None => return,
+        };
+        if events.is_empty() {
+            return;
+        }
+        for event in events {
+            self.race_event(event);
+        }
+        self.update_race_panel();
+    }
+
+    /// Handle one thing that happened in the race
+    fn race_event(self: &Rc<Self>, event: RaceEvent) -> () {
+        let mut race_val: RefMut<Option<RaceSession>> = self.race.borrow_mut();
+        let Some(race) = race_val.as_mut() else {
+            return;
+        };
+        match event {
+            RaceEvent::Message(message) => {
+                race.state.apply(&message);
+                let new_race: bool = matches!(&message, Message::Race(settings)
+                    if race.playing.as_ref() != Some(settings));
+                drop(race_val);
+                match message {
+                    Message::Race(settings) if new_race => self.play_race(settings),
+                    Message::Winner { .. } | Message::End => self.race_over(),
+                    _ => {}
+                }
+            }
+            RaceEvent::Disconnected => race.connected = false,
+            RaceEvent::Reconnected => {
+                // The host sends the whole race again
+                race.connected = true;
+                race.state = Default::default();
+            }
+            RaceEvent::Closed(reason) => {
+                drop(race_val);
+                show_error_toast(&self.toast_overlay, &reason);
+                if self.leave_race() {
+                    self.start_game();
+                }
+            }
+        }
+    }
+
+    /// Put a race that just started on the board
+    fn play_race(&self, settings: RaceSettings) -> () {
+        let options: GameOptions = settings.game_options(&self.options.borrow());
+        let lists: WordLists = match WordLists::load(&options) {
+            Ok(lists) if !lists.answers.is_empty() => lists,
+            Ok(_) => {
+                show_error_toast(
+                    &self.toast_overlay,
+                    &fill(
+                        &gettext("There are no {}-letter answers to race with"),
+                        &[&settings.word_length.to_string()],
+                    ),
+                );
+                return;
+            }
+            Err(error) => {
+                show_error_toast(&self.toast_overlay, &options_error_message(&error));
+                return;
+            }
+        };
+        // The seed would pick another word from a different list
+        if lists.fingerprint() != settings.answers {
+            show_error_toast(
+                &self.toast_overlay,
+                &gettext(
+                    "Your answer list isn't the same as the host's, so you can't play this race",
+                ),
+            );
+            return;
+        }
+        // A race starting during the tutorial ends it, without going back to the game before
+        if self.tutorial_step.take().is_some() {
+            self.close_callout();
+            self.interrupted_game.take();
+            AppSettings::load().set_tutorial_done(true);
+        }
+        let game: Game = Game::new(&options, &lists, settings.seed);
+        *self.lists.borrow_mut() = lists;
+        // The player's own word lists are loaded again once they leave the race
+        self.preferences_changed.set(true);
+        *self.speed_run.borrow_mut() = None;
+        self.survival.set(None);
+        self.clock.set(Clock::default());
+        self.archive.set(None);
+        let round: u32 = settings.round;
+        if let Some(race) = self.race.borrow_mut().as_mut() {
+            race.playing = Some(settings);
+            race.finished = false;
+        }
+        self.show_game(game);
+        self.window_title
+            .set_subtitle(&fill(&gettext("Race {}"), &[&round.to_string()]));
+        self.window.announce(
+            &gettext("The race is on!"),
+            AccessibleAnnouncementPriority::High,
+        );
+    }
+
+    /// End the race on the board once someone has won it, or everyone has run out of guesses
+    fn race_over(&self) -> () {
+        let mut race_val: RefMut<Option<RaceSession>> = self.race.borrow_mut();
+        let Some(race) = race_val.as_mut() else {
+            return;
+        };
+        if race.playing.is_none() || race.finished {
+            return;
+        }
+        race.finished = true;
+        let message: String = match race.state.winner {
+            Some((id, _)) if id == race.client.id() => gettext("You won the race!"),
+            Some((id, guesses)) => fill(
+                &ngettext(
+                    "{} won the race in {} try",
+                    "{} won the race in {} tries",
+                    guesses as u32,
+                ),
+                &[&race.player_name(id), &guesses.to_string()],
+            ),
+            None => gettext("Nobody solved it"),
+        };
+        drop(race_val);
+        let mut game_val: RefMut<Game> = self.game.borrow_mut();
+        // Someone else won while the player was still guessing
+        let stopped: bool = !game_val.locked;
+        game_val.time_out();
+        let answer: String = game_val.answer.clone();
+        drop(game_val);
+        if stopped {
+            self.revealing.set(false);
+            self.refresh();
+        }
+        let toast: Toast = Toast::new(&message);
+        toast.set_timeout(4);
+        self.toast_overlay.add_toast(toast);
+        if stopped {
+            self.toast_overlay.add_toast(Toast::new(&fill(
+                &gettext("The word was \"{}\""),
+                &[&answer],
+            )));
+        }
+        self.window
+            .announce(&message, AccessibleAnnouncementPriority::High);
+    }
+
+    /// Tell the player how their race went once their own board is finished. Who won is
+    /// announced once the host says so.
+    fn finish_race_game(&self) -> () {
+        let game_val: Ref<Game> = self.game.borrow();
+        if game_val.won || game_val.timed_out {
+            return;
+        }
+        self.toast_overlay.add_toast(Toast::new(&fill(
+            &gettext("Out of guesses! The word was \"{}\""),
+            &[&game_val.answer],
+        )));
+    }
+
+    /// Show the active profile in the header, with every profile in its menu
+    fn update_profiles(&self) -> () {
+        let profiles: Profiles = Profiles::load();
+        self.profile_content.set_label(&profiles.active().name);
+        self.profile_button
+            .set_menu_model(Some(&profile_menu(&profiles)));
+        self.profile_action.set_state(&profiles.active.to_variant());
+    }
+
+    /// Switch to another profile, putting this player's game aside and picking up theirs
+    fn switch_profile(&self, id: &str) -> () {
+        let mut profiles: Profiles = Profiles::load();
+        if profiles.active == id || profiles.get(id).is_none() {
+            return;
+        }
+        // The tutorial's game and race games don't belong to anyone, and archive games aren't
+        // put aside since they'd be picked up as today's puzzle
+        self.end_tutorial();
+        if !self.leave_race()
+            && self.archive.get().is_none()
+            && let Err(error) = save_game(&self.game.borrow(), Date::today())
+        {
+            eprintln!("Failed to save the game: {}", error);
+        }
+        profiles.active = id.to_string();
+        if let Err(error) = profiles.save() {
+            show_error_toast(
+                &self.toast_overlay,
+                &fill(
+                    &gettext("Failed to switch profile: {}"),
+                    &[&error.to_string()],
+                ),
+            );
+            return;
+        }
+        self.update_profiles();
+
+        *self.stats.borrow_mut() = Stats::load();
+        *self.unlocks.borrow_mut() = Unlocks::load();
+        self.new_achievements.take();
+        // Changing the app's actions applies the new profile's look everywhere
+        if let Some(app) = self.window.application() {
+            app.change_action_state("theme", &theme::load_theme().name().to_variant());
+            app.change_action_state("palette", &theme::load_palette().name().to_variant());
+            app.change_action_state("patterns", &theme::load_patterns().to_variant());
+        }
+        animation::set_allowed(AppSettings::load().animations());
+        self.apply_preferences();
+        self.preferences_changed.set(false);
+        match take_saved_game(Date::today()) {
+            Some(game) => self.resume_game(game),
+            None => self.start_game(),
+        }
+    }
+
+    /// Put a game that was set aside back on the board
+    fn resume_game(&self, game: Game) -> () {
+        self.options.borrow_mut().mode = game.mode;
+        self.archive.set(None);
+        self.mode_action.set_state(&game.mode.name().to_variant());
+        *self.speed_run.borrow_mut() = None;
+        self.survival.set(None);
+        self.clock.set(Clock::default());
+        self.show_game(game);
+    }
+
+    /// Ask where to save the stats and history, then export them there
+    fn export_data(&self) -> () {
+        let toast_overlay: ToastOverlay = self.toast_overlay.clone();
+        FileDialog::builder()
+            .title(gettext("Export Data"))
+            .initial_name(format!("rustle-{}.json", Date::today()))
+            .filters(&data_file_filters())
+            .build()
+            .save(
+                Some(&self.window),
+                None::<&Cancellable>,
+                move |result: Result<File, glib::Error>| {
+                    let Some(path) = result.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+                    match export_file(&path) {
+                        Ok(()) => toast_overlay.add_toast(Toast::new(&gettext("Data exported"))),
+                        Err(message) => show_error_toast(&toast_overlay, &message),
+                    }
+                },
+            );
+    }
+
+    /// Ask for a file exported on another machine, then merge its stats and history in
+    fn import_data(self: &Rc<Self>) -> () {
+        let this: Weak<GameWindow> = Rc::downgrade(self);
+        FileDialog::builder()
+            .title(gettext("Import Data"))
+            .filters(&data_file_filters())
+            .build()
+            .open(
+                Some(&self.window),
+                None::<&Cancellable>,
+                move |result: Result<File, glib::Error>| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let Some(path) = result.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+                    match import_file(&path) {
+                        Ok(added) => {
+                            *this.stats.borrow_mut() = Stats::load();
+                            this.check_achievements();
+                            let message: String = ngettext(
+                                "Imported {} new game",
+                                "Imported {} new games",
+                                added as u32,
+                            );
+                            this.toast_overlay
+                                .add_toast(Toast::new(&fill(&message, &[&added.to_string()])));
+                            announce_achievements(
+                                &this.toast_overlay,
+                                &this.new_achievements.take(),
+                            );
+                        }
+                        Err(message) => show_error_toast(&this.toast_overlay, &message),
+                    }
+                },
+            );
+    }
+
+    /// Show the saved statistics
+    fn show_statistics(&self) -> () {
+        let summary: Label = Label::new(Some(&statistics_summary(&self.stats.borrow())));
+        summary.add_css_class("monospace");
+        summary.set_xalign(0.0);
+        let dialog: libadwaita::AlertDialog =
+            libadwaita::AlertDialog::new(Some(&gettext("Statistics")), None);
+        dialog.set_extra_child(Some(&summary));
+        dialog.add_response("close", &gettext("Close"));
+        dialog.present(Some(&self.window));
+    }
+
+    /// Ask for a seed and start a practice game with it
+    fn show_seed_dialog(self: &Rc<Self>) -> () {
+        let entry: Entry = Entry::builder()
+            .placeholder_text(gettext("Seed"))
+            .input_purpose(InputPurpose::Digits)
+            .activates_default(true)
+            .build();
+        let dialog: libadwaita::AlertDialog = libadwaita::AlertDialog::new(
+            Some(&gettext("Play a Seed")),
+            Some(&gettext("Enter the seed of a game to play it again")),
+        );
+        dialog.add_responses(&[("cancel", &gettext("Cancel")), ("play", &gettext("Play"))]);
+        dialog.set_response_appearance("play", ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("play"));
+        dialog.set_close_response("cancel");
+        dialog.set_extra_child(Some(&entry));
+
+        let this: Weak<GameWindow> = Rc::downgrade(self);
+        dialog.connect_response(None, move |_, response: &str| {
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            if response != "play" {
+                return;
+            }
+            match entry.text().trim().parse::<u64>() {
+                Ok(seed) => {
+                    let mut options_val: RefMut<GameOptions> = this.options.borrow_mut();
+                    options_val.mode = GameMode::Practice;
+                    options_val.seed = Some(seed);
+                    drop(options_val);
+                    this.start_game();
+                }
+                Err(_) => show_error_toast(&this.toast_overlay, &gettext("Invalid seed!")),
+            }
+        });
+        dialog.present(Some(&self.window));
+    }
+
+    /// Play through a scripted game, explaining the colors as it goes
+    fn start_tutorial(self: &Rc<Self>) -> () {
+        if self.tutorial_step.get().is_some() {
+            return;
+        }
+        let options: GameOptions = GameOptions {
+            mode: GameMode::Practice,
+            word_length: 5,
+            max_guesses: 6,
+            hard_mode: false,
+            seed: None,
+            ..self.options.borrow().clone()
+        };
+        let game: Game = Game::with_answer(&options, tutorial::ANSWER.to_string(), 0);
+        let old_game: Game = self.game.borrow().clone();
+        *self.interrupted_game.borrow_mut() = Some(old_game);
+        self.show_game(game);
+        self.window_title.set_subtitle(&gettext("Tutorial"));
+        self.tutorial_step.set(Some(0));
+        self.play_tutorial_step();
+    }
+
+    /// Play the guess of the current tutorial step, if it has one, then explain it
+    fn play_tutorial_step(self: &Rc<Self>) -> () {
+        let Some(index) = self.tutorial_step.get() else {
+            return;
+        };
+        let Some(guess) = tutorial::STEPS[index].guess else {
+            self.show_callout();
+            return;
+        };
+        let mut game_val: RefMut<Game> = self.game.borrow_mut();
+        game_val.enter_word(guess);
+        game_val.submit(&[guess.to_string()]);
+        drop(game_val);
+        self.reveal_guess();
+    }
+
+    /// Show the callout of the current tutorial step
+    fn show_callout(self: &Rc<Self>) -> () {
+        let Some(index) = self.tutorial_step.get() else {
+            return;
+        };
+        let step: &Step = &tutorial::STEPS[index];
+        let target: Widget = match step.target {
+            Target::Board => self.grid_box.clone().upcast(),
+            Target::Tile(row, col) => tile_at(&self.grid.borrow(), col, row).upcast(),
+            Target::Key(c) => {
+                match key_label(&self.keyboard_rows.borrow(), self.keyboard_layout.get(), c) {
+                    Some(key) => key.upcast(),
+                    None => self.keyboard_box.clone().upcast(),
+                }
+            }
+        };
+
+        let this: Weak<GameWindow> = Rc::downgrade(self);
+        let this_2: Weak<GameWindow> = Rc::downgrade(self);
+        let popover: Popover = tutorial::build_callout(
+            &target,
+            index,
+            move || {
+                if let Some(this) = this.upgrade() {
+                    this.next_tutorial_step();
+                }
+            },
+            move || {
+                if let Some(this) = this_2.upgrade() {
+                    this.end_tutorial();
+                }
+            },
+        );
+
+        // Escape closes the callout on its own, which leaves the tutorial
+        let this: Weak<GameWindow> = Rc::downgrade(self);
+        popover.connect_closed(move |popover: &Popover| {
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            if this.callout.borrow().as_ref() != Some(popover) {
+                return;
+            }
+            // The callout can't be removed while it's still closing
+            let this: Weak<GameWindow> = Rc::downgrade(&this);
+            glib::idle_add_local_once(move || {
+                if let Some(this) = this.upgrade() {
+                    this.end_tutorial();
+                }
+            });
+        });
+        *self.callout.borrow_mut() = Some(popover.clone());
+        popover.popup();
+    }
+
+    /// Remove the callout of the tutorial step being shown
+    fn close_callout(&self) -> () {
+        let callout: Option<Popover> = self.callout.take();
+        if let Some(popover) = callout {
+            popover.popdown();
+            popover.unparent();
+        }
+    }
+
+    /// Go on to the next step of the tutorial, or finish it after the last one
+    fn next_tutorial_step(self: &Rc<Self>) -> () {
+        let Some(index) = self.tutorial_step.get() else {
+            return;
+        };
+        self.close_callout();
+        if index + 1 >= tutorial::STEPS.len() {
+            self.end_tutorial();
+            return;
+        }
+        self.tutorial_step.set(Some(index + 1));
+        self.play_tutorial_step();
+    }
+
+    /// Leave the tutorial, going back to the game it interrupted or starting a new one
+    fn end_tutorial(&self) -> () {
+        if self.tutorial_step.take().is_none() {
+            return;
+        }
+        self.close_callout();
+        AppSettings::load().set_tutorial_done(true);
+        self.revealing.set(false);
+        let interrupted_game: Option<Game> = self.interrupted_game.take();
+        match interrupted_game {
+            Some(game) if game.guesses_used() > 0 && !game.locked => self.show_game(game),
+            _ => self.start_game(),
+        }
+    }
+}